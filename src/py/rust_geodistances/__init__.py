@@ -10,6 +10,20 @@ This project includes a Rust binary backend:
 
 - :mod:`~rust_geodistances.lib_rust_geodistances` which can be loaded as
   :attr:`~rust_geodistances.bin`.
+
+Utility modules of the backend are also exposed at the top level:
+
+- :attr:`~rust_geodistances.cells`
+- :attr:`~rust_geodistances.coordinates`
+- :attr:`~rust_geodistances.facilities`
+- :attr:`~rust_geodistances.formats`
+- :attr:`~rust_geodistances.geofence`
+- :attr:`~rust_geodistances.geohash`
+- :attr:`~rust_geodistances.graph`
+- :attr:`~rust_geodistances.matching`
+- :attr:`~rust_geodistances.tiles`
+- :attr:`~rust_geodistances.trajectory`
+- :attr:`~rust_geodistances.transforms`
 """
 
 from . import decorators, lib_rust_geodistances
@@ -35,3 +49,78 @@ Enum instance containing Vincenty calculations methods.
 .. seealso::
   See :class:`CalculationMethod` for all inherited methods.
 """
+
+cells = bin.cells
+"""
+Submodule containing hierarchical cell IDs, cell boundaries and coverings.
+"""
+
+coordinates = bin.coordinates
+"""
+Submodule containing parsing and formatting of human readable coordinates.
+"""
+
+parse_coordinates = coordinates.parse_coordinates
+"""
+Alias for :func:`~rust_geodistances.coordinates.parse_coordinates`.
+"""
+
+format_coordinates = coordinates.format_coordinates
+"""
+Alias for :func:`~rust_geodistances.coordinates.format_coordinates`.
+"""
+
+facilities = bin.facilities
+"""
+Submodule containing :class:`~rust_geodistances.facilities.FacilityIndex`, a
+long-lived index of facilities answering nearest-facility queries in batches.
+"""
+
+formats = bin.formats
+"""
+Submodule containing readers and writers of geospatial file formats, e.g.
+:mod:`~rust_geodistances.formats.geojson`, as well as batch processing of
+``pyarrow`` tables and Parquet files in :mod:`~rust_geodistances.formats.arrow`.
+"""
+
+geofence = bin.geofence
+"""
+Submodule containing :class:`~rust_geodistances.geofence.Geofence`, a set of
+circular and polygonal fences emitting enter, exit and dwell events for tracks.
+"""
+
+geohash = bin.geohash
+"""
+Submodule containing geohash encoding, decoding and cell covering functions.
+"""
+
+graph = bin.graph
+"""
+Submodule containing shortest paths and distance matrices over networks of
+coordinates, with geodesic edge weights.
+"""
+
+matching = bin.matching
+"""
+Submodule containing hidden Markov map matching of noisy tracks onto a road
+network.
+"""
+
+tiles = bin.tiles
+"""
+Submodule containing slippy map tile indices, pixel offsets, bounds and coverings.
+"""
+
+trajectory = bin.trajectory
+"""
+Submodule containing length, heading, speed and acceleration series of ordered
+tracks, stay-point detection, simplification, resampling, and distances between
+whole tracks.
+"""
+
+transforms = bin.transforms
+"""
+Submodule containing conversions between geodetic, ECEF and local ENU
+coordinates, straight line chord and slant distances, and the UTM and Web Mercator
+projections.
+"""
@@ -16,6 +16,7 @@ use super::traits::{
     LatLng,
     LatLngArray,
     CalculateDistance,
+    CalculateInverse,
     OffsetByVector,
 };
 
@@ -207,3 +208,31 @@ impl<__impl_generics__> OffsetByVector<__vector_type__> for Haversine {
         return e_latlng_r;
     }
 }
+
+impl CalculateInverse for Haversine {
+    fn inverse_rad(
+        s_lat_r:f64,
+        s_lng_r:f64,
+        e_lat_r:f64,
+        e_lng_r:f64,
+        settings: Option<&config::CalculationSettings>,
+    ) -> (f64, f64) {
+        let radius: f64 = settings.unwrap_or(
+            &config::CalculationSettings::default()
+        ).spherical_radius;
+
+        let diff_lng_r = e_lng_r - s_lng_r;
+
+        let ang_dist = {
+            ((e_lat_r - s_lat_r)/2.).sin().powi(2)
+            + s_lat_r.cos()*e_lat_r.cos()
+            * (diff_lng_r/2.).sin().powi(2)
+        }.sqrt().min(1.).asin() * 2.;
+
+        let bearing_r = (diff_lng_r.sin()*e_lat_r.cos()).atan2(
+            s_lat_r.cos()*e_lat_r.sin() - s_lat_r.sin()*e_lat_r.cos()*diff_lng_r.cos()
+        );
+
+        return (ang_dist * radius, (bearing_r * 180. / PI + 360.) % 360.);
+    }
+}
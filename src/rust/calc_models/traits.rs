@@ -3,10 +3,13 @@
 // These traits describes what a Calculation Method (e.g. Haversine)
 // can do.
 
+use std::cmp;
 use std::ops::Index;
 
 use duplicate::duplicate_item;
 
+use rayon::prelude::*;
+
 use ndarray::{
     Dim,
     Ix,
@@ -252,3 +255,84 @@ pub trait OffsetByVector<T>:CalculateDistance {
         settings: Option<&config::CalculationSettings>,
     ) -> F64LatLngArray;
 }
+
+/// Trait for structs that are able to solve the inverse geodesic problem, i.e.
+/// the distance and initial bearing from one point to another.
+///
+/// Unlike :class:`CalculateDistance` which maps every point of ``s`` to every
+/// point of ``e``, this operates on pairs of points only; hence it is suitable for
+/// row-by-row calculations over very long arrays.
+pub trait CalculateInverse {
+    /// Distance and initial bearing from one pair of radian coordinates to another.
+    ///
+    /// .. note::
+    ///     Internal Function; exposed within Rust, but not intended for use with
+    ///     Python interface directly.
+    ///
+    /// Returns
+    /// -------
+    /// (f64, f64)
+    ///     The great-circle distance in the same unit as ``settings``, and the
+    ///     initial bearing in degrees between 0º and 360º, with 0º being due
+    ///     North. The bearing of coincident points is 0º.
+    fn inverse_rad(
+        s_lat_r:f64,
+        s_lng_r:f64,
+        e_lat_r:f64,
+        e_lng_r:f64,
+        settings: Option<&config::CalculationSettings>,
+    ) -> (f64, f64);
+
+    /// Distances and initial bearings from each point of ``s`` to the point of
+    /// ``e`` on the same row.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: Array2<f64>| ArcArray2<f64> | ArrayView2<'a, f64>
+    ///     Dimension `(n, 2)`, in degrees.
+    ///
+    /// e: Array2<f64>| ArcArray2<f64> | ArrayView2<'a, f64>
+    ///     Dimension `(n, 2)`, in degrees.
+    ///
+    /// settings: Option<&config::CalculationSettings>
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// (Array1<f64>, Array1<f64>)
+    ///     Dimension `(n)` each; distances and bearings in degrees.
+    fn inverse_pairwise(
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        settings: Option<&config::CalculationSettings>,
+    ) -> (F64Array1, F64Array1) {
+        assert!(
+            s.shape() == e.shape(),
+            "`s` and `e` must be of the same shape, yet {:?} and {:?} provided.", s.shape(), e.shape()
+        );
+
+        let (s_latlng_r, e_latlng_r) = (s.to_rad(), e.to_rad());
+        let n = s_latlng_r.shape()[0];
+
+        let workers: usize = settings.unwrap_or(
+            &config::CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+        let (distances, bearings): (Vec<f64>, Vec<f64>) = {
+            (0..n)
+            .into_par_iter()
+            .with_min_len(chunk_size)
+            .map(
+                | i | Self::inverse_rad(
+                    s_latlng_r[[i, 0]], s_latlng_r[[i, 1]],
+                    e_latlng_r[[i, 0]], e_latlng_r[[i, 1]],
+                    settings,
+                )
+            )
+            .unzip()
+        };
+
+        return (F64Array1::from_vec(distances), F64Array1::from_vec(bearings));
+    }
+}
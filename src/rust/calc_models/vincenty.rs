@@ -18,8 +18,6 @@ use ndarray_numeric::{
     ArrayWithF64AngularMethods,
     ArrayWithF64LatLngMethods,
 
-    BoolArray1,
-
     F64Array,
     F64Array1,
     F64Array2,
@@ -40,9 +38,145 @@ use super::traits::{
     LatLng,
     LatLngArray,
     CalculateDistance,
+    CalculateInverse,
     OffsetByVector
 };
 
+/// Iterative solution of the inverse problem between one pair of radian
+/// coordinates, shared by :meth:`CalculateDistance::distance_from_point_rad` and
+/// :meth:`CalculateInverse::inverse_rad`.
+///
+/// Returns the distance on an ellipsoid scaled to a semi-minor axis of ``1``, i.e.
+/// to be multiplied by ``ellipse_b``, and the initial bearing in radians.
+/// Coincident points are ``(0., 0.)``.
+#[allow(non_snake_case)]
+fn inverse_iterate(
+    s_lat_r:f64,
+    s_lng_r:f64,
+    e_lat_r:f64,
+    e_lng_r:f64,
+    settings:&config::CalculationSettings,
+) -> (f64, f64) {
+    let eps:f64 = settings.eps;
+    let tolerance:f64 = settings.tolerance;
+    let ellipse_a:f64 = settings.ellipse_a;
+    let ellipse_b:f64 = settings.ellipse_b;
+    let ellipse_f:f64 = settings.ellipse_f;
+
+    assert!(tolerance>0., "`tolerance` must be positive, yet {:?} provided.", tolerance);
+
+    let diff_lng_r = e_lng_r - s_lng_r;
+
+    let tan_reduced_s_lat_r = (1.-ellipse_f) * s_lat_r.tan();
+    let cos_reduced_s_lat_r = (tan_reduced_s_lat_r.powi(2) + 1.).sqrt().powi(-1);
+    let sin_reduced_s_lat_r = tan_reduced_s_lat_r * cos_reduced_s_lat_r;
+
+    let tan_reduced_e_lat_r = (1.-ellipse_f) * e_lat_r.tan();
+    let cos_reduced_e_lat_r = (tan_reduced_e_lat_r.powi(2) + 1.).sqrt().powi(-1);
+    let sin_reduced_e_lat_r = tan_reduced_e_lat_r * cos_reduced_e_lat_r;
+
+    let antipodal = diff_lng_r.abs() > PI/2. || (e_lat_r - s_lat_r).abs() > PI/2.;
+
+    let mut lambda = diff_lng_r;
+    let mut lambda_dash = lambda;
+    let (mut sin_lng_r, mut cos_lng_r) = (lambda.sin(), lambda.cos());
+
+    let mut ang_dist = if antipodal { PI } else { 0. };
+    let mut sin_ang_dist = 0.;
+    let mut cos_ang_dist = if antipodal { -1. } else { 1. };
+    let mut cos_2_ang_dist_from_equator_bisect = 0.;
+    let mut cos_sq_azimuth_of_geodesic_at_equator = 0.;
+
+    for i in 0..settings.max_iterations {
+        sin_lng_r = lambda.sin();
+        cos_lng_r = lambda.cos();
+
+        let sin_sq_ang_dist = {
+            (cos_reduced_e_lat_r*sin_lng_r).powi(2)
+            + (
+                cos_reduced_s_lat_r*sin_reduced_e_lat_r
+                - sin_reduced_s_lat_r*cos_reduced_e_lat_r*cos_lng_r
+            ).powi(2)
+        };
+
+        // Coincident points. This is a squared sine, so comparing it with `eps`
+        // would also zero out points up to about 10 cm apart.
+        if sin_sq_ang_dist == 0. { return (0., 0.) }
+
+        // Stop once lambda has converged; the first iteration always runs, even if
+        // the points share a meridian.
+        if i > 0 && (lambda_dash-lambda).abs() <= tolerance { break }
+
+        sin_ang_dist = sin_sq_ang_dist.sqrt();
+        cos_ang_dist = {
+            sin_reduced_e_lat_r*sin_reduced_s_lat_r
+            + cos_reduced_e_lat_r*cos_lng_r*cos_reduced_s_lat_r
+        };
+        ang_dist = sin_ang_dist.atan2(cos_ang_dist);
+
+        let sin_azimuth_of_geodesic_at_equator = {
+            cos_reduced_e_lat_r*sin_lng_r*cos_reduced_s_lat_r/sin_ang_dist
+        };
+        cos_sq_azimuth_of_geodesic_at_equator = 1. - sin_azimuth_of_geodesic_at_equator.powi(2);
+
+        cos_2_ang_dist_from_equator_bisect = {
+            if cos_sq_azimuth_of_geodesic_at_equator.abs() > eps {
+                cos_ang_dist - 2.*sin_reduced_s_lat_r*sin_reduced_e_lat_r/cos_sq_azimuth_of_geodesic_at_equator
+            } else {
+                0.
+            }
+        };
+
+        let _c = {
+            ellipse_f / 16.
+            * cos_sq_azimuth_of_geodesic_at_equator
+            * (4.+ellipse_f*(4.-3.*cos_sq_azimuth_of_geodesic_at_equator))
+        };
+
+        lambda_dash = lambda;
+        lambda = {
+            diff_lng_r + (1.-_c) * ellipse_f
+            * sin_azimuth_of_geodesic_at_equator
+            * (ang_dist + _c*sin_ang_dist*(
+                cos_2_ang_dist_from_equator_bisect
+                +_c*cos_ang_dist*(
+                    -1.
+                    +2.*cos_2_ang_dist_from_equator_bisect.powi(2)
+                )
+            ))
+        };
+    }
+
+    let uSq = cos_sq_azimuth_of_geodesic_at_equator * (
+        ellipse_a.powi(2) - ellipse_b.powi(2)
+    ) / ellipse_b.powi(2);
+
+    let _a = 1.+uSq/16384.*(4096.+uSq*(-768.+uSq*(320.-175.*uSq)));
+    let _b = uSq/1024. * (256.+uSq*(-128.+uSq*(74.-47.*uSq)));
+
+    let delta_ang_dist = {
+        _b*sin_ang_dist*(
+            cos_2_ang_dist_from_equator_bisect
+            + _b/4.*(
+                cos_ang_dist*(
+                    -1.+2.*cos_2_ang_dist_from_equator_bisect.powi(2)
+                )
+                - _b/6.*cos_2_ang_dist_from_equator_bisect*(
+                    -3.+4.*sin_ang_dist.powi(2)
+                )*(
+                    -3.+4.*cos_2_ang_dist_from_equator_bisect.powi(2)
+                )
+            )
+        )
+    };
+
+    let bearing_r = (cos_reduced_e_lat_r*sin_lng_r).atan2(
+        cos_reduced_s_lat_r*sin_reduced_e_lat_r - sin_reduced_s_lat_r*cos_reduced_e_lat_r*cos_lng_r
+    );
+
+    return ((ang_dist-delta_ang_dist)*_a, bearing_r);
+}
+
 ///  Vincenty solutions of geodescis on the ellipsoid
 ///  Adapted from https://www.movable-type.co.uk/scripts/latlong-vincenty.html
 ///
@@ -53,7 +187,6 @@ use super::traits::{
 pub struct Vincenty;
 impl CalculateDistance for Vincenty {
     /// Internal function
-    #[allow(non_snake_case)]
     fn distance_from_point_rad(
         s_lat_r:&f64,
         s_lng_r:&f64,
@@ -62,172 +195,13 @@ impl CalculateDistance for Vincenty {
         settings: Option<&config::CalculationSettings>,
     ) -> F64Array1 {
         let settings_default = &config::CalculationSettings::default();
+        let settings = settings.unwrap_or(settings_default);
 
-        let eps:f64 = settings.unwrap_or(&settings_default).eps;
-        let tolerance:f64 = settings.unwrap_or(&settings_default).tolerance;
-        let ellipse_a:f64 = settings.unwrap_or(&settings_default).ellipse_a;
-        let ellipse_b:f64 = settings.unwrap_or(&settings_default).ellipse_b;
-        let ellipse_f:f64 = settings.unwrap_or(&settings_default).ellipse_f;
-        let max_iterations:usize = settings.unwrap_or(&settings_default).max_iterations;
-
-        assert!(tolerance>0., "`tolerance` must be positive, yet {:?} provided.", tolerance);
-
-        let diff_lng_r = e_lng_r - *s_lng_r;
-        let tan_reduced_s_lat_r = (1.-ellipse_f) * s_lat_r.tan();
-        let cos_reduced_s_lat_r = (tan_reduced_s_lat_r.powi(2) + 1.).sqrt().powi(-1);
-        let sin_reduced_s_lat_r = tan_reduced_s_lat_r * cos_reduced_s_lat_r;
-
-        let tan_reduced_e_lat_r = (1.-ellipse_f) * e_lat_r.tan();
-        let cos_reduced_e_lat_r = (tan_reduced_e_lat_r.powi(2) + 1.).sqrt().powi(-1);
-        let sin_reduced_e_lat_r = tan_reduced_e_lat_r * &cos_reduced_e_lat_r;
-
-        let shape = (diff_lng_r.len(), );
-        let mut lambda:F64Array1 = diff_lng_r.clone();
-        let mut lambda_dash:F64Array1 = F64Array1::zeros(shape);
-
-        let mut sin_lng_r:F64Array1 = F64Array1::zeros(shape);
-        let mut cos_lng_r:F64Array1 = F64Array1::zeros(shape);
-
-        // Does ndarray does not implement ||
-        // but BitOr on bool is the same as || so | is correct here.
-        let antipodal:BoolArray1 = (diff_lng_r.gt(&(PI/2.))) | ((e_lat_r - *s_lat_r).abs().gt(&(PI/2.)));
-
-        let mut ang_dist:F64Array1 = antipodal.map(|b| if *b {PI} else {0.});
-        let mut sin_ang_dist:F64Array1 = F64Array1::zeros(shape);
-        let mut cos_ang_dist:F64Array1 = antipodal.map(|b| if *b {-1.} else {1.});
-
-        let mut sin_azimuth_of_geodesic_at_equator:F64Array1 = F64Array1::zeros(shape);
-        let mut cos_2_ang_dist_from_equator_bisect:F64Array1 = F64Array1::zeros(shape);
-        let mut cos_sq_azimuth_of_geodesic_at_equator:F64Array1 = F64Array1::zeros(shape);
-
-        // Dropping a useless reference.
-        // Just to get around the compiler "value never used" check.
-        drop(&sin_lng_r);
-        drop(&cos_lng_r);
-        drop(&sin_azimuth_of_geodesic_at_equator);
-
-        // TODO vectorize this check
-        // e_lat, e_lng does not exist yet
-        // if (-e_lat+s_lat).abs() <= eps && (-e_lng+s_lng).abs() <= eps {
-        //     return Some(0.)
-        // }
-
-        for _i in 0..max_iterations {
-            sin_lng_r = lambda.sin();
-            cos_lng_r = lambda.cos();
-
-            let sin_sq_ang_dist = {
-                (&cos_reduced_e_lat_r*&sin_lng_r).powi(2)
-                + (
-                    cos_reduced_s_lat_r*&sin_reduced_e_lat_r
-                    - sin_reduced_s_lat_r*&cos_reduced_e_lat_r*&cos_lng_r
-                ).powi(2)
-            };
-
-            // let next_iteration_mask = sin_sq_ang_dist.abs().gt(&tolerance);
-            let next_iteration_mask = (&lambda_dash-&lambda).abs().gt(&tolerance);
-
-            // If nothing needs to be iterated any more, stop the for loop
-            // println!("{}, {}, {}", _i, sin_sq_ang_dist.abs(), next_iteration_mask.any());
-            if !next_iteration_mask.any() { break }
-
-            // Mapping block
-            {
-                lambda.indexed_iter_mut()
-                       .map(
-                        |(idx, _lambda)| {
-                            // Skip iteration if we are already within tolerance
-                            if next_iteration_mask[idx] {
-                                // Get all variables local to idx
-                                let _sin_lng_r = sin_lng_r[idx];
-                                let _cos_lng_r = cos_lng_r[idx];
-
-                                let _sin_sq_ang_dist = sin_sq_ang_dist[idx];
-
-                                let _sin_reduced_e_lat_r = sin_reduced_e_lat_r[idx];
-                                let _cos_reduced_e_lat_r = cos_reduced_e_lat_r[idx];
-
-                                let mut _sin_ang_dist = sin_ang_dist.get_mut(idx).unwrap();
-                                let mut _cos_ang_dist = cos_ang_dist.get_mut(idx).unwrap();
-                                *_sin_ang_dist = _sin_sq_ang_dist.sqrt();
-                                *_cos_ang_dist = {
-                                    _sin_reduced_e_lat_r*sin_reduced_s_lat_r
-                                    + _cos_reduced_e_lat_r*_cos_lng_r*cos_reduced_s_lat_r
-                                };
-
-                                let mut _ang_dist = ang_dist.get_mut(idx).unwrap();
-                                *_ang_dist = _sin_ang_dist.atan2(*_cos_ang_dist);
-
-                                let mut _sin_azimuth_of_geodesic_at_equator = sin_azimuth_of_geodesic_at_equator.get_mut(idx).unwrap();
-                                *_sin_azimuth_of_geodesic_at_equator = {
-                                    _cos_reduced_e_lat_r*_sin_lng_r*cos_reduced_s_lat_r/(*_sin_ang_dist)
-                                };
-
-                                let mut _cos_sq_azimuth_of_geodesic_at_equator = cos_sq_azimuth_of_geodesic_at_equator.get_mut(idx).unwrap();
-                                *_cos_sq_azimuth_of_geodesic_at_equator = {
-                                    1. - _sin_azimuth_of_geodesic_at_equator.powi(2)
-                                };
-
-                                let mut _cos_2_ang_dist_from_equator_bisect = cos_2_ang_dist_from_equator_bisect.get_mut(idx).unwrap();
-                                *_cos_2_ang_dist_from_equator_bisect = {
-                                    if _cos_sq_azimuth_of_geodesic_at_equator.abs() > eps {
-                                        *_cos_ang_dist - 2.*sin_reduced_s_lat_r*_sin_reduced_e_lat_r/((*_cos_sq_azimuth_of_geodesic_at_equator))
-                                    } else {
-                                        0.
-                                    }
-                                };
-
-                                let _c = {
-                                    ellipse_f / 16.
-                                    * (*_cos_sq_azimuth_of_geodesic_at_equator)
-                                    * (4.+ellipse_f*(4.-3.*(*_cos_sq_azimuth_of_geodesic_at_equator)))
-                                };
-
-                                let mut _lambda_dash = lambda_dash.get_mut(idx).unwrap();
-                                *_lambda_dash = _lambda.clone();
-
-                                *_lambda = {
-                                    diff_lng_r[idx] + (1.-_c) * ellipse_f
-                                    * (*_sin_azimuth_of_geodesic_at_equator)
-                                    * (*_ang_dist + _c* (*_sin_ang_dist)*(
-                                        *_cos_2_ang_dist_from_equator_bisect
-                                        +_c*(*_cos_ang_dist)*(
-                                            -1.
-                                            +2.*(*_cos_2_ang_dist_from_equator_bisect).powi(2)
-                                        )
-                                    ))
-                                };
-                            }
-                        }
-                       )
-                       .count();
-            }
-        }
-
-        let uSq = cos_sq_azimuth_of_geodesic_at_equator * (
-            ellipse_a.powi(2) - ellipse_b.powi(2)
-        ) / ellipse_b.powi(2);
-
-        let _a = 1.+&uSq/16384.*(4096.+&uSq*(-768.+&uSq*(320.-175.*&uSq)));
-        let _b = &uSq/1024. * (256.+&uSq*(-128.+&uSq*(74.-47.*&uSq)));
-
-        let delta_ang_dist = {
-            &_b*&sin_ang_dist*(
-                &cos_2_ang_dist_from_equator_bisect
-                + &_b/4.*(
-                    &cos_ang_dist*(
-                        -1.+2.*&cos_2_ang_dist_from_equator_bisect.powi(2)
-                    )
-                    - &_b/6.*&cos_2_ang_dist_from_equator_bisect*(
-                        -3.+4.*sin_ang_dist.powi(2)
-                    )*(
-                        -3.+4.*cos_2_ang_dist_from_equator_bisect.powi(2)
-                    )
-                )
-            )
-        };
-
-        return (ang_dist-delta_ang_dist)*_a
+        return Zip::from(e_lat_r)
+                   .and(e_lng_r)
+                   .map_collect(
+                       | e_lat_r, e_lng_r | inverse_iterate(*s_lat_r, *s_lng_r, *e_lat_r, *e_lng_r, settings).0
+                   );
     }
 
     fn distance_rad(
@@ -513,3 +487,27 @@ impl OffsetByVector<f64> for Vincenty {
         );
     }
 }
+
+/// Scalar implementation of the inverse problem.
+///
+/// Same iteration as :meth:`distance_from_point_rad`, but additionally returns the
+/// initial bearing, which is the forward azimuth of the geodesic at ``s``.
+impl CalculateInverse for Vincenty {
+    fn inverse_rad(
+        s_lat_r:f64,
+        s_lng_r:f64,
+        e_lat_r:f64,
+        e_lng_r:f64,
+        settings: Option<&config::CalculationSettings>,
+    ) -> (f64, f64) {
+        let settings_default = &config::CalculationSettings::default();
+        let settings = settings.unwrap_or(settings_default);
+
+        let (ang_dist, bearing_r) = inverse_iterate(s_lat_r, s_lng_r, e_lat_r, e_lng_r, settings);
+
+        return (
+            ang_dist * settings.ellipse_b,
+            (bearing_r * 180. / PI + 360.) % 360.,
+        );
+    }
+}
@@ -0,0 +1,678 @@
+/// Hierarchical cell indexing of the sphere.
+///
+/// This is a discrete global grid in the style of S2: the sphere is projected onto
+/// the 6 faces of a cube, and each face is recursively divided into quadrants up
+/// to :const:`MAX_LEVEL`. A quadratic transformation is applied between the cube
+/// face and the grid, which keeps cells of the same level within a factor of ~2 in
+/// area of each other anywhere on the sphere.
+///
+/// Each cell is identified by a 64-bit :class:`CellId`:
+///
+/// - 3 bits for the face,
+/// - 2 bits per level for the position of the cell along a Hilbert curve on the
+///   face, then
+/// - a single ``1`` bit marking the level, followed by ``0``s.
+///
+/// The Hilbert curve keeps nearby cells close together in ID order, and all
+/// descendants of a cell have IDs within a contiguous range around the cell's own
+/// ID. This allows points sorted by their cell IDs to be looked up by range.
+
+use std::cmp;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use ndarray::{
+    Array1,
+    Axis,
+};
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+    LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+use crate::spatial::kdtree::{
+    UnitVector,
+    to_unit_vector,
+};
+
+// Import this if you want the cells module to be available in Python.
+pub mod python;
+
+/// Deepest level of cells; leaf cells are roughly 1cm across.
+pub const MAX_LEVEL:u8 = 30;
+
+/// Number of bits used for the position of the cell on a face.
+const POS_BITS:u32 = 2 * MAX_LEVEL as u32 + 1;
+
+/// Average angular width of a level 0 cell in radians, for the quadratic projection.
+///
+/// The width of a level ``n`` cell is roughly this divided by ``2^n``.
+pub const AVG_EDGE_DERIV:f64 = 1.459213746386106062;
+
+/// Maximum number of cells used to cover each point in
+/// :func:`indices_within_distance`.
+pub const INDEX_COVERING_MAX_CELLS:usize = 8;
+
+// ================================================================================
+//  Projections
+// ================================================================================
+
+/// Quadratic transformation from grid coordinate ``s`` to face coordinate ``u``.
+fn st_to_uv(s:f64) -> f64 {
+    return if s >= 0.5 {
+        (1. / 3.) * (4. * s * s - 1.)
+    } else {
+        (1. / 3.) * (1. - 4. * (1. - s) * (1. - s))
+    };
+}
+
+/// Quadratic transformation from face coordinate ``u`` to grid coordinate ``s``.
+fn uv_to_st(u:f64) -> f64 {
+    return if u >= 0. {
+        0.5 * (1. + 3. * u).sqrt()
+    } else {
+        1. - 0.5 * (1. - 3. * u).sqrt()
+    };
+}
+
+/// Project a unit vector onto the cube; returns ``(face, u, v)``.
+fn xyz_to_face_uv(p:&UnitVector) -> (u8, f64, f64) {
+    let abs = [p[0].abs(), p[1].abs(), p[2].abs()];
+
+    let axis = if abs[0] >= abs[1] && abs[0] >= abs[2] {
+        0
+    } else if abs[1] >= abs[2] {
+        1
+    } else {
+        2
+    };
+    let face = if p[axis] < 0. { axis + 3 } else { axis } as u8;
+
+    let (u, v) = match face {
+        0 => ( p[1] / p[0],  p[2] / p[0]),
+        1 => (-p[0] / p[1],  p[2] / p[1]),
+        2 => (-p[0] / p[2], -p[1] / p[2]),
+        3 => ( p[2] / p[0],  p[1] / p[0]),
+        4 => ( p[2] / p[1], -p[0] / p[1]),
+        _ => (-p[1] / p[2], -p[0] / p[2]),
+    };
+
+    return (face, u, v);
+}
+
+/// Project a point on a cube face back onto the unit sphere.
+fn face_uv_to_xyz(face:u8, u:f64, v:f64) -> UnitVector {
+    let p = match face {
+        0 => [ 1.,  u,  v],
+        1 => [-u,  1.,  v],
+        2 => [-u, -v,  1.],
+        3 => [-1., -v, -u],
+        4 => [ v, -1., -u],
+        _ => [ v,  u, -1.],
+    };
+
+    return normalize(&p);
+}
+
+fn normalize(p:&[f64; 3]) -> UnitVector {
+    let norm = dot(p, p).sqrt();
+
+    return [p[0] / norm, p[1] / norm, p[2] / norm];
+}
+
+fn dot(a:&[f64; 3], b:&[f64; 3]) -> f64 {
+    return a[0]*b[0] + a[1]*b[1] + a[2]*b[2];
+}
+
+fn cross(a:&[f64; 3], b:&[f64; 3]) -> [f64; 3] {
+    return [
+        a[1]*b[2] - a[2]*b[1],
+        a[2]*b[0] - a[0]*b[2],
+        a[0]*b[1] - a[1]*b[0],
+    ];
+}
+
+/// Angle between two unit vectors in radians.
+fn angle_between(a:&UnitVector, b:&UnitVector) -> f64 {
+    let c = cross(a, b);
+
+    return dot(&c, &c).sqrt().atan2(dot(a, b));
+}
+
+/// Convert a unit vector to ``(lat, lng)`` in degrees.
+pub fn to_latlng(p:&UnitVector) -> (f64, f64) {
+    return (
+        p[2].atan2((p[0]*p[0] + p[1]*p[1]).sqrt()).to_degrees(),
+        p[1].atan2(p[0]).to_degrees(),
+    );
+}
+
+// ================================================================================
+//  Hilbert curve
+// ================================================================================
+
+fn hilbert_rotate(n:u64, x:&mut u64, y:&mut u64, rx:u64, ry:u64) {
+    if ry == 0 {
+        if rx == 1 {
+            *x = n - 1 - *x;
+            *y = n - 1 - *y;
+        }
+        std::mem::swap(x, y);
+    }
+}
+
+/// Position along a Hilbert curve of ``(x, y)`` on a ``2^order`` square grid.
+fn hilbert_xy_to_d(order:u8, x:u64, y:u64) -> u64 {
+    let n = 1_u64 << order;
+    let (mut x, mut y) = (x, y);
+    let mut d = 0;
+    let mut s = n / 2;
+
+    while s > 0 {
+        let rx = ((x & s) > 0) as u64;
+        let ry = ((y & s) > 0) as u64;
+        d += s * s * ((3 * rx) ^ ry);
+        hilbert_rotate(n, &mut x, &mut y, rx, ry);
+        s /= 2;
+    }
+
+    return d;
+}
+
+/// ``(x, y)`` on a ``2^order`` square grid of a position along a Hilbert curve.
+fn hilbert_d_to_xy(order:u8, d:u64) -> (u64, u64) {
+    let n = 1_u64 << order;
+    let (mut x, mut y) = (0, 0);
+    let mut t = d;
+    let mut s = 1;
+
+    while s < n {
+        let rx = 1 & (t / 2);
+        let ry = 1 & (t ^ rx);
+        hilbert_rotate(s, &mut x, &mut y, rx, ry);
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+
+    return (x, y);
+}
+
+// ================================================================================
+//  Cell IDs
+// ================================================================================
+
+/// Identifier of a cell at any level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellId(pub u64);
+impl CellId {
+    /// The lowest set bit of a cell at ``level``.
+    pub fn lsb_for_level(level:u8) -> u64 {
+        return 1_u64 << (2 * (MAX_LEVEL - level) as u32);
+    }
+
+    /// The level 0 cell of a face.
+    pub fn from_face(face:u8) -> Self {
+        return Self(((face as u64) << POS_BITS) | Self::lsb_for_level(0));
+    }
+
+    /// The cell at row ``j`` and column ``i`` of a face at ``level``.
+    pub fn from_face_ij(face:u8, i:u64, j:u64, level:u8) -> Self {
+        let pos = hilbert_xy_to_d(level, i, j);
+
+        return Self(
+            ((face as u64) << POS_BITS)
+            | (pos << (POS_BITS - 2 * level as u32))
+            | Self::lsb_for_level(level)
+        );
+    }
+
+    /// The cell containing a unit vector at ``level``.
+    pub fn from_vector(p:&UnitVector, level:u8) -> Self {
+        let (face, u, v) = xyz_to_face_uv(p);
+        let leaf_count = (1_u64 << MAX_LEVEL) as f64;
+
+        let to_ij = | st:f64 | ((st * leaf_count).floor().max(0.) as u64).min((1_u64 << MAX_LEVEL) - 1);
+        let (i, j) = (to_ij(uv_to_st(u)), to_ij(uv_to_st(v)));
+        let shift = MAX_LEVEL - level;
+
+        return Self::from_face_ij(face, i >> shift, j >> shift, level);
+    }
+
+    /// The cell containing a latitude-longitude pair in degrees at ``level``.
+    pub fn from_latlng(lat:f64, lng:f64, level:u8) -> Self {
+        return Self::from_vector(&to_unit_vector(lat, lng), level);
+    }
+
+    /// Whether this is a valid cell ID.
+    pub fn is_valid(&self) -> bool {
+        return self.face() < 6 && (self.lsb() & 0x1555555555555555) != 0;
+    }
+
+    pub fn face(&self) -> u8 {
+        return (self.0 >> POS_BITS) as u8;
+    }
+
+    /// The lowest set bit of this cell ID.
+    pub fn lsb(&self) -> u64 {
+        return self.0 & self.0.wrapping_neg();
+    }
+
+    pub fn level(&self) -> u8 {
+        return MAX_LEVEL - (self.lsb().trailing_zeros() / 2) as u8;
+    }
+
+    /// The ancestor of this cell at ``level``, which must not be deeper than the
+    /// level of this cell.
+    pub fn parent(&self, level:u8) -> Self {
+        let lsb = Self::lsb_for_level(level);
+
+        return Self((self.0 & lsb.wrapping_neg()) | lsb);
+    }
+
+    /// The 4 children of this cell, in Hilbert curve order.
+    pub fn children(&self) -> [Self; 4] {
+        let lsb = self.lsb() >> 2;
+        let first = self.0 - self.lsb() + lsb;
+
+        return [
+            Self(first),
+            Self(first + 2 * lsb),
+            Self(first + 4 * lsb),
+            Self(first + 6 * lsb),
+        ];
+    }
+
+    /// Smallest cell ID of all descendants of this cell.
+    pub fn range_min(&self) -> u64 {
+        return self.0 - (self.lsb() - 1);
+    }
+
+    /// Largest cell ID of all descendants of this cell.
+    pub fn range_max(&self) -> u64 {
+        return self.0 + (self.lsb() - 1);
+    }
+
+    /// Whether ``other`` is this cell or one of its descendants.
+    pub fn contains(&self, other:&Self) -> bool {
+        return other.0 >= self.range_min() && other.0 <= self.range_max();
+    }
+
+    /// ``(face, i, j, level)`` of this cell.
+    pub fn face_ij(&self) -> (u8, u64, u64, u8) {
+        let level = self.level();
+        let pos = (self.0 & ((1_u64 << POS_BITS) - 1)) >> (POS_BITS - 2 * level as u32);
+        let (i, j) = hilbert_d_to_xy(level, pos);
+
+        return (self.face(), i, j, level);
+    }
+
+    /// The 4 corners of this cell as unit vectors, counter-clockwise.
+    ///
+    /// The edges between the corners are great circle arcs.
+    pub fn vertices(&self) -> [UnitVector; 4] {
+        let (face, i, j, level) = self.face_ij();
+        let size = 1. / (1_u64 << level) as f64;
+
+        let (u0, u1) = (st_to_uv(i as f64 * size), st_to_uv((i + 1) as f64 * size));
+        let (v0, v1) = (st_to_uv(j as f64 * size), st_to_uv((j + 1) as f64 * size));
+
+        return [
+            face_uv_to_xyz(face, u0, v0),
+            face_uv_to_xyz(face, u1, v0),
+            face_uv_to_xyz(face, u1, v1),
+            face_uv_to_xyz(face, u0, v1),
+        ];
+    }
+
+    /// The centre of this cell as a unit vector.
+    pub fn centre(&self) -> UnitVector {
+        let vertices = self.vertices();
+        let mut sum = [0_f64; 3];
+
+        for vertex in vertices.iter() {
+            for dim in 0..3 { sum[dim] += vertex[dim] }
+        }
+
+        return normalize(&sum);
+    }
+
+    /// Angular radius in radians of a cap around :meth:`centre` enclosing this cell.
+    pub fn bounding_angle(&self) -> f64 {
+        let centre = self.centre();
+
+        return self.vertices()
+                   .iter()
+                   .map(| vertex | angle_between(&centre, vertex))
+                   .fold(0., f64::max);
+    }
+
+    /// The 4 corners of this cell in degrees, counter-clockwise.
+    ///
+    /// Returns an array of dimension ``(4, 2)``, in order ``(latitude, longitude)``.
+    pub fn boundary(&self) -> F64LatLngArray {
+        let mut boundary = F64LatLngArray::zeros((4, 2));
+
+        for (k, vertex) in self.vertices().iter().enumerate() {
+            let (lat, lng) = to_latlng(vertex);
+
+            boundary[[k, 0]] = lat;
+            boundary[[k, 1]] = lng;
+        }
+
+        return boundary;
+    }
+}
+
+/// Cell IDs of each row of an array of lat-long coordinates at ``level``.
+pub fn cell_of(
+    s:&dyn LatLngArray,
+    level:u8,
+) -> Vec<CellId> {
+    let s_owned: F64LatLngArray = s.to_owned();
+
+    return s_owned.axis_iter(Axis(0))
+                  .map(| row | CellId::from_latlng(row[0], row[1], level))
+                  .collect();
+}
+
+/// The deepest level whose cells are at least as wide as ``angle`` radians.
+pub fn level_for_angle(angle:f64) -> u8 {
+    if !(angle > 0.) { return MAX_LEVEL }
+
+    return (AVG_EDGE_DERIV / angle).log2()
+                                   .floor()
+                                   .clamp(0., MAX_LEVEL as f64) as u8;
+}
+
+// ================================================================================
+//  Regions and coverings
+// ================================================================================
+
+/// A region on the sphere that can be covered by cells.
+pub trait Region {
+    /// Whether the region contains the whole of ``cell``.
+    ///
+    /// False negatives are allowed, at the expense of more subdivisions.
+    fn contains_cell(&self, cell:&CellId) -> bool;
+
+    /// Whether the region may intersect ``cell``.
+    ///
+    /// False positives are allowed, at the expense of a looser covering.
+    fn may_intersect_cell(&self, cell:&CellId) -> bool;
+}
+
+/// A spherical cap, i.e. a circle on the sphere.
+pub struct Cap {
+    pub centre: UnitVector,
+    pub angle: f64,
+}
+impl Cap {
+    /// A cap of ``angle`` radians around ``centre``.
+    pub fn new(centre:UnitVector, angle:f64) -> Self {
+        return Self { centre, angle };
+    }
+
+    /// A cap that encloses every point within ``distance`` of ``centre``
+    /// according to ``method``.
+    pub fn from_distance(
+        method:&CalculationMethod,
+        centre:&dyn LatLng,
+        distance:f64,
+        settings: Option<&CalculationSettings>,
+    ) -> Self {
+        let (radius_min, _) = method._radius_bounds(settings);
+
+        // Nudge the angle out, so that points exactly on the boundary are kept.
+        return Self::new(
+            to_unit_vector(centre[0], centre[1]),
+            (distance / radius_min) * (1. + 1e-9) + 1e-12,
+        );
+    }
+}
+impl Region for Cap {
+    fn contains_cell(&self, cell:&CellId) -> bool {
+        // Caps larger than a hemisphere are not convex.
+        return self.angle <= PI / 2.
+            && cell.vertices()
+                   .iter()
+                   .all(| vertex | angle_between(&self.centre, vertex) <= self.angle);
+    }
+
+    fn may_intersect_cell(&self, cell:&CellId) -> bool {
+        return angle_between(&self.centre, &cell.centre()) <= self.angle + cell.bounding_angle();
+    }
+}
+
+/// A polygon with great circle edges.
+///
+/// The polygon must be smaller than a hemisphere; the vertices may be in either
+/// winding order.
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    pub vertices: Vec<UnitVector>,
+}
+impl Polygon {
+    /// A polygon from an array of lat-long vertices.
+    ///
+    /// The polygon is closed implicitly; if the last vertex is identical to the
+    /// first, it is dropped.
+    pub fn new(vertices:&dyn LatLngArray) -> Self {
+        let vertices_owned: F64LatLngArray = vertices.to_owned();
+
+        let mut vertices: Vec<UnitVector> = vertices_owned.axis_iter(Axis(0))
+                                                          .map(| row | to_unit_vector(row[0], row[1]))
+                                                          .collect();
+
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+
+        return Self { vertices };
+    }
+
+    /// Edges of the polygon as pairs of vertices.
+    fn edges(&self) -> impl Iterator<Item=(&UnitVector, &UnitVector)> {
+        return self.vertices
+                   .iter()
+                   .zip(self.vertices.iter().cycle().skip(1));
+    }
+
+    /// Whether the polygon contains a point, by its winding number.
+    pub fn contains_point(&self, p:&UnitVector) -> bool {
+        let winding: f64 = self.edges()
+                               .map(
+                                   | (a, b) | {
+                                       let (pa, pb) = (cross(p, a), cross(p, b));
+                                       dot(&cross(&pa, &pb), p).atan2(dot(&pa, &pb))
+                                   }
+                               )
+                               .sum();
+
+        return winding.abs() > PI;
+    }
+
+    /// Whether any edge of the polygon crosses the great circle arc ``c`` to ``d``.
+    fn crosses(&self, c:&UnitVector, d:&UnitVector) -> bool {
+        return self.edges().any(| (a, b) | edges_cross(a, b, c, d));
+    }
+}
+
+/// Whether the great circle arcs ``a`` to ``b`` and ``c`` to ``d`` cross.
+fn edges_cross(a:&UnitVector, b:&UnitVector, c:&UnitVector, d:&UnitVector) -> bool {
+    let ab = cross(a, b);
+    let acb = -dot(&ab, c);
+    let bda = dot(&ab, d);
+    if acb * bda <= 0. { return false }
+
+    let cd = cross(c, d);
+    let cbd = -dot(&cd, b);
+    let dac = dot(&cd, a);
+
+    return acb * cbd > 0. && acb * dac > 0.;
+}
+
+impl Region for Polygon {
+    fn contains_cell(&self, cell:&CellId) -> bool {
+        let vertices = cell.vertices();
+
+        return vertices.iter().all(| vertex | self.contains_point(vertex))
+            && (0..4).all(| k | !self.crosses(&vertices[k], &vertices[(k+1) % 4]));
+    }
+
+    fn may_intersect_cell(&self, cell:&CellId) -> bool {
+        let vertices = cell.vertices();
+
+        return vertices.iter().any(| vertex | self.contains_point(vertex))
+            || (0..4).any(| k | self.crosses(&vertices[k], &vertices[(k+1) % 4]))
+            || self.vertices.iter().any(| vertex | cell.contains(&CellId::from_vector(vertex, MAX_LEVEL)));
+    }
+}
+
+/// Cells of up to ``max_level`` covering a region, using at most ``max_cells``.
+///
+/// Cells are subdivided coarsest first, as long as the covering does not exceed
+/// ``max_cells``; hence fewer cells result in a looser covering. The returned
+/// cells are sorted by ID, and may be of different levels.
+///
+/// .. note::
+///     ``max_cells`` is a target rather than a hard limit: at least the level 0
+///     faces intersecting the region are always returned.
+pub fn covering<R: Region>(
+    region:&R,
+    max_cells:usize,
+    max_level:u8,
+) -> Vec<CellId> {
+    let mut queue: VecDeque<CellId> = (0..6).map(CellId::from_face)
+                                            .filter(| cell | region.may_intersect_cell(cell))
+                                            .collect();
+    let mut result: Vec<CellId> = Vec::new();
+
+    while let Some(cell) = queue.pop_front() {
+        if cell.level() >= max_level || region.contains_cell(&cell) {
+            result.push(cell);
+            continue;
+        }
+
+        let children: Vec<CellId> = cell.children()
+                                        .into_iter()
+                                        .filter(| child | region.may_intersect_cell(child))
+                                        .collect();
+
+        if result.len() + queue.len() + children.len() > max_cells {
+            result.push(cell);
+            continue;
+        }
+
+        queue.extend(children);
+    }
+
+    result.sort();
+
+    return result;
+}
+
+// ================================================================================
+//  Cell-based index
+// ================================================================================
+
+/// Points sorted by their leaf cell IDs.
+///
+/// Since all descendants of a cell have IDs in a contiguous range, all points
+/// within a cell can be found by two binary searches.
+pub struct CellIndex {
+    leaves: Vec<(u64, usize)>,
+}
+impl CellIndex {
+    pub fn new(e:&dyn LatLngArray) -> Self {
+        let mut leaves: Vec<(u64, usize)> = {
+            cell_of(e, MAX_LEVEL).into_iter()
+                                 .enumerate()
+                                 .map(| (idx, cell) | (cell.0, idx))
+                                 .collect()
+        };
+        leaves.sort_unstable();
+
+        return Self { leaves };
+    }
+
+    /// Indices of all points within any of ``cells``, sorted.
+    pub fn candidates(&self, cells:&[CellId]) -> Vec<usize> {
+        let mut found: Vec<usize> = Vec::new();
+
+        for cell in cells {
+            let start = self.leaves.partition_point(| (id, _) | *id < cell.range_min());
+            let end = self.leaves.partition_point(| (id, _) | *id <= cell.range_max());
+
+            found.extend(self.leaves[start..end].iter().map(| (_, idx) | *idx));
+        }
+
+        found.sort_unstable();
+        found.dedup();
+
+        return found;
+    }
+}
+
+/// Indices of points in ``e`` within ``distance`` of each point of ``s``.
+///
+/// ``e`` is indexed by :class:`CellIndex`; for each point of ``s``, a covering of
+/// up to :const:`INDEX_COVERING_MAX_CELLS` cells of a level comparable to
+/// ``distance`` is generated, and only the points of ``e`` within those cells
+/// have their distances calculated. Since the covering is conservative, the
+/// results are identical to checking every point of ``e``.
+///
+/// Returns a Vector of sorted 1-dimensional arrays of `usizes`.
+pub fn indices_within_distance(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    e:&dyn LatLngArray,
+    distance:f64,
+    settings: Option<&CalculationSettings>,
+) -> Vec<Array1<usize>> {
+    let n = s.shape()[0];
+
+    let s_owned: F64LatLngArray = s.to_owned();
+    let e_owned: F64LatLngArray = e.to_owned();
+    let index = CellIndex::new(e);
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+    let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+    return (0..n)
+           .into_par_iter()
+           .with_min_len(chunk_size)
+           .map(
+               | i | {
+                   let point = s_owned.row(i);
+                   let cap = Cap::from_distance(method, &point, distance, settings);
+                   let cells = covering(&cap, INDEX_COVERING_MAX_CELLS, level_for_angle(2. * cap.angle));
+
+                   method._distance_to_indices(
+                       &point, &e_owned,
+                       &index.candidates(&cells),
+                       settings,
+                   )
+                   .into_iter()
+                   .filter(| (_, d) | *d <= distance)
+                   .map(| (idx, _) | idx)
+                   .collect::<Array1<usize>>()
+               }
+           )
+           .collect();
+}
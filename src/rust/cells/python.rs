@@ -0,0 +1,295 @@
+/// Python compatibility layer for the `cells` module.
+///
+/// The functions here are registered into a `cells` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.cells`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array1,
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    CellId,
+    Cap,
+    Polygon,
+    MAX_LEVEL,
+};
+
+/// Validate ``level``, or default it to :const:`MAX_LEVEL`.
+fn check_level(level:Option<u8>) -> PyResult<u8> {
+    return match level {
+        Some(level) if level > MAX_LEVEL => Err(
+            PyValueError::new_err(
+                format!("`level` must be between 0 and {:?}, yet {:?} provided.", MAX_LEVEL, level)
+            )
+        ),
+        Some(level) => Ok(level),
+        None => Ok(MAX_LEVEL),
+    };
+}
+
+/// Validate a cell ID.
+fn check_cell(cell:u64) -> PyResult<CellId> {
+    let cell = CellId(cell);
+
+    return if cell.is_valid() {
+        Ok(cell)
+    } else {
+        Err(PyValueError::new_err(format!("{:?} is not a valid cell ID.", cell.0)))
+    };
+}
+
+/// Convert cells to a ``numpy.uint64`` array.
+fn to_array(cells:Vec<CellId>) -> Array1<u64> {
+    return cells.into_iter().map(| cell | cell.0).collect();
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, level)")]
+/// Cell IDs of an array of lat-long coordinates.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// level: Optional[int]
+///     Level of the cells, between ``0`` (the 6 faces of the cube) and ``30``
+///     (roughly 1cm across). Defaults to ``30``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.uint64)
+///     The cell ID containing each point in ``s``.
+fn cell_of(
+    s: &PyArray<f64, Ix2>,
+    level: Option<u8>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let level = check_level(level)?;
+
+    return Ok(to_array(super::cell_of(&s.to_owned_array(), level)).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(cells, level)")]
+/// Ancestors of cells at a coarser level.
+///
+/// Parameters
+/// ----------
+/// cells: numpy.ndarray (dtype=numpy.uint64)
+///     Of dimension ``(n)``; cell IDs of any level not coarser than ``level``.
+///
+/// level: int
+///     Level of the ancestors, between ``0`` and ``30``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.uint64)
+///     The ancestor of each cell at ``level``.
+fn cell_parent(
+    cells: &PyArray<u64, Ix1>,
+    level: u8,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let level = check_level(Some(level))?;
+
+    let parents = cells.to_owned_array()
+                       .iter()
+                       .map(
+                           | cell | {
+                               let cell = check_cell(*cell)?;
+
+                               if cell.level() < level {
+                                   return Err(
+                                       PyValueError::new_err(
+                                           format!("Cell {:?} of level {:?} is coarser than `level` {:?}.", cell.0, cell.level(), level)
+                                       )
+                                   );
+                               }
+
+                               Ok(cell.parent(level))
+                           }
+                       )
+                       .collect::<PyResult<Vec<CellId>>>()?;
+
+    return Ok(to_array(parents).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(cells)")]
+/// Levels of cells.
+///
+/// Parameters
+/// ----------
+/// cells: numpy.ndarray (dtype=numpy.uint64)
+///     Of dimension ``(n)``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.uint8)
+///     The level of each cell.
+fn cell_level(
+    cells: &PyArray<u64, Ix1>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let levels = cells.to_owned_array()
+                      .iter()
+                      .map(| cell | Ok(check_cell(*cell)?.level()))
+                      .collect::<PyResult<Array1<u8>>>()?;
+
+    return Ok(levels.to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(cell)")]
+/// The corners of a cell.
+///
+/// Parameters
+/// ----------
+/// cell: int
+///     A cell ID of any level.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(4, 2)`` in degrees, in order ``(latitude, longitude)``.
+///     The corners are in counter-clockwise order; the edges between them are
+///     great circle arcs.
+fn cell_boundary(
+    cell: u64,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    return Ok(check_cell(cell)?.boundary().to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(centre, radius, max_cells, max_level, method, *, settings)")]
+/// Cells covering a geodesic circle.
+///
+/// Cells are subdivided coarsest first as long as the covering stays within
+/// ``max_cells``; hence more cells give a tighter covering. Every point within
+/// ``radius`` of ``centre`` is guaranteed to be within one of the cells.
+///
+/// Parameters
+/// ----------
+/// centre: numpy.ndarray
+///     Of dimension ``(2)``, e.g. ``numpy.array([51.5072, -0.1276])``.
+///
+/// radius: numpy.float64
+///     Radius of the circle.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// max_cells: int
+///     Target number of cells in the covering.
+///
+/// max_level: Optional[int]
+///     Deepest level of cells to use. Defaults to ``30``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.uint64)
+///     Sorted cell IDs of possibly different levels.
+fn covering_circle(
+    centre: &PyArray<f64, Ix1>,
+    radius: f64,
+    max_cells: usize,
+    max_level: Option<u8>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let max_level = check_level(max_level)?;
+    let method_default = &enums::CalculationMethod::default();
+
+    let cap = Cap::from_distance(
+        method.unwrap_or(method_default),
+        &centre.to_owned_array(),
+        radius,
+        settings,
+    );
+
+    return Ok(to_array(super::covering(&cap, max_cells, max_level)).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(vertices, max_cells, max_level)")]
+/// Cells covering a polygon.
+///
+/// Cells are subdivided coarsest first as long as the covering stays within
+/// ``max_cells``; hence more cells give a tighter covering.
+///
+/// Parameters
+/// ----------
+/// vertices: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///     The polygon is closed implicitly, and its edges are great circle arcs. It
+///     must be smaller than a hemisphere.
+///
+/// max_cells: int
+///     Target number of cells in the covering.
+///
+/// max_level: Optional[int]
+///     Deepest level of cells to use. Defaults to ``30``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.uint64)
+///     Sorted cell IDs of possibly different levels.
+fn covering_polygon(
+    vertices: &PyArray<f64, Ix2>,
+    max_cells: usize,
+    max_level: Option<u8>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let max_level = check_level(max_level)?;
+
+    if vertices.shape()[0] < 3 {
+        return Err(PyValueError::new_err("`vertices` must contain at least 3 points."));
+    }
+
+    let polygon = Polygon::new(&vertices.to_owned_array());
+
+    return Ok(to_array(super::covering(&polygon, max_cells, max_level)).to_pyarray(py).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(cell_of, m)?)?;
+    m.add_function(wrap_pyfunction!(cell_parent, m)?)?;
+    m.add_function(wrap_pyfunction!(cell_level, m)?)?;
+    m.add_function(wrap_pyfunction!(cell_boundary, m)?)?;
+    m.add_function(wrap_pyfunction!(covering_circle, m)?)?;
+    m.add_function(wrap_pyfunction!(covering_polygon, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+/// Distances taking heights into account.
+///
+/// Arrays of dimension ``(n, 3)`` carry a height in column 2, in the same unit as
+/// the distances of the calculation method; arrays of dimension ``(n, 2)`` are
+/// taken to be at height ``0``. All calculation models only read columns 0 and 1,
+/// so the surface distance is unaffected by heights; the methods here then combine
+/// it with the height difference according to an :enum:`AltitudeMode`.
+
+use std::str::FromStr;
+
+use ndarray::{
+    Axis,
+    Zip,
+};
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLng,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+};
+
+use super::enums::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+use super::pairwise::CalculationInterfacePairwise;
+
+/// How to combine the surface distance ``d`` with the height difference ``h``.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AltitudeMode {
+    /// ``d``; heights are ignored.
+    Surface,
+
+    /// ``sqrt(d² + h²)``, the length of the straight line over the geodesic.
+    Slant,
+
+    /// ``d + |h|``, the surface distance plus the climb or descent.
+    SurfaceClimb,
+}
+impl Default for AltitudeMode {
+    fn default() -> Self { Self::Surface }
+}
+impl AltitudeMode {
+    /// Combine a surface distance with a height difference.
+    pub fn combine(&self, distance:f64, height_difference:f64) -> f64 {
+        return match self {
+            Self::Surface => distance,
+            Self::Slant => distance.hypot(height_difference),
+            Self::SurfaceClimb => distance + height_difference.abs(),
+        };
+    }
+}
+impl FromStr for AltitudeMode {
+    type Err = String;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "surface" => Ok(Self::Surface),
+            "slant" => Ok(Self::Slant),
+            "surface_climb" => Ok(Self::SurfaceClimb),
+            other => Err(
+                format!("Unknown mode {:?}; expected \"surface\", \"slant\" or \"surface_climb\".", other)
+            ),
+        };
+    }
+}
+
+/// Heights in column 2 of an array of dimension ``(n, 2)`` or ``(n, 3)``; zeros if
+/// absent.
+pub fn heights(s:&dyn LatLngArray) -> F64Array1 {
+    let s_owned = s.to_owned();
+
+    return if s_owned.shape()[1] > 2 {
+        s_owned.column(2).to_owned()
+    } else {
+        F64Array1::zeros(s_owned.shape()[0])
+    };
+}
+
+/// Trait for altitude-aware distance methods.
+pub trait CalculationInterfaceAltitude {
+    /// Distances from a point of dimension ``(2)`` or ``(3)`` to every point of
+    /// ``e``.
+    fn _distance_from_point_with_altitude(
+        &self,
+        s:&F64LatLng,
+        e:&dyn LatLngArray,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1;
+
+    /// Distances from every point of ``s`` to every point of ``e``.
+    ///
+    /// If ``e`` is ``None``, ``s`` is paired with itself using
+    /// :meth:`CalculationInterfaceInternal::_distance_within_array`.
+    fn _distance_with_altitude(
+        &self,
+        s:&dyn LatLngArray,
+        e:Option<&dyn LatLngArray>,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array2;
+
+    /// Distances from each row of ``s`` to the same row of ``e``.
+    fn _distance_pairwise_with_altitude(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceAltitude for CalculationMethod {
+    fn _distance_from_point_with_altitude(
+        &self,
+        s:&F64LatLng,
+        e:&dyn LatLngArray,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1 {
+        let mut distances = CalculationInterfaceInternal::<f64>::_distance_from_point(self, s, e, settings);
+
+        if mode != AltitudeMode::Surface {
+            let s_height = if s.len() > 2 { s[2] } else { 0. };
+
+            Zip::from(&mut distances)
+                .and(&heights(e))
+                .for_each(| d, e_height | *d = mode.combine(*d, e_height - s_height));
+        }
+
+        return distances;
+    }
+
+    fn _distance_with_altitude(
+        &self,
+        s:&dyn LatLngArray,
+        e:Option<&dyn LatLngArray>,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array2 {
+        let mut distances = match e {
+            Some(e) => CalculationInterfaceInternal::<f64>::_distance(self, s, e, settings),
+            None => CalculationInterfaceInternal::<f64>::_distance_within_array(self, s, settings),
+        };
+
+        if mode != AltitudeMode::Surface {
+            let s_heights = heights(s);
+            let e_heights = match e {
+                Some(e) => heights(e),
+                None => s_heights.clone(),
+            };
+
+            Zip::from(distances.axis_iter_mut(Axis(0)))
+                .and(&s_heights)
+                .par_for_each(
+                    | mut row, s_height | {
+                        Zip::from(&mut row)
+                            .and(&e_heights)
+                            .for_each(| d, e_height | *d = mode.combine(*d, e_height - s_height));
+                    }
+                );
+        }
+
+        return distances;
+    }
+
+    fn _distance_pairwise_with_altitude(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        mode:AltitudeMode,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1 {
+        let mut distances = self._distance_pairwise(s, e, settings);
+
+        if mode != AltitudeMode::Surface {
+            Zip::from(&mut distances)
+                .and(&heights(s))
+                .and(&heights(e))
+                .for_each(| d, s_height, e_height | *d = mode.combine(*d, e_height - s_height));
+        }
+
+        return distances;
+    }
+}
@@ -0,0 +1,362 @@
+/// k-medoids clustering and facility location.
+///
+/// Both problems choose ``k`` points to minimise the total great-circle distance
+/// from a set of points to their closest chosen point:
+///
+/// - :meth:`_cluster_kmedoids` chooses from the points themselves, using PAM
+///   (Partitioning Around Medoids) for small arrays and CLARA (PAM over repeated
+///   random samples) for large ones;
+/// - :meth:`_facility_location` chooses from a separate array of candidates
+///   using a greedy heuristic, weighting each demand point.
+
+use std::cmp;
+
+use ndarray::Axis;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+};
+
+use super::enums::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+/// Largest array that PAM runs on directly; larger arrays use CLARA.
+///
+/// PAM requires the full ``(n, n)`` distance array in memory.
+pub const PAM_MAX_POINTS:usize = 2048;
+
+/// Number of samples drawn by CLARA.
+pub const CLARA_SAMPLES:usize = 5;
+
+/// Minimum size of each sample drawn by CLARA.
+pub const CLARA_MIN_SAMPLE_SIZE:usize = 256;
+
+/// Result of a clustering or facility location: ``(chosen, assignment, cost)``.
+///
+/// - ``chosen`` are the row indices of the chosen points,
+/// - ``assignment`` contains, for each point, the position in ``chosen`` of its
+///   closest chosen point, and
+/// - ``cost`` is the total (weighted) distance to the closest chosen points.
+pub type Assignment = (Vec<usize>, Vec<usize>, f64);
+
+/// Trait for clustering methods.
+pub trait CalculationInterfaceClustering {
+    /// Partition ``s`` into ``k`` clusters around medoids.
+    ///
+    /// ``seed`` is used for the random samples drawn by CLARA; it has no effect on
+    /// arrays short enough for PAM.
+    fn _cluster_kmedoids(
+        &self,
+        s:&dyn LatLngArray,
+        k:usize,
+        seed:Option<u64>,
+        settings: Option<&CalculationSettings>,
+    ) -> Assignment;
+
+    /// Choose ``k`` of ``candidates`` to serve ``demand`` with the least total
+    /// ``weights`` * distance.
+    ///
+    /// If ``weights`` is `None`, each point in ``demand`` is weighted equally.
+    fn _facility_location(
+        &self,
+        candidates:&dyn LatLngArray,
+        demand:&dyn LatLngArray,
+        k:usize,
+        weights:Option<&F64Array1>,
+        settings: Option<&CalculationSettings>,
+    ) -> Assignment;
+}
+
+/// For each row of ``distances``, the closest and second closest of ``columns``.
+///
+/// Returns ``(nearest, nearest_distance, second_distance)``, where ``nearest`` are
+/// positions within ``columns``.
+fn assign_nearest(
+    distances:&F64Array2,
+    columns:&[usize],
+) -> (Vec<usize>, Vec<f64>, Vec<f64>) {
+    let n = distances.shape()[0];
+
+    let mut nearest = vec![0; n];
+    let mut nearest_distance = vec![f64::INFINITY; n];
+    let mut second_distance = vec![f64::INFINITY; n];
+
+    for j in 0..n {
+        for (pos, column) in columns.iter().enumerate() {
+            let d = distances[[j, *column]];
+
+            if d < nearest_distance[j] {
+                second_distance[j] = nearest_distance[j];
+                nearest_distance[j] = d;
+                nearest[j] = pos;
+            } else if d < second_distance[j] {
+                second_distance[j] = d;
+            }
+        }
+    }
+
+    return (nearest, nearest_distance, second_distance);
+}
+
+/// Size of each sample drawn by CLARA for ``k`` medoids among ``n`` points.
+///
+/// Normally capped at :const:`PAM_MAX_POINTS`, but never fewer than ``k`` points,
+/// or PAM would run out of medoids to choose from.
+pub fn clara_sample_size(n:usize, k:usize) -> usize {
+    return cmp::min(
+        n,
+        cmp::max(
+            k,
+            cmp::min(PAM_MAX_POINTS, cmp::max(CLARA_MIN_SAMPLE_SIZE, 40 + 2*k)),
+        ),
+    );
+}
+
+/// Partitioning Around Medoids over a full ``(n, n)`` distance array.
+///
+/// ``initial`` medoids are kept during BUILD if provided.
+fn pam(
+    distances:&F64Array2,
+    k:usize,
+    initial:&[usize],
+    max_iterations:usize,
+    eps:f64,
+    chunk_size:usize,
+) -> Vec<usize> {
+    let n = distances.shape()[0];
+
+    // BUILD: greedily add the medoid that reduces the total cost the most.
+    let mut medoids: Vec<usize> = initial.iter().cloned().take(k).collect();
+    let (_, mut nearest_distance, _) = assign_nearest(distances, &medoids);
+
+    while medoids.len() < k {
+        let (best, _) = {
+            (0..n)
+            .into_par_iter()
+            .with_min_len(chunk_size)
+            .filter(| c | !medoids.contains(c))
+            .map(
+                | c | (
+                    c,
+                    (0..n).map(| j | nearest_distance[j].min(distances[[j, c]]))
+                          .sum::<f64>()
+                )
+            )
+            .min_by(| a, b | a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+            .unwrap()
+        };
+
+        medoids.push(best);
+        for j in 0..n {
+            nearest_distance[j] = nearest_distance[j].min(distances[[j, best]]);
+        }
+    }
+
+    // SWAP: replace a medoid with a non-medoid while it reduces the total cost.
+    for _ in 0..max_iterations {
+        let (nearest, nearest_distance, second_distance) = assign_nearest(distances, &medoids);
+
+        let best = {
+            (0..n)
+            .into_par_iter()
+            .with_min_len(chunk_size)
+            .filter(| o | !medoids.contains(o))
+            .flat_map_iter(
+                | o | {
+                    // Change in cost for all points if `o` is added; then adjust
+                    // for the points that lose their closest medoid.
+                    let mut common = 0.;
+                    let mut deltas = vec![0_f64; k];
+
+                    for j in 0..n {
+                        let d = distances[[j, o]];
+                        let gain = d.min(nearest_distance[j]) - nearest_distance[j];
+
+                        common += gain;
+                        deltas[nearest[j]] += d.min(second_distance[j]) - nearest_distance[j] - gain;
+                    }
+
+                    deltas.into_iter()
+                          .enumerate()
+                          .map(move | (pos, delta) | (pos, o, delta + common))
+                }
+            )
+            .min_by(| a, b | a.2.total_cmp(&b.2).then((a.0, a.1).cmp(&(b.0, b.1))))
+        };
+
+        match best {
+            Some((pos, o, delta)) if delta < -eps => medoids[pos] = o,
+            _ => break,
+        }
+    }
+
+    return medoids;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceClustering for CalculationMethod {
+    fn _cluster_kmedoids(
+        &self,
+        s:&dyn LatLngArray,
+        k:usize,
+        seed:Option<u64>,
+        settings: Option<&CalculationSettings>,
+    ) -> Assignment {
+        let n = s.shape()[0];
+
+        assert!(k > 0 && k <= n, "`k` must be between 1 and the number of points {:?}, yet {:?} provided.", n, k);
+
+        let settings_default = &CalculationSettings::default();
+        let eps:f64 = settings.unwrap_or(settings_default).eps;
+        let max_iterations:usize = settings.unwrap_or(settings_default).max_iterations;
+        let workers:usize = settings.unwrap_or(settings_default).workers;
+
+        if n <= PAM_MAX_POINTS {
+            let chunk_size: usize = (n as f32 / workers as f32).ceil() as usize;
+
+            let distances = CalculationInterfaceInternal::<f64>::_distance_within_array(
+                self,
+                s,
+                settings,
+            );
+
+            let medoids = pam(&distances, k, &[], max_iterations, eps, chunk_size);
+            let (assignment, nearest_distance, _) = assign_nearest(&distances, &medoids);
+
+            return (medoids, assignment, nearest_distance.iter().sum());
+        }
+
+        // CLARA: run PAM on samples, keeping the medoids with the lowest total cost
+        // across the whole array. The best medoids so far are included in each
+        // subsequent sample.
+        let s_owned: F64LatLngArray = s.to_owned();
+        let sample_size = clara_sample_size(n, k);
+        let chunk_size: usize = (sample_size as f32 / workers as f32).ceil() as usize;
+
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut best: Option<Assignment> = None;
+
+        for _ in 0..CLARA_SAMPLES {
+            let mut sample: Vec<usize> = match &best {
+                Some((medoids, _, _)) => medoids.clone(),
+                None => Vec::new(),
+            };
+            for idx in index::sample(&mut rng, n, sample_size).into_iter() {
+                if sample.len() >= sample_size { break }
+                if !sample.contains(&idx) { sample.push(idx) }
+            }
+
+            let sample_distances = CalculationInterfaceInternal::<f64>::_distance_within_array(
+                self,
+                &s_owned.select(Axis(0), &sample),
+                settings,
+            );
+
+            // Previous best medoids are at the start of the sample.
+            let initial: Vec<usize> = (0..best.as_ref().map_or(0, | (medoids, _, _) | medoids.len())).collect();
+
+            let medoids: Vec<usize> = {
+                pam(&sample_distances, k, &initial, max_iterations, eps, chunk_size)
+                .into_iter()
+                .map(| pos | sample[pos])
+                .collect()
+            };
+
+            let distances = CalculationInterfaceInternal::<f64>::_distance(
+                self,
+                &s_owned,
+                &s_owned.select(Axis(0), &medoids),
+                settings,
+            );
+            let columns: Vec<usize> = (0..k).collect();
+            let (assignment, nearest_distance, _) = assign_nearest(&distances, &columns);
+            let cost: f64 = nearest_distance.iter().sum();
+
+            if best.as_ref().map_or(true, | (_, _, best_cost) | cost < *best_cost) {
+                best = Some((medoids, assignment, cost));
+            }
+        }
+
+        return best.unwrap();
+    }
+
+    fn _facility_location(
+        &self,
+        candidates:&dyn LatLngArray,
+        demand:&dyn LatLngArray,
+        k:usize,
+        weights:Option<&F64Array1>,
+        settings: Option<&CalculationSettings>,
+    ) -> Assignment {
+        let (m, n) = (candidates.shape()[0], demand.shape()[0]);
+
+        assert!(k > 0 && k <= m, "`k` must be between 1 and the number of candidates {:?}, yet {:?} provided.", m, k);
+
+        let weights = match weights {
+            Some(weights) => {
+                assert!(weights.len() == n, "`weights` must be of the same length as `demand`.");
+                weights.to_owned()
+            },
+            None => F64Array1::ones(n),
+        };
+
+        let workers: usize = settings.unwrap_or(
+            &CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = (m as f32 / workers as f32).ceil() as usize;
+
+        let distances = CalculationInterfaceInternal::<f64>::_distance(
+            self,
+            demand, candidates,
+            settings,
+        );
+
+        let mut chosen: Vec<usize> = Vec::with_capacity(k);
+        let mut nearest_distance = vec![f64::INFINITY; n];
+
+        while chosen.len() < k {
+            let (best, _) = {
+                (0..m)
+                .into_par_iter()
+                .with_min_len(chunk_size)
+                .filter(| c | !chosen.contains(c))
+                .map(
+                    | c | (
+                        c,
+                        (0..n).map(| j | weights[j] * nearest_distance[j].min(distances[[j, c]]))
+                              .sum::<f64>()
+                    )
+                )
+                .min_by(| a, b | a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+                .unwrap()
+            };
+
+            chosen.push(best);
+            for j in 0..n {
+                nearest_distance[j] = nearest_distance[j].min(distances[[j, best]]);
+            }
+        }
+
+        let (assignment, nearest_distance, _) = assign_nearest(&distances, &chosen);
+        let cost = (0..n).map(| j | weights[j] * nearest_distance[j]).sum();
+
+        return (chosen, assignment, cost);
+    }
+}
@@ -53,6 +53,8 @@ use crate::calc_models::{
     Vincenty,
 };
 
+use crate::cells;
+
 // use super::conversions::{
 //     BoolArrayToVecIndex,
 // };
@@ -99,6 +101,9 @@ pub use crate::calc_models::CalculationSettings;
 /// - column 0 being latitudes in degrees, and
 /// - column 1 being longitudes in degrees.
 ///
+/// Distance methods also accept an optional column 2 of heights, in the same unit
+/// as the distances; see the ``mode`` parameter of :meth:`distance`.
+///
 /// To illustrate this::
 ///
 ///             [:,0]       [:,1]
@@ -278,6 +283,10 @@ pub trait CalculationInterfaceInternal<T> {
     ///
     /// Returns a Vector of 1-dimensional arrays of `usizes`.
     ///
+    /// Points of ``e`` are pruned by cells covering ``distance`` around each point
+    /// of ``s`` before any distances are calculated; see
+    /// :func:`cells::indices_within_distance`.
+    ///
     /// .. note::
     ///     Note that the return value is a ``Vec``, not a 2-dimensional array.
     ///     This is because of each ``Array1<usize>>`` being variable in length,
@@ -512,21 +521,12 @@ impl<__impl_generics__> CalculationInterfaceInternal<__vector_type__> for Calcul
         distance: f64,
         settings: Option<&CalculationSettings>,
     ) -> Vec<Array1<usize>> {
-        return CalculationInterfaceInternal
-               ::<__vector_type__>
-               ::_within_distance(
-                    self,
-                    s, e,
-                    distance,
-                    settings,
-                )
-                .axis_iter(Axis(0))
-                .map(
-                    | row| {
-                        row.indices()
-                    }
-                )
-                .collect();
+        return cells::indices_within_distance(
+            self,
+            s, e,
+            distance,
+            settings,
+        );
     }
 
     fn _indices_within_distance_of_point(
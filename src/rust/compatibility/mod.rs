@@ -18,8 +18,15 @@
 /// boilerplates shall be unified and carried out in this module.
 ///
 
+pub mod altitude;
+pub mod clustering;
 pub mod conversions;
 pub mod enums;
+pub mod neighbours;
+pub mod pairwise;
+pub mod proximity;
+pub mod routing;
+pub mod spanning_tree;
 
 // Import this if you want CalculationMethod to have Python Methods.
 pub mod python;
@@ -33,3 +40,32 @@ pub use enums::{
     CalculationMethod,
     CalculationSettings,    // Re-imported from `calc_models`.
 };
+
+pub use altitude::{
+    AltitudeMode,
+    CalculationInterfaceAltitude,
+};
+
+pub use clustering::{
+    CalculationInterfaceClustering,
+};
+
+pub use neighbours::{
+    CalculationInterfaceNeighbours,
+};
+
+pub use pairwise::{
+    CalculationInterfacePairwise,
+};
+
+pub use proximity::{
+    CalculationInterfaceProximity,
+};
+
+pub use routing::{
+    CalculationInterfaceRouting,
+};
+
+pub use spanning_tree::{
+    CalculationInterfaceSpanningTree,
+};
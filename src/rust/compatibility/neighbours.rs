@@ -0,0 +1,246 @@
+/// Neighbour searches backed by a spatial index.
+///
+/// :class:`~spatial::UnitVectorTree` ranks points by their great-circle angle on a
+/// unit sphere. For Haversine this ranking is exact; for Vincenty the geodesic
+/// distance of the same angle can vary by a fraction of a percent depending on
+/// latitude and direction. The methods here use the tree to narrow down the
+/// candidates, then re-calculate the candidates with the chosen model so that the
+/// results are always exact for the :class:`CalculationMethod` used.
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+};
+
+use crate::spatial::{
+    UnitVectorTree,
+    angle_to_chord,
+};
+
+use super::enums::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+/// Safety margin applied to the radius bounds of ellipsoidal models.
+pub const RADIUS_BOUNDS_MARGIN:f64 = 0.01;
+
+/// Trait for neighbour searches using a :class:`~spatial::UnitVectorTree`.
+pub trait CalculationInterfaceNeighbours {
+    /// Lowest and highest distance per radian of central angle of the model.
+    ///
+    /// For Haversine this is simply the spherical radius on both ends. For
+    /// Vincenty this is the range of the radius of curvature of the ellipsoid,
+    /// i.e. ``b²/a`` at the equator to ``a²/b`` at the poles.
+    fn _radius_bounds(
+        &self,
+        settings: Option<&CalculationSettings>,
+    ) -> (f64, f64);
+
+    /// Chord distance on the unit sphere that is guaranteed to enclose all points
+    /// within ``distance`` of a point according to the model.
+    fn _chord_bound(
+        &self,
+        distance: f64,
+        settings: Option<&CalculationSettings>,
+    ) -> f64;
+
+    /// Model distances from ``s`` to the rows ``indices`` of ``e``.
+    fn _distance_to_indices(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        indices:&[usize],
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>;
+
+    /// The ``k`` nearest points of ``e`` to ``s`` accepted by ``accept``.
+    ///
+    /// ``tree`` must have been built from ``e``.
+    ///
+    /// Returns a `Vec` of ``(index, distance)`` sorted by ascending distance.
+    fn _nearest_from_tree<F>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        k: usize,
+        accept:F,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool;
+
+    /// Same as :meth:`_nearest_from_tree`, but skipping the subtrees of ``tree``
+    /// for which ``skip`` is ``true``; see :meth:`UnitVectorTree::nearest_skipping`.
+    #[allow(clippy::too_many_arguments)]
+    fn _nearest_from_tree_skipping<F, G>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        k: usize,
+        accept:F,
+        skip:G,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool;
+
+    /// All points of ``e`` within ``distance`` of ``s`` accepted by ``accept``.
+    ///
+    /// ``tree`` must have been built from ``e``.
+    ///
+    /// Returns a `Vec` of ``(index, distance)`` sorted by ascending distance.
+    fn _within_distance_from_tree<F>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        distance: f64,
+        accept:F,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceNeighbours for CalculationMethod {
+    fn _radius_bounds(
+        &self,
+        settings: Option<&CalculationSettings>,
+    ) -> (f64, f64) {
+        let settings_default = &CalculationSettings::default();
+        let settings = settings.unwrap_or(settings_default);
+
+        return match self {
+            Self::HAVERSINE => (settings.spherical_radius, settings.spherical_radius),
+            Self::VINCENTY => (
+                settings.ellipse_b.powi(2) / settings.ellipse_a * (1. - RADIUS_BOUNDS_MARGIN),
+                settings.ellipse_a.powi(2) / settings.ellipse_b * (1. + RADIUS_BOUNDS_MARGIN),
+            ),
+        };
+    }
+
+    fn _chord_bound(
+        &self,
+        distance: f64,
+        settings: Option<&CalculationSettings>,
+    ) -> f64 {
+        let (radius_min, _) = self._radius_bounds(settings);
+
+        // Rounding errors in the tree are in the order of `f64::EPSILON`; nudge the
+        // bound out so that points exactly on the boundary are not lost.
+        return angle_to_chord(distance / radius_min) * (1. + 1e-9) + 1e-12;
+    }
+
+    fn _distance_to_indices(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        indices:&[usize],
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)> {
+        if indices.is_empty() { return Vec::new() }
+
+        let e_subset: F64LatLngArray = e.select(Axis(0), indices);
+
+        let distances = CalculationInterfaceInternal::<f64>::_ser_distance_from_point(
+            self,
+            s, &e_subset,
+            settings,
+        );
+
+        return indices.iter()
+                      .cloned()
+                      .zip(distances.iter().cloned())
+                      .collect();
+    }
+
+    fn _nearest_from_tree<F>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        k: usize,
+        accept:F,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool {
+        return self._nearest_from_tree_skipping(s, e, tree, k, accept, | _ | false, settings);
+    }
+
+    fn _nearest_from_tree_skipping<F, G>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        k: usize,
+        accept:F,
+        skip:G,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool {
+        let q = UnitVectorTree::query_vector(s);
+
+        let spherical: Vec<usize> = tree.nearest_skipping(&q, k, f64::INFINITY, &accept, &skip)
+                                        .into_iter()
+                                        .map(| (idx, _) | idx)
+                                        .collect();
+
+        let mut results = self._distance_to_indices(s, e, &spherical, settings);
+
+        let (radius_min, radius_max) = self._radius_bounds(settings);
+
+        // Ellipsoidal models can rank points differently to the sphere; widen the
+        // search to everything that could possibly be closer than the k-th point.
+        if radius_min < radius_max && results.len() == k {
+            let furthest = results.iter().fold(0_f64, | d, (_, _d) | d.max(*_d));
+            let max_chord = self._chord_bound(furthest, settings);
+
+            let candidates: Vec<usize> = tree.within_skipping(&q, max_chord, &accept, &skip)
+                                             .into_iter()
+                                             .map(| (idx, _) | idx)
+                                             .collect();
+
+            results = self._distance_to_indices(s, e, &candidates, settings);
+        }
+
+        results.sort_by(| a, b | a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        results.truncate(k);
+
+        return results;
+    }
+
+    fn _within_distance_from_tree<F>(
+        &self,
+        s:&dyn LatLng,
+        e:&F64LatLngArray,
+        tree:&UnitVectorTree,
+        distance: f64,
+        accept:F,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool {
+        let q = UnitVectorTree::query_vector(s);
+        let max_chord = self._chord_bound(distance, settings);
+
+        let candidates: Vec<usize> = tree.within(&q, max_chord, &accept)
+                                         .into_iter()
+                                         .map(| (idx, _) | idx)
+                                         .collect();
+
+        let mut results: Vec<(usize, f64)> = self._distance_to_indices(s, e, &candidates, settings)
+                                                 .into_iter()
+                                                 .filter(| (_, d) | *d <= distance)
+                                                 .collect();
+
+        results.sort_by(| a, b | a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        return results;
+    }
+}
@@ -0,0 +1,108 @@
+/// Row-by-row distances and bearings.
+///
+/// Unlike :meth:`CalculationInterfaceInternal::_distance`, which calculates an
+/// ``(n, m)`` array of distances from every point of ``s`` to every point of
+/// ``e``, the methods here pair up rows of ``s`` and ``e`` and return arrays of
+/// dimension ``(n)``. Memory usage is therefore linear, which allows them to run
+/// over tables of millions of rows.
+
+use ndarray_numeric::{
+    F64Array1,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+    CalculateInverse,
+};
+
+use crate::calc_models::{
+    Haversine,
+    Vincenty,
+};
+
+use super::enums::{
+    CalculationMethod,
+    CalculationSettings,
+};
+
+/// Trait for pairwise methods.
+pub trait CalculationInterfacePairwise {
+    /// Distances and initial bearings from each row of ``s`` to the same row of
+    /// ``e``.
+    ///
+    /// ``s`` and ``e`` must be of the same shape ``(n, 2)``. Returns a tuple of
+    /// arrays of dimension ``(n)``: distances, and bearings in degrees.
+    fn _inverse_pairwise(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> (F64Array1, F64Array1);
+
+    /// Distance and initial bearing in degrees from a single point ``(s_lat,
+    /// s_lng)`` to another ``(e_lat, e_lng)``, all in degrees.
+    ///
+    /// For point-by-point algorithms, which cannot be batched into arrays.
+    fn _inverse(
+        &self,
+        s_lat:f64,
+        s_lng:f64,
+        e_lat:f64,
+        e_lng:f64,
+        settings: Option<&CalculationSettings>,
+    ) -> (f64, f64);
+
+    /// Distances from each row of ``s`` to the same row of ``e``.
+    fn _distance_pairwise(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1 {
+        return self._inverse_pairwise(s, e, settings).0;
+    }
+
+    /// Initial bearings in degrees from each row of ``s`` to the same row of
+    /// ``e``.
+    fn _bearing_pairwise(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1 {
+        return self._inverse_pairwise(s, e, settings).1;
+    }
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfacePairwise for CalculationMethod {
+    fn _inverse_pairwise(
+        &self,
+        s:&dyn LatLngArray,
+        e:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> (F64Array1, F64Array1) {
+        return match self {
+            Self::HAVERSINE => Haversine::inverse_pairwise(s, e, settings),
+            Self::VINCENTY => Vincenty::inverse_pairwise(s, e, settings),
+        };
+    }
+
+    fn _inverse(
+        &self,
+        s_lat:f64,
+        s_lng:f64,
+        e_lat:f64,
+        e_lng:f64,
+        settings: Option<&CalculationSettings>,
+    ) -> (f64, f64) {
+        let (s_lat_r, s_lng_r, e_lat_r, e_lng_r) = (
+            s_lat.to_radians(), s_lng.to_radians(), e_lat.to_radians(), e_lng.to_radians(),
+        );
+
+        return match self {
+            Self::HAVERSINE => Haversine::inverse_rad(s_lat_r, s_lng_r, e_lat_r, e_lng_r, settings),
+            Self::VINCENTY => Vincenty::inverse_rad(s_lat_r, s_lng_r, e_lat_r, e_lng_r, settings),
+        };
+    }
+}
@@ -0,0 +1,145 @@
+/// Proximity graphs among an array of points.
+///
+/// Edges connect pairs of points within a distance of each other, or each point
+/// to its ``k`` nearest neighbours, or both. Like the spanning tree, neighbours
+/// are found through a :class:`~spatial::UnitVectorTree`, so memory usage is
+/// linear to the number of edges rather than quadratic to the number of points.
+///
+/// Graphs are undirected: each edge ``(i, j, distance)`` is listed once with
+/// ``i < j``. :func:`to_csr` expands them into a symmetric sparse adjacency.
+
+use std::cmp;
+
+use ndarray::Array1;
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+};
+
+use crate::spatial::UnitVectorTree;
+
+use super::enums::{
+    CalculationMethod,
+    CalculationSettings,
+};
+use super::neighbours::CalculationInterfaceNeighbours;
+use super::spanning_tree::Edge;
+
+/// Trait for proximity graph methods.
+pub trait CalculationInterfaceProximity {
+    /// Edges among a single array of points.
+    ///
+    /// - With only ``distance``, every pair of points within ``distance`` of each
+    ///   other is connected.
+    /// - With only ``k``, every point is connected to its ``k`` nearest
+    ///   neighbours; a pair is connected if either is among the nearest of the
+    ///   other.
+    /// - With both, every point is connected to those of its ``k`` nearest
+    ///   neighbours that are within ``distance``.
+    ///
+    /// Returns a `Vec` of edges ``(i, j, distance)`` where ``i < j``, sorted by
+    /// ``(i, j)``. With neither ``distance`` nor ``k``, there are no edges.
+    fn _proximity_graph(
+        &self,
+        s:&dyn LatLngArray,
+        distance:Option<f64>,
+        k:Option<usize>,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Edge>;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceProximity for CalculationMethod {
+    fn _proximity_graph(
+        &self,
+        s:&dyn LatLngArray,
+        distance:Option<f64>,
+        k:Option<usize>,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Edge> {
+        let n = s.shape()[0];
+        if n < 2 || (distance.is_none() && k.is_none()) { return Vec::new() }
+
+        let s_owned: F64LatLngArray = s.to_owned();
+        let tree = UnitVectorTree::new(s);
+
+        let workers: usize = settings.unwrap_or(
+            &CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+        let mut edges: Vec<Edge> = {
+            (0..n)
+            .into_par_iter()
+            .with_min_len(chunk_size)
+            .flat_map_iter(
+                | i | {
+                    let neighbours = match (distance, k) {
+                        // Each pair is found from its lower index only.
+                        (Some(distance), None) => self._within_distance_from_tree(
+                            &s_owned.row(i), &s_owned, &tree,
+                            distance,
+                            | j | j > i,
+                            settings,
+                        ),
+                        (distance, Some(k)) => self._nearest_from_tree(
+                            &s_owned.row(i), &s_owned, &tree,
+                            k,
+                            | j | j != i,
+                            settings,
+                        )
+                        .into_iter()
+                        .filter(| (_, d) | distance.map_or(true, | distance | *d <= distance))
+                        .collect(),
+                        (None, None) => Vec::new(),
+                    };
+
+                    neighbours.into_iter()
+                              .map(move | (j, d) | (cmp::min(i, j), cmp::max(i, j), d))
+                }
+            )
+            .collect()
+        };
+
+        edges.sort_by(| a, b | (a.0, a.1).cmp(&(b.0, b.1)));
+        edges.dedup_by(| a, b | (a.0, a.1) == (b.0, b.1));
+
+        return edges;
+    }
+}
+
+/// Expand undirected edges among ``n`` points into a symmetric adjacency in
+/// compressed sparse row format.
+///
+/// Returns a tuple of ``(data, indices, indptr)``: the distances, and column
+/// indices, of the neighbours of row ``i`` are ``data[indptr[i]:indptr[i+1]]`` and
+/// ``indices[indptr[i]:indptr[i+1]]``, in ascending order of column.
+pub fn to_csr(n:usize, edges:&[Edge]) -> (F64Array1, Array1<usize>, Array1<usize>) {
+    let mut rows: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    for (i, j, d) in edges {
+        rows[*i].push((*j, *d));
+        if i != j { rows[*j].push((*i, *d)); }
+    }
+
+    let mut indptr: Vec<usize> = Vec::with_capacity(n + 1);
+    indptr.push(0);
+
+    let mut entries: Vec<(usize, f64)> = Vec::with_capacity(edges.len() * 2);
+    for mut row in rows {
+        row.sort_by(| a, b | a.0.cmp(&b.0));
+        entries.extend(row);
+        indptr.push(entries.len());
+    }
+
+    return (
+        entries.iter().map(| entry | entry.1).collect(),
+        entries.iter().map(| entry | entry.0).collect(),
+        Array1::from_vec(indptr),
+    );
+}
@@ -15,11 +15,13 @@
 /// of each method; thus they need to be sphinx+numpydoc compliant.
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
 use pyo3::types::{
     PyTuple,
 };
 
 use numpy::ndarray::{
+    Array1,
     Ix1,
     Ix2,
 };
@@ -37,24 +39,48 @@ use ndarray_numeric::{
 };
 
 use crate::calc_models::config;
-use super::{enums, CalculationInterfaceInternal};
+use super::{
+    enums,
+    AltitudeMode,
+    CalculationInterfaceAltitude,
+    CalculationInterfaceClustering,
+    CalculationInterfaceInternal,
+    CalculationInterfacePairwise,
+    CalculationInterfaceProximity,
+    CalculationInterfaceRouting,
+    CalculationInterfaceSpanningTree,
+};
+use super::proximity::to_csr;
 
 #[pymethods]
 impl enums::CalculationMethod {
-    #[pyo3(text_signature = "($self, s, e, *, settings)")]
+    #[pyo3(text_signature = "($self, s, e, *, settings, mode)")]
     /// Great-circle distances from a point to an array of lat-long coordinates.
     ///
     /// Parameters
     /// ----------
     /// s: numpy.ndarray
-    ///     Of dimension ``(2)``, e.g. ``numpy.array([51.5072, -0.1276])``.
+    ///     Of dimension ``(2)``, e.g. ``numpy.array([51.5072, -0.1276])``, or ``(3)``
+    ///     with a height.
     ///
     /// e: numpy.ndarray
-    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``;
+    ///     or ``(n, 3)`` in order ``(latitude, longitude, height)``.
     ///
     /// settings: CalculationSettings
     ///     Settings to be passed on to the calculation method.
     ///
+    /// mode: Optional[str]
+    ///     How heights in column 2 of ``(n, 3)`` arrays are combined with the
+    ///     surface distance ``d``, given the height difference ``h``:
+    ///
+    ///     - ``"surface"`` (default): ``d``, ignoring heights,
+    ///     - ``"slant"``: ``sqrt(d**2 + h**2)``, or
+    ///     - ``"surface_climb"``: ``d + abs(h)``.
+    ///
+    ///     Heights must be in the same unit as the distances; arrays of dimension
+    ///     ``(n, 2)`` are taken to be at height ``0``.
+    ///
     /// Returns
     /// -------
     /// numpy.ndarray (dtype=numpy.float64)
@@ -66,12 +92,17 @@ impl enums::CalculationMethod {
         e: &PyArray<f64, Ix2>,
 
         settings: Option< &config::CalculationSettings>,
+        mode: Option<&str>,
         py: Python<'_>,
     ) -> PyResult<PyObject> {
+        check_latlng_columns("e", e)?;
+        let mode = altitude_mode_from_py(mode)?;
+
         let result = {
-            CalculationInterfaceInternal::<f64>::_distance_from_point(
+            CalculationInterfaceAltitude::_distance_from_point_with_altitude(
                 self,
                 &s.to_owned_array(), &e.to_owned_array(),
+                mode,
                 settings,
             )
             .to_pyarray(py)
@@ -80,7 +111,7 @@ impl enums::CalculationMethod {
         return Ok(result.into_py(py));
     }
 
-    #[pyo3(text_signature = "($self, s, e, *, settings)")]
+    #[pyo3(text_signature = "($self, s, e, *, settings, mode)")]
     /// Great-circle distances between two arrays of lat-long coordinates.
     ///
     /// .. note::
@@ -111,14 +142,27 @@ impl enums::CalculationMethod {
     /// Parameters
     /// ----------
     /// s: numpy.ndarray
-    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``;
+    ///     or ``(n, 3)`` in order ``(latitude, longitude, height)``.
     ///
     /// e: numpy.ndarray
-    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``;
+    ///     or ``(n, 3)`` in order ``(latitude, longitude, height)``.
     ///
     /// settings: CalculationSettings
     ///     Settings to be passed on to the calculation method.
     ///
+    /// mode: Optional[str]
+    ///     How heights in column 2 of ``(n, 3)`` arrays are combined with the
+    ///     surface distance ``d``, given the height difference ``h``:
+    ///
+    ///     - ``"surface"`` (default): ``d``, ignoring heights,
+    ///     - ``"slant"``: ``sqrt(d**2 + h**2)``, or
+    ///     - ``"surface_climb"``: ``d + abs(h)``.
+    ///
+    ///     Heights must be in the same unit as the distances; arrays of dimension
+    ///     ``(n, 2)`` are taken to be at height ``0``.
+    ///
     /// Returns
     /// -------
     /// numpy.ndarray (dtype=numpy.float64)
@@ -162,16 +206,21 @@ impl enums::CalculationMethod {
         s: &PyArray<f64, Ix2>,
         e: &PyArray<f64, Ix2>,
         settings: Option< &config::CalculationSettings>,
+        mode: Option<&str>,
         py: Python<'_>,
     ) -> PyResult<PyObject> {
+        check_latlng_columns("s", s)?;
+        check_latlng_columns("e", e)?;
+        let mode = altitude_mode_from_py(mode)?;
 
         let result = if !s.is(e){
             let (s_native, e_native) = (&s.to_owned_array(), &e.to_owned_array());
 
             {
-                CalculationInterfaceInternal::<f64>::_distance(
+                CalculationInterfaceAltitude::_distance_with_altitude(
                     self,
-                    s_native, e_native,
+                    s_native, Some(e_native),
+                    mode,
                     settings,
                 )
                 .to_pyarray(py)
@@ -180,9 +229,10 @@ impl enums::CalculationMethod {
             let s_native = &s.to_owned_array();
 
             {
-                CalculationInterfaceInternal::<f64>::_distance_within_array(
+                CalculationInterfaceAltitude::_distance_with_altitude(
                     self,
-                    s_native,
+                    s_native, None,
+                    mode,
                     settings,
                 )
                 .to_pyarray(py)
@@ -530,29 +580,12 @@ impl enums::CalculationMethod {
     /// Indices of all points from ``s`` is within ``distance`` of that of ``e``.
     ///
     /// .. note::
-    ///     This function will check if ``s`` and ``e`` are identical (i.e. Python
-    ///     ``s is e``); if it is, it will not calculate the upper half of the result
-    ///     array above the diagonal, and simply mirror the bottom half over. This
-    ///     effectively halves the calculations.
-    ///
-    ///     For this reason, if you are to pair each coordinates of an array with
-    ///     every other within the same array, make sure an **identical** object is
-    ///     passed to both ``s`` and ``e``:
-    ///
-    ///         >>> # Assume s is some numpy.ndarray of shape (n, 2).
-    ///         >>>
-    ///         >>> from rust_geodistances import haversine
-    ///         >>>
-    ///         >>> # Fast
-    ///         >>> result = haversine.indices_within_distance(s, s)
-    ///         >>>
-    ///         >>> # Fast
-    ///         >>> e = s
-    ///         >>> result = haversine.indices_within_distance(s, e)
-    ///         >>>
-    ///         >>> # Slow
-    ///         >>> e = s.copy()
-    ///         >>> result = haversine.indices_within_distance(s, e)
+    ///     Points of ``e`` are indexed by hierarchical cells (see
+    ///     :mod:`rust_geodistances.cells`), and only those within cells covering
+    ///     the circle of ``distance`` around each ``s[i]`` have their distances
+    ///     calculated. No ``(n, m)`` array is allocated, so unlike
+    ///     :meth:`within_distance`, there is no need to pass an identical object
+    ///     to ``s`` and ``e`` for speed.
     ///
     /// Parameters
     /// ----------
@@ -616,42 +649,577 @@ impl enums::CalculationMethod {
         py: Python<'_>,
     ) -> PyResult<PyObject> {
 
-        let result = if !s.is(e){
-            let (s_native, e_native) = (&s.to_owned_array(), &e.to_owned_array());
+        let result = PyTuple::new(
+            py,
+            CalculationInterfaceInternal::<f64>::_indices_within_distance(
+                self,
+                &s.to_owned_array(), &e.to_owned_array(),
+                distance,
+                settings,
+            )
+            .into_iter()
+            .map(
+                |indices| indices.to_pyarray(py)
+            )
+        );
 
-            PyTuple::new(
-                py,
-                CalculationInterfaceInternal::<f64>::_within_distance(
-                    self,
-                    s_native, e_native,
-                    distance,
-                    settings,
-                )
-                .rows()
-                .into_iter()
-                .map(
-                    |row| row.indices().to_pyarray(py)
-                )
+        return Ok(result.into_py(py));
+    }
+
+    #[pyo3(text_signature = "($self, s, *, settings)")]
+    /// Minimum spanning tree among an array of lat-long coordinates.
+    ///
+    /// The tree is built using Borůvka's algorithm over a spatial index, so that
+    /// unlike :meth:`distance`, no ``(n, n)`` array is ever allocated. This allows
+    /// spanning trees over hundreds of thousands of points to be calculated.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]
+    ///     ``(i, j, distance)``, each of dimension ``(n-1)``, sorted by ascending
+    ///     ``distance``. Each edge of the tree connects ``s[i[k]]`` to
+    ///     ``s[j[k]]`` with a great-circle distance of ``distance[k]``.
+    ///
+    /// Example
+    /// -------
+    /// Total length of a network connecting all points::
+    ///
+    ///     >>> from rust_geodistances import haversine
+    ///     >>> i, j, distance = haversine.minimum_spanning_tree(sn)
+    ///     >>> distance.sum()
+    fn minimum_spanning_tree(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let edges = CalculationInterfaceSpanningTree::_minimum_spanning_tree(
+            self,
+            &s.to_owned_array(),
+            settings,
+        );
+
+        let i: Array1<usize> = edges.iter().map(| edge | edge.0).collect();
+        let j: Array1<usize> = edges.iter().map(| edge | edge.1).collect();
+        let distance: Array1<f64> = edges.iter().map(| edge | edge.2).collect();
+
+        return Ok(
+            (
+                i.to_pyarray(py),
+                j.to_pyarray(py),
+                distance.to_pyarray(py),
+            ).into_py(py)
+        );
+    }
+
+    #[pyo3(text_signature = "($self, s, *, settings)")]
+    /// Single-linkage hierarchical clustering of an array of lat-long coordinates.
+    ///
+    /// This is derived from :meth:`minimum_spanning_tree`, and shares its memory
+    /// characteristics.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray (dtype=numpy.float64)
+    ///     Dimension ``(n-1, 4)``. A linkage matrix in the same format as
+    ///     :func:`scipy.cluster.hierarchy.linkage`; it can be passed directly to
+    ///     functions such as :func:`scipy.cluster.hierarchy.fcluster` and
+    ///     :func:`scipy.cluster.hierarchy.dendrogram`.
+    fn single_linkage(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let result = {
+            CalculationInterfaceSpanningTree::_single_linkage(
+                self,
+                &s.to_owned_array(),
+                settings,
             )
-        } else {
-            let s_native = &s.to_owned_array();
+            .to_pyarray(py)
+        };
 
-            PyTuple::new(
-                py,
-                CalculationInterfaceInternal::<f64>::_within_distance_among_array(
-                    self,
-                    s_native,
-                    distance,
-                    settings,
-                )
-                .rows()
-                .into_iter()
-                .map(
-                    |row| row.indices().to_pyarray(py)
-                )
+        return Ok(result.into_py(py));
+    }
+
+    #[pyo3(text_signature = "($self, s, distance, k, format, *, settings)")]
+    /// Proximity graph among an array of lat-long coordinates.
+    ///
+    /// Like :meth:`minimum_spanning_tree`, neighbours are found through a spatial
+    /// index, so no ``(n, n)`` array is ever allocated.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// distance: Optional[numpy.float64]
+    ///     Connect all pairs of points within this distance of each other.
+    ///     The unit of this must be the same as that of:
+    ///
+    ///     - :attr:`CalculationSettings.spherical_radius` or
+    ///     - :attr:`CalculationSettings.ellipse_a` and
+    ///     - :attr:`CalculationSettings.ellipse_b` and
+    ///     - :attr:`CalculationSettings.ellipse_f`
+    ///
+    ///     whichever used by in this method.
+    ///
+    /// k: Optional[int]
+    ///     Connect each point to its ``k`` nearest neighbours; a pair is connected
+    ///     if either is among the nearest of the other. If ``distance`` is also
+    ///     provided, only neighbours within ``distance`` are connected.
+    ///
+    ///     At least one of ``distance`` and ``k`` must be provided.
+    ///
+    /// format: Optional[str]
+    ///     Either:
+    ///
+    ///     - ``"edges"`` (default): an edge list, or
+    ///     - ``"csr"``: a symmetric adjacency in compressed sparse row format.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]
+    ///     For ``"edges"``, ``(i, j, distance)`` each of dimension ``(m)``, sorted
+    ///     by ``(i, j)``. Each edge connects ``s[i[k]]`` to ``s[j[k]]`` where
+    ///     ``i[k] < j[k]``, with a distance of ``distance[k]``.
+    ///
+    ///     For ``"csr"``, ``(data, indices, indptr)`` as accepted by
+    ///     :class:`scipy.sparse.csr_matrix`, listing each edge in both directions.
+    ///     Edges between identical points are stored as explicit zeros.
+    ///
+    /// Example
+    /// -------
+    /// Graphs of all points within 500 metres of each other::
+    ///
+    ///     >>> import networkx as nx
+    ///     >>> from scipy.sparse import csr_matrix
+    ///     >>> from rust_geodistances import haversine
+    ///     >>> i, j, d = haversine.build_graph(sn, 0.5)
+    ///     >>> g = nx.Graph()
+    ///     >>> g.add_weighted_edges_from(zip(i, j, d))
+    ///     >>> data, indices, indptr = haversine.build_graph(sn, 0.5, format="csr")
+    ///     >>> adjacency = csr_matrix((data, indices, indptr), shape=(len(sn), len(sn)))
+    fn build_graph(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        distance: Option<f64>,
+        k: Option<usize>,
+        format: Option<&str>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        if distance.is_none() && k.is_none() {
+            return Err(PyValueError::new_err("At least one of `distance` and `k` must be provided."));
+        }
+
+        let s_owned = s.to_owned_array();
+        let edges = CalculationInterfaceProximity::_proximity_graph(
+            self,
+            &s_owned,
+            distance,
+            k,
+            settings,
+        );
+
+        return match format.unwrap_or("edges") {
+            "edges" => {
+                let i: Array1<usize> = edges.iter().map(| edge | edge.0).collect();
+                let j: Array1<usize> = edges.iter().map(| edge | edge.1).collect();
+                let distance: Array1<f64> = edges.iter().map(| edge | edge.2).collect();
+
+                Ok((i.to_pyarray(py), j.to_pyarray(py), distance.to_pyarray(py)).into_py(py))
+            },
+            "csr" => {
+                let (data, indices, indptr) = to_csr(s_owned.shape()[0], &edges);
+
+                Ok((data.to_pyarray(py), indices.to_pyarray(py), indptr.to_pyarray(py)).into_py(py))
+            },
+            other => Err(PyValueError::new_err(
+                format!("Unknown format {:?}; expected \"edges\" or \"csr\".", other)
+            )),
+        };
+    }
+
+    #[pyo3(text_signature = "($self, s, start, return_to_start, *, settings)")]
+    /// Order in which to visit an array of lat-long coordinates.
+    ///
+    /// A heuristic solution to the travelling-salesman problem: the route is
+    /// constructed by visiting the nearest unvisited point each time, then improved
+    /// with 2-opt and Or-opt moves until no move can shorten it further.
+    ///
+    /// .. note::
+    ///     This method calculates the full ``(n, n)`` distance array among ``s``;
+    ///     it is intended for routes of up to a few thousand points.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// start: Optional[int]
+    ///     Index of the point in ``s`` to start from. Defaults to ``0``.
+    ///
+    /// return_to_start: Optional[bool]
+    ///     Whether the route returns to ``start`` at the end. Defaults to ``True``.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.float64]
+    ///     ``(order, length)``; ``order`` is of dimension ``(n)`` and ``dtype``
+    ///     ``numpy.uint64``, containing the indices of ``s`` in visiting order
+    ///     starting with ``start``. ``length`` is the total great-circle length
+    ///     of the route.
+    fn route_order(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        start: Option<usize>,
+        return_to_start: Option<bool>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let start = start.unwrap_or(0);
+        let n = s.shape()[0];
+
+        if n > 0 && start >= n {
+            return Err(PyValueError::new_err(
+                format!("`start` must be an index of `s`, yet {:?} provided for {:?} points.", start, n)
+            ));
+        }
+
+        let (order, length) = CalculationInterfaceRouting::_route_order(
+            self,
+            &s.to_owned_array(),
+            start,
+            return_to_start.unwrap_or(true),
+            settings,
+        );
+
+        return Ok(
+            (
+                Array1::from_vec(order).to_pyarray(py),
+                length,
+            ).into_py(py)
+        );
+    }
+
+    #[pyo3(text_signature = "($self, s, k, seed, *, settings)")]
+    /// Partition an array of lat-long coordinates into ``k`` clusters around medoids.
+    ///
+    /// Arrays of up to 2048 points are clustered with PAM (Partitioning Around
+    /// Medoids) over the full distance array. Longer arrays are clustered with
+    /// CLARA, which runs PAM on several random samples and keeps the medoids with
+    /// the lowest total distance over the whole array.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// k: int
+    ///     Number of clusters, between ``1`` and ``n``.
+    ///
+    /// seed: Optional[int]
+    ///     Seed for the random samples drawn by CLARA. Has no effect on arrays
+    ///     short enough for PAM.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.ndarray, numpy.float64]
+    ///     ``(medoids, labels, cost)``:
+    ///
+    ///     - ``medoids`` of dimension ``(k)``, the indices of ``s`` chosen as
+    ///       medoids,
+    ///     - ``labels`` of dimension ``(n)``, the cluster of each point in ``s``
+    ///       as an index of ``medoids``, and
+    ///     - ``cost``, the total great-circle distance of all points to their
+    ///       medoids.
+    fn cluster_kmedoids(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        k: usize,
+        seed: Option<u64>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let n = s.shape()[0];
+
+        if k == 0 || k > n {
+            return Err(PyValueError::new_err(
+                format!("`k` must be between 1 and the number of points {:?}, yet {:?} provided.", n, k)
+            ));
+        }
+
+        let (medoids, labels, cost) = CalculationInterfaceClustering::_cluster_kmedoids(
+            self,
+            &s.to_owned_array(),
+            k,
+            seed,
+            settings,
+        );
+
+        return Ok(
+            (
+                Array1::from_vec(medoids).to_pyarray(py),
+                Array1::from_vec(labels).to_pyarray(py),
+                cost,
+            ).into_py(py)
+        );
+    }
+
+    #[pyo3(text_signature = "($self, candidates, demand, k, weights, *, settings)")]
+    /// Choose ``k`` facility locations from ``candidates`` to serve ``demand``.
+    ///
+    /// Facilities are added one at a time, each time choosing the candidate that
+    /// reduces the total weighted great-circle distance from ``demand`` to their
+    /// closest facility the most.
+    ///
+    /// .. note::
+    ///     This method calculates the full ``(n, m)`` distance array between
+    ///     ``demand`` and ``candidates``.
+    ///
+    /// Parameters
+    /// ----------
+    /// candidates: numpy.ndarray
+    ///     Of dimension ``(m, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// demand: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// k: int
+    ///     Number of facilities to choose, between ``1`` and ``m``.
+    ///
+    /// weights: Optional[numpy.ndarray]
+    ///     Of dimension ``(n)``. Weight of each point in ``demand``, e.g. the number
+    ///     of deliveries. Defaults to equal weights.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.ndarray, numpy.float64]
+    ///     ``(facilities, assignment, cost)``:
+    ///
+    ///     - ``facilities`` of dimension ``(k)``, the indices of ``candidates``
+    ///       chosen, in the order they were chosen,
+    ///     - ``assignment`` of dimension ``(n)``, the closest facility of each
+    ///       point in ``demand`` as an index of ``facilities``, and
+    ///     - ``cost``, the total weighted great-circle distance of ``demand`` to
+    ///       their closest facilities.
+    fn facility_location(
+        &self,
+        candidates: &PyArray<f64, Ix2>,
+        demand: &PyArray<f64, Ix2>,
+        k: usize,
+        weights: Option<&PyArray<f64, Ix1>>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let m = candidates.shape()[0];
+
+        if k == 0 || k > m {
+            return Err(PyValueError::new_err(
+                format!("`k` must be between 1 and the number of candidates {:?}, yet {:?} provided.", m, k)
+            ));
+        }
+
+        let weights = weights.map(| weights | weights.to_owned_array());
+        if let Some(weights) = &weights {
+            if weights.len() != demand.shape()[0] {
+                return Err(PyValueError::new_err(
+                    "`weights` must be of the same length as `demand`."
+                ));
+            }
+        }
+
+        let (facilities, assignment, cost) = CalculationInterfaceClustering::_facility_location(
+            self,
+            &candidates.to_owned_array(),
+            &demand.to_owned_array(),
+            k,
+            weights.as_ref(),
+            settings,
+        );
+
+        return Ok(
+            (
+                Array1::from_vec(facilities).to_pyarray(py),
+                Array1::from_vec(assignment).to_pyarray(py),
+                cost,
+            ).into_py(py)
+        );
+    }
+
+    #[pyo3(text_signature = "($self, s, e, *, settings, mode)")]
+    /// Great-circle distances between corresponding rows of two arrays of lat-long
+    /// coordinates.
+    ///
+    /// Unlike :meth:`distance`, which calculates the distance from every point of
+    /// ``s`` to every point of ``e``, this pairs ``s[i]`` with ``e[i]`` only; memory
+    /// usage is linear to the number of rows.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``;
+    ///     or ``(n, 3)`` in order ``(latitude, longitude, height)``.
+    ///
+    /// e: numpy.ndarray
+    ///     Of the same dimension as ``s``.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// mode: Optional[str]
+    ///     How heights in column 2 of ``(n, 3)`` arrays are combined with the
+    ///     surface distance ``d``, given the height difference ``h``:
+    ///
+    ///     - ``"surface"`` (default): ``d``, ignoring heights,
+    ///     - ``"slant"``: ``sqrt(d**2 + h**2)``, or
+    ///     - ``"surface_climb"``: ``d + abs(h)``.
+    ///
+    ///     Heights must be in the same unit as the distances; arrays of dimension
+    ///     ``(n, 2)`` are taken to be at height ``0``.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray (dtype=numpy.float64)
+    ///     Dimension ``(n)``, the great-circle distance from ``s[i]`` to ``e[i]``.
+    fn distance_pairwise(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        e: &PyArray<f64, Ix2>,
+        settings: Option<&config::CalculationSettings>,
+        mode: Option<&str>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        check_pairwise_shapes(s, e)?;
+        check_latlng_columns("s", s)?;
+        let mode = altitude_mode_from_py(mode)?;
+
+        let result = {
+            CalculationInterfaceAltitude::_distance_pairwise_with_altitude(
+                self,
+                &s.to_owned_array(), &e.to_owned_array(),
+                mode,
+                settings,
             )
+            .to_pyarray(py)
         };
 
         return Ok(result.into_py(py));
     }
+
+    #[pyo3(text_signature = "($self, s, e, *, settings)")]
+    /// Initial bearings between corresponding rows of two arrays of lat-long
+    /// coordinates.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// e: numpy.ndarray
+    ///     Of the same dimension as ``s``.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray (dtype=numpy.float64)
+    ///     Dimension ``(n)``, the bearing in degrees at ``s[i]`` towards ``e[i]``,
+    ///     between 0º and 360º with 0º being due North. The bearing between
+    ///     coincident points is 0º.
+    ///
+    /// Example
+    /// -------
+    /// Headings along a track of consecutive points::
+    ///
+    ///     >>> from rust_geodistances import vincenty
+    ///     >>> vincenty.bearing_pairwise(track[:-1], track[1:])
+    fn bearing_pairwise(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        e: &PyArray<f64, Ix2>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        check_pairwise_shapes(s, e)?;
+
+        let result = {
+            CalculationInterfacePairwise::_bearing_pairwise(
+                self,
+                &s.to_owned_array(), &e.to_owned_array(),
+                settings,
+            )
+            .to_pyarray(py)
+        };
+
+        return Ok(result.into_py(py));
+    }
+}
+
+/// Check that ``s`` and ``e`` can be paired up row by row.
+fn check_pairwise_shapes(
+    s: &PyArray<f64, Ix2>,
+    e: &PyArray<f64, Ix2>,
+) -> PyResult<()> {
+    if s.shape() != e.shape() {
+        return Err(PyValueError::new_err(
+            format!("`s` and `e` must be of the same shape, yet {:?} and {:?} provided.", s.shape(), e.shape())
+        ));
+    }
+
+    return Ok(());
+}
+
+/// Check that an array is of dimension ``(n, 2)``, or ``(n, 3)`` with heights.
+pub(crate) fn check_latlng_columns(
+    name: &str,
+    s: &PyArray<f64, Ix2>,
+) -> PyResult<()> {
+    return match s.shape()[1] {
+        2 | 3 => Ok(()),
+        k => Err(PyValueError::new_err(
+            format!("`{}` must be of dimension (n, 2) or (n, 3), yet (n, {:?}) provided.", name, k)
+        )),
+    };
+}
+
+/// Parse the ``mode`` of the distance methods; defaults to ``"surface"``.
+fn altitude_mode_from_py(
+    mode: Option<&str>,
+) -> PyResult<AltitudeMode> {
+    return match mode {
+        Some(mode) => mode.parse().map_err(PyValueError::new_err),
+        None => Ok(AltitudeMode::default()),
+    };
 }
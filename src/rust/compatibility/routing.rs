@@ -0,0 +1,242 @@
+/// Travelling-salesman route ordering.
+///
+/// This is a heuristic for the simple case of sequencing a few hundred stops: a
+/// route is first constructed by repeatedly visiting the nearest unvisited point,
+/// then improved by 2-opt (reversing a section of the route) and Or-opt (moving a
+/// section of up to 3 points elsewhere) until no move shortens the route.
+///
+/// The full ``(n, n)`` distance array is calculated up front via
+/// :meth:`_distance_within_array`, so this is not intended for large arrays.
+
+use ndarray_numeric::{
+    F64Array2,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+};
+
+use super::enums::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+/// Maximum length of a section of route moved by Or-opt.
+pub const OR_OPT_MAX_SEGMENT:usize = 3;
+
+/// Trait for route ordering methods.
+pub trait CalculationInterfaceRouting {
+    /// Order in which to visit all points in ``s``, starting from ``s[start]``.
+    ///
+    /// If ``return_to_start`` is ``true``, the route is a closed tour, and its
+    /// length includes the leg from the last point back to ``start``.
+    ///
+    /// Returns a tuple of the indices of ``s`` in visiting order, and the total
+    /// length of the route.
+    fn _route_order(
+        &self,
+        s:&dyn LatLngArray,
+        start:usize,
+        return_to_start:bool,
+        settings: Option<&CalculationSettings>,
+    ) -> (Vec<usize>, f64);
+}
+
+/// Length of a route given a distance array among its points.
+pub fn route_length(
+    route:&[usize],
+    distances:&F64Array2,
+    return_to_start:bool,
+) -> f64 {
+    let mut total: f64 = route.windows(2)
+                              .map(| leg | distances[[leg[0], leg[1]]])
+                              .sum();
+
+    if return_to_start && route.len() > 1 {
+        total += distances[[route[route.len()-1], route[0]]];
+    }
+
+    return total;
+}
+
+/// The point after position ``pos``, or `None` if ``pos`` is the end of an open route.
+fn next_of(
+    route:&[usize],
+    pos:usize,
+    closed:bool,
+) -> Option<usize> {
+    return if pos+1 < route.len() {
+        Some(route[pos+1])
+    } else if closed {
+        Some(route[0])
+    } else {
+        None
+    };
+}
+
+/// Route constructed by always visiting the nearest unvisited point next.
+fn nearest_neighbour_route(
+    distances:&F64Array2,
+    start:usize,
+) -> Vec<usize> {
+    let n = distances.shape()[0];
+    let mut visited = vec![false; n];
+    let mut route = Vec::with_capacity(n);
+
+    let mut current = start;
+    visited[current] = true;
+    route.push(current);
+
+    while route.len() < n {
+        let next = (0..n).filter(| idx | !visited[*idx])
+                         .min_by(
+                            | a, b | distances[[current, *a]].total_cmp(&distances[[current, *b]])
+                         )
+                         .unwrap();
+
+        visited[next] = true;
+        route.push(next);
+        current = next;
+    }
+
+    return route;
+}
+
+/// One pass of 2-opt over the route. Returns whether the route was improved.
+fn two_opt(
+    route:&mut Vec<usize>,
+    distances:&F64Array2,
+    closed:bool,
+    eps:f64,
+) -> bool {
+    let n = route.len();
+    let mut improved = false;
+
+    for i in 1..n.saturating_sub(1) {
+        for j in i+1..n {
+            let (a, b, c) = (route[i-1], route[i], route[j]);
+            let next = next_of(route, j, closed);
+
+            let before = distances[[a, b]] + next.map_or(0., | e | distances[[c, e]]);
+            let after = distances[[a, c]] + next.map_or(0., | e | distances[[b, e]]);
+
+            if after < before - eps {
+                route[i..=j].reverse();
+                improved = true;
+            }
+        }
+    }
+
+    return improved;
+}
+
+/// One pass of Or-opt over the route. Returns whether the route was improved.
+fn or_opt(
+    route:&mut Vec<usize>,
+    distances:&F64Array2,
+    closed:bool,
+    eps:f64,
+) -> bool {
+    let mut improved = false;
+
+    for segment_len in 1..=OR_OPT_MAX_SEGMENT {
+        let mut i = 1;
+
+        while i + segment_len <= route.len() {
+            let (first, last) = (route[i], route[i+segment_len-1]);
+            let prev = route[i-1];
+            let next = next_of(route, i+segment_len-1, closed);
+
+            let removal_gain = {
+                distances[[prev, first]]
+                + next.map_or(0., | e | distances[[last, e]])
+                - next.map_or(0., | e | distances[[prev, e]])
+            };
+
+            let mut rest: Vec<usize> = route[..i].iter()
+                                                 .chain(route[i+segment_len..].iter())
+                                                 .cloned()
+                                                 .collect();
+
+            // Cheapest position to re-insert the segment, i.e. after `rest[pos]`.
+            let mut best: Option<(f64, usize, bool)> = None;
+            for pos in 0..rest.len() {
+                if pos == i-1 { continue }
+
+                let a = rest[pos];
+                let b = next_of(&rest, pos, closed);
+
+                for reversed in [false, true] {
+                    let (f, l) = if reversed { (last, first) } else { (first, last) };
+
+                    let insertion_cost = {
+                        distances[[a, f]]
+                        + b.map_or(0., | b | distances[[l, b]])
+                        - b.map_or(0., | b | distances[[a, b]])
+                    };
+
+                    if insertion_cost < removal_gain - eps
+                    && best.map_or(true, | (cost, _, _) | insertion_cost < cost) {
+                        best = Some((insertion_cost, pos, reversed));
+                    }
+                }
+            }
+
+            if let Some((_, pos, reversed)) = best {
+                let mut segment = route[i..i+segment_len].to_vec();
+                if reversed { segment.reverse() }
+
+                for (offset, idx) in segment.into_iter().enumerate() {
+                    rest.insert(pos+1+offset, idx);
+                }
+                *route = rest;
+                improved = true;
+            }
+
+            i += 1;
+        }
+    }
+
+    return improved;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceRouting for CalculationMethod {
+    fn _route_order(
+        &self,
+        s:&dyn LatLngArray,
+        start:usize,
+        return_to_start:bool,
+        settings: Option<&CalculationSettings>,
+    ) -> (Vec<usize>, f64) {
+        let n = s.shape()[0];
+
+        if n == 0 { return (Vec::new(), 0.) }
+
+        assert!(start < n, "`start` must be an index of `s`, yet {:?} provided for {:?} points.", start, n);
+
+        let settings_default = &CalculationSettings::default();
+        let eps:f64 = settings.unwrap_or(settings_default).eps;
+        let max_iterations:usize = settings.unwrap_or(settings_default).max_iterations;
+
+        let distances = CalculationInterfaceInternal::<f64>::_distance_within_array(
+            self,
+            s,
+            settings,
+        );
+
+        let mut route = nearest_neighbour_route(&distances, start);
+
+        for _ in 0..max_iterations {
+            let improved_two_opt = two_opt(&mut route, &distances, return_to_start, eps);
+            let improved_or_opt = or_opt(&mut route, &distances, return_to_start, eps);
+
+            if !(improved_two_opt || improved_or_opt) { break }
+        }
+
+        let total = route_length(&route, &distances, return_to_start);
+
+        return (route, total);
+    }
+}
@@ -0,0 +1,185 @@
+/// Minimum spanning trees and single-linkage hierarchies.
+///
+/// The spanning tree is built with Borůvka's algorithm: in each round, every
+/// component finds its closest point outside of itself and the components are
+/// merged along those edges. The closest points are found through a
+/// :class:`~spatial::UnitVectorTree`, so no ``(n, n)`` distance array is ever
+/// allocated; memory usage is linear to the number of points. Each round labels
+/// the subtrees lying within a single component, so searches skip their own
+/// component instead of walking through it.
+
+use std::cmp;
+
+use ndarray::Array2;
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLngArray,
+};
+
+use crate::data::DisjointSet;
+use crate::spatial::UnitVectorTree;
+
+use super::enums::{
+    CalculationMethod,
+    CalculationSettings,
+};
+use super::neighbours::CalculationInterfaceNeighbours;
+
+/// Edge of a graph, in the form of ``(i, j, distance)``.
+pub type Edge = (usize, usize, f64);
+
+/// Trait for spanning tree methods.
+pub trait CalculationInterfaceSpanningTree {
+    /// Minimum spanning tree among a single array of points.
+    ///
+    /// Returns a `Vec` of ``n-1`` edges ``(i, j, distance)`` where ``i < j``,
+    /// sorted by ascending distance.
+    fn _minimum_spanning_tree(
+        &self,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Edge>;
+
+    /// Single-linkage hierarchical clustering among a single array of points.
+    ///
+    /// Returns a linkage matrix of shape ``(n-1, 4)`` in the same format as
+    /// ``scipy.cluster.hierarchy.linkage``: each row ``k`` merges clusters
+    /// ``row[0]`` and ``row[1]`` at a distance of ``row[2]`` into a new cluster
+    /// ``n+k`` containing ``row[3]`` original points.
+    fn _single_linkage(
+        &self,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array2;
+}
+
+/// *See trait for method descriptions.*
+impl CalculationInterfaceSpanningTree for CalculationMethod {
+    fn _minimum_spanning_tree(
+        &self,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Edge> {
+        let n = s.shape()[0];
+        let mut edges: Vec<Edge> = Vec::with_capacity(n.saturating_sub(1));
+
+        if n < 2 { return edges }
+
+        let s_owned: F64LatLngArray = s.to_owned();
+        let tree = UnitVectorTree::new(s);
+        let mut components = DisjointSet::new(n);
+
+        let workers: usize = settings.unwrap_or(
+            &CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = (n as f32 / workers as f32).ceil() as usize;
+
+        while edges.len() < n-1 {
+            let labels: Vec<usize> = (0..n).map(| i | components.find(i)).collect();
+
+            // Subtrees entirely within one component are skipped whole by the
+            // points of that component, rather than rejected point by point; late
+            // rounds would otherwise walk most of their own large components.
+            let uniform: Vec<Option<usize>> = tree.subtree_labels(&labels);
+
+            // Closest point in another component, for each point.
+            let candidates: Vec<Edge> = {
+                (0..n)
+                .into_par_iter()
+                .step_by(chunk_size)
+                .flat_map_iter(
+                    | start | {
+                        let labels = &labels;
+                        let uniform = &uniform;
+                        let s_owned = &s_owned;
+                        let tree = &tree;
+
+                        (start..cmp::min(start+chunk_size, n))
+                        .filter_map(
+                            move | i | {
+                                self._nearest_from_tree_skipping(
+                                    &s_owned.row(i), s_owned, tree,
+                                    1,
+                                    | j | labels[j] != labels[i],
+                                    | node | uniform[node] == Some(labels[i]),
+                                    settings,
+                                )
+                                .first()
+                                .map(| (j, d) | (cmp::min(i, *j), cmp::max(i, *j), *d))
+                            }
+                        )
+                    }
+                )
+                .collect()
+            };
+
+            // Cheapest edge out of each component; ties are broken by the indices
+            // so that all components agree on the same edge.
+            let mut cheapest: Vec<Option<Edge>> = vec![None; n];
+            for edge in candidates {
+                for label in [labels[edge.0], labels[edge.1]] {
+                    let replace = match cheapest[label] {
+                        None => true,
+                        Some(current) => {
+                            edge.2.total_cmp(&current.2)
+                                  .then((edge.0, edge.1).cmp(&(current.0, current.1)))
+                                  .is_lt()
+                        },
+                    };
+
+                    if replace { cheapest[label] = Some(edge) }
+                }
+            }
+
+            let merged_count = edges.len();
+            for edge in cheapest.into_iter().flatten() {
+                if components.union(edge.0, edge.1).is_some() {
+                    edges.push(edge);
+                }
+            }
+
+            // Only possible with non-finite coordinates; bail out rather than loop.
+            if edges.len() == merged_count { break }
+        }
+
+        edges.sort_by(| a, b | a.2.total_cmp(&b.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+
+        return edges;
+    }
+
+    fn _single_linkage(
+        &self,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array2 {
+        let n = s.shape()[0];
+        let edges = self._minimum_spanning_tree(s, settings);
+
+        let mut clusters = DisjointSet::new(n);
+        let mut cluster_ids: Vec<usize> = (0..n).collect();
+        let mut linkage = Array2::<f64>::zeros((edges.len(), 4));
+
+        // Edges are already sorted by distance.
+        for (k, (i, j, d)) in edges.into_iter().enumerate() {
+            let (root_i, root_j) = (clusters.find(i), clusters.find(j));
+            let (id_i, id_j) = (cluster_ids[root_i], cluster_ids[root_j]);
+
+            let root = clusters.union(root_i, root_j).unwrap();
+            cluster_ids[root] = n + k;
+
+            let mut row = linkage.row_mut(k);
+            row[0] = cmp::min(id_i, id_j) as f64;
+            row[1] = cmp::max(id_i, id_j) as f64;
+            row[2] = d;
+            row[3] = clusters.size(root) as f64;
+        }
+
+        return linkage;
+    }
+}
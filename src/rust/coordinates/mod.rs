@@ -0,0 +1,399 @@
+/// Parsing and formatting of human readable coordinates.
+///
+/// Each latitude or longitude may be written as:
+///
+/// - signed decimal degrees, e.g. ``-0.1276``,
+/// - degrees and decimal minutes (DDM), e.g. ``51 30.433`` or ``51°30.433'``, or
+/// - degrees, minutes and decimal seconds (DMS), e.g. ``51°30'26"``,
+///
+/// optionally with a hemisphere letter ``N``, ``S``, ``E`` or ``W`` before or after
+/// it, e.g. ``N51.5072`` or ``0°7'39"W``. Unit markers may be any of ``°º˚``,
+/// ``'′’`` and ``"″”``. A pair of latitude and longitude may be separated by a comma,
+/// semicolon or whitespace; if hemisphere letters are provided, they may be in
+/// either order.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ndarray::Axis;
+use rayon::prelude::*;
+
+use crate::calc_models::traits::LatLngArray;
+
+// Import this if you want the coordinates module to be available in Python.
+pub mod python;
+
+/// Errors from parsing coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CoordinateError {
+    /// A character that cannot be part of a coordinate.
+    UnexpectedCharacter(char),
+
+    /// The text cannot be interpreted as a coordinate.
+    Malformed(String),
+
+    /// A latitude beyond ±90º, a longitude beyond ±180º, or minutes or seconds not
+    /// between 0 and 60.
+    OutOfRange(String),
+}
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::UnexpectedCharacter(c) => write!(f, "{:?} is not a valid character in a coordinate.", c),
+            Self::Malformed(msg) => write!(f, "Malformed coordinate: {}", msg),
+            Self::OutOfRange(msg) => write!(f, "Coordinate out of range: {}", msg),
+        };
+    }
+}
+impl std::error::Error for CoordinateError {}
+
+/// Hemisphere letter of a coordinate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hemisphere {
+    N,
+    S,
+    E,
+    W,
+}
+impl Hemisphere {
+    fn from_char(c:char) -> Option<Self> {
+        return match c.to_ascii_uppercase() {
+            'N' => Some(Self::N),
+            'S' => Some(Self::S),
+            'E' => Some(Self::E),
+            'W' => Some(Self::W),
+            _ => None,
+        };
+    }
+
+    /// Whether this is a hemisphere of latitudes rather than longitudes.
+    pub fn is_latitude(&self) -> bool {
+        return matches!(self, Self::N | Self::S);
+    }
+
+    /// Sign of coordinates in this hemisphere.
+    pub fn sign(&self) -> f64 {
+        return match self {
+            Self::N | Self::E => 1.,
+            Self::S | Self::W => -1.,
+        };
+    }
+}
+
+/// Degree, minute or second marker following a number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Unit {
+    Degree,
+    Minute,
+    Second,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number {
+        value: f64,
+        negative: bool,
+        integral: bool,
+        unit: Option<Unit>,
+    },
+    Hemisphere(Hemisphere),
+    Separator,
+}
+
+/// Split a text into tokens.
+fn tokenize(text:&str) -> Result<Vec<Token>, CoordinateError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c == ',' || c == ';' {
+            tokens.push(Token::Separator);
+            pos += 1;
+        } else if let Some(hemisphere) = Hemisphere::from_char(c) {
+            tokens.push(Token::Hemisphere(hemisphere));
+            pos += 1;
+        } else if c.is_ascii_digit() || c == '.' || "+-−".contains(c) {
+            let negative = c == '-' || c == '−';
+            if "+-−".contains(c) { pos += 1; }
+
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') { pos += 1; }
+            let digits: String = chars[start..pos].iter().collect();
+
+            let value: f64 = digits.parse().map_err(
+                | _ | CoordinateError::Malformed(format!("{:?} is not a number.", digits))
+            )?;
+
+            while pos < chars.len() && chars[pos].is_whitespace() { pos += 1; }
+            let unit = match chars.get(pos) {
+                Some('°' | 'º' | '˚') => Some(Unit::Degree),
+                Some('"' | '″' | '”') => Some(Unit::Second),
+                Some('\'' | '′' | '’') if chars.get(pos+1) == Some(&'\'') => { pos += 1; Some(Unit::Second) },
+                Some('\'' | '′' | '’') => Some(Unit::Minute),
+                _ => None,
+            };
+            if unit.is_some() { pos += 1; }
+
+            tokens.push(Token::Number { value, negative, integral: !digits.contains('.'), unit });
+        } else {
+            return Err(CoordinateError::UnexpectedCharacter(c));
+        }
+    }
+
+    return Ok(tokens);
+}
+
+/// Convert the tokens of a single latitude or longitude into signed degrees.
+fn angle_from_tokens(tokens:&[Token]) -> Result<(f64, Option<Hemisphere>), CoordinateError> {
+    let mut hemisphere = None;
+    let mut numbers = Vec::new();
+
+    for (k, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Hemisphere(h) if hemisphere.is_none() && (k == 0 || k == tokens.len()-1) => hemisphere = Some(*h),
+            Token::Hemisphere(_) => return Err(CoordinateError::Malformed("misplaced hemisphere letter.".to_string())),
+            Token::Number { .. } => numbers.push(token),
+            Token::Separator => return Err(CoordinateError::Malformed("unexpected separator.".to_string())),
+        }
+    }
+
+    if numbers.is_empty() || numbers.len() > 3 {
+        return Err(CoordinateError::Malformed(
+            format!("expected 1 to 3 numbers for degrees, minutes and seconds, yet {:?} found.", numbers.len())
+        ));
+    }
+
+    let units = [Unit::Degree, Unit::Minute, Unit::Second];
+    let mut degrees = 0.;
+    let mut negative = false;
+
+    for (k, number) in numbers.iter().enumerate() {
+        if let Token::Number { value, negative: signed, integral, unit } = number {
+            if unit.map_or(false, | unit | unit != units[k]) {
+                return Err(CoordinateError::Malformed(format!("expected {:?} markers in order.", &units[..numbers.len()])));
+            }
+            if k > 0 && *signed {
+                return Err(CoordinateError::Malformed("only degrees can be signed.".to_string()));
+            }
+            if k < numbers.len()-1 && !integral {
+                return Err(CoordinateError::Malformed("only the last number can have decimals.".to_string()));
+            }
+            if k > 0 && *value >= 60. {
+                return Err(CoordinateError::OutOfRange(format!("{:?} is not between 0 and 60.", value)));
+            }
+
+            negative |= *signed;
+            degrees += value / 60_f64.powi(k as i32);
+        }
+    }
+
+    if negative && hemisphere.is_some() {
+        return Err(CoordinateError::Malformed("a negative coordinate cannot have a hemisphere letter.".to_string()));
+    }
+
+    let sign = if negative { -1. } else { hemisphere.map_or(1., | h | h.sign()) };
+
+    return Ok((sign * degrees, hemisphere));
+}
+
+/// Index at which the tokens of a latitude-longitude pair are split in two.
+fn split_index(tokens:&[Token]) -> Result<usize, CoordinateError> {
+    let separators: Vec<usize> = {
+        tokens.iter()
+              .enumerate()
+              .filter(| (_, token) | **token == Token::Separator)
+              .map(| (k, _) | k)
+              .collect()
+    };
+    if separators.len() > 1 {
+        return Err(CoordinateError::Malformed("more than 1 separator found.".to_string()));
+    }
+    if let Some(k) = separators.first() {
+        return Ok(*k);
+    }
+
+    let hemispheres: Vec<usize> = {
+        tokens.iter()
+              .enumerate()
+              .filter(| (_, token) | matches!(token, Token::Hemisphere(_)))
+              .map(| (k, _) | k)
+              .collect()
+    };
+    if !hemispheres.is_empty() {
+        // Hemisphere letters lead if the text starts with one, and trail otherwise.
+        return match (hemispheres[0] == 0, hemispheres.get(1)) {
+            (true, Some(k)) => Ok(*k),
+            (true, None) => Err(CoordinateError::Malformed("expected 2 hemisphere letters.".to_string())),
+            (false, _) => Ok(hemispheres[0] + 1),
+        };
+    }
+
+    let degrees: Vec<usize> = {
+        tokens.iter()
+              .enumerate()
+              .filter(| (_, token) | matches!(token, Token::Number { unit: Some(Unit::Degree), .. }))
+              .map(| (k, _) | k)
+              .collect()
+    };
+    if degrees.len() == 2 {
+        return Ok(degrees[1]);
+    }
+
+    // Unmarked numbers only; they must be split evenly.
+    return match tokens.len() {
+        2 | 4 | 6 => Ok(tokens.len() / 2),
+        n => Err(CoordinateError::Malformed(format!("cannot split {:?} numbers into latitude and longitude.", n))),
+    };
+}
+
+/// Check the range of a latitude and longitude.
+fn check_range(lat:f64, lng:f64) -> Result<[f64; 2], CoordinateError> {
+    if lat.abs() > 90. {
+        return Err(CoordinateError::OutOfRange(format!("latitude {:?} is not between -90 and 90.", lat)));
+    }
+    if lng.abs() > 180. {
+        return Err(CoordinateError::OutOfRange(format!("longitude {:?} is not between -180 and 180.", lng)));
+    }
+
+    return Ok([lat, lng]);
+}
+
+/// Order two angles as ``[latitude, longitude]`` by their hemisphere letters.
+fn order_angles(
+    first:(f64, Option<Hemisphere>),
+    second:(f64, Option<Hemisphere>),
+) -> Result<[f64; 2], CoordinateError> {
+    let is_latitude = | angle:(f64, Option<Hemisphere>) | angle.1.map(| h | h.is_latitude());
+
+    return match (is_latitude(first), is_latitude(second)) {
+        (Some(a), Some(b)) if a == b => Err(CoordinateError::Malformed("both hemisphere letters are of the same axis.".to_string())),
+        (Some(false), _) | (_, Some(true)) => check_range(second.0, first.0),
+        _ => check_range(first.0, second.0),
+    };
+}
+
+/// Parse a single latitude or longitude, returning signed degrees and its
+/// hemisphere letter, if any.
+pub fn parse_angle(text:&str) -> Result<(f64, Option<Hemisphere>), CoordinateError> {
+    return angle_from_tokens(&tokenize(text)?);
+}
+
+/// Parse a text containing both latitude and longitude into
+/// ``[latitude, longitude]``.
+pub fn parse_latlng(text:&str) -> Result<[f64; 2], CoordinateError> {
+    let tokens = tokenize(text)?;
+    let k = split_index(&tokens)?;
+
+    let after = if tokens.get(k) == Some(&Token::Separator) { k+1 } else { k };
+
+    return order_angles(angle_from_tokens(&tokens[..k])?, angle_from_tokens(&tokens[after..])?);
+}
+
+/// Parse a latitude and a longitude from separate texts into
+/// ``[latitude, longitude]``.
+///
+/// If hemisphere letters show that the texts are the other way round, they are
+/// swapped.
+pub fn parse_pair(lat:&str, lng:&str) -> Result<[f64; 2], CoordinateError> {
+    return order_angles(parse_angle(lat)?, parse_angle(lng)?);
+}
+
+/// Parse many texts in parallel, each containing both latitude and longitude.
+pub fn parse_coordinates<S>(texts:&[S]) -> Vec<Result<[f64; 2], CoordinateError>>
+where S: AsRef<str> + Sync
+{
+    return texts.par_iter().map(| text | parse_latlng(text.as_ref())).collect();
+}
+
+/// Output style of :func:`format_latlng`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Signed decimal degrees, e.g. ``51.507200, -0.127600``.
+    Decimal,
+
+    /// Degrees and decimal minutes, e.g. ``51°30.432'N 0°07.656'W``.
+    DDM,
+
+    /// Degrees, minutes and decimal seconds, e.g. ``51°30'25.9"N 0°07'39.4"W``.
+    DMS,
+}
+impl Style {
+    /// Default number of decimal places of the last component.
+    pub fn default_precision(&self) -> usize {
+        return match self {
+            Self::Decimal => 6,
+            Self::DDM => 3,
+            Self::DMS => 1,
+        };
+    }
+}
+impl FromStr for Style {
+    type Err = CoordinateError;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "decimal" => Ok(Self::Decimal),
+            "ddm" => Ok(Self::DDM),
+            "dms" => Ok(Self::DMS),
+            other => Err(CoordinateError::Malformed(format!("unknown style {:?}.", other))),
+        };
+    }
+}
+
+/// Format an angle as ``D°M.m'`` or ``D°M'S.s"`` with a hemisphere letter.
+fn format_angle(value:f64, hemispheres:[char; 2], style:Style, precision:usize) -> String {
+    let hemisphere = if value < 0. { hemispheres[1] } else { hemispheres[0] };
+
+    // Round in units of the last component, so that e.g. 59.96" carries over.
+    let (units_per_degree, scale) = match style {
+        Style::DDM => (60., 10_f64.powi(precision as i32)),
+        _ => (3600., 10_f64.powi(precision as i32)),
+    };
+    let total = (value.abs() * units_per_degree * scale).round() as u64;
+    let scale = scale as u64;
+
+    let (last, fraction) = ((total / scale) % 60, total % scale);
+    let (degrees, minutes) = match style {
+        Style::DDM => (total / scale / 60, None),
+        _ => (total / scale / 3600, Some((total / scale / 60) % 60)),
+    };
+
+    let fraction = if precision > 0 { format!(".{:0precision$}", fraction, precision=precision) } else { String::new() };
+    let last_marker = if minutes.is_some() { '"' } else { '\'' };
+
+    return match minutes {
+        Some(minutes) => format!("{}°{:02}'{:02}{}{}{}", degrees, minutes, last, fraction, last_marker, hemisphere),
+        None => format!("{}°{:02}{}{}{}", degrees, last, fraction, last_marker, hemisphere),
+    };
+}
+
+/// Format a latitude and longitude as text, which :func:`parse_latlng` can read
+/// back.
+///
+/// ``precision`` is the number of decimal places of the last component; see
+/// :meth:`Style::default_precision`.
+pub fn format_latlng(lat:f64, lng:f64, style:Style, precision:Option<usize>) -> String {
+    let precision = precision.unwrap_or(style.default_precision());
+
+    return match style {
+        Style::Decimal => format!("{:.precision$}, {:.precision$}", lat, lng, precision=precision),
+        _ => format!(
+            "{} {}",
+            format_angle(lat, ['N', 'S'], style, precision),
+            format_angle(lng, ['E', 'W'], style, precision),
+        ),
+    };
+}
+
+/// Format each row of an array of lat-long coordinates as text.
+pub fn format_coordinates(s:&dyn LatLngArray, style:Style, precision:Option<usize>) -> Vec<String> {
+    return s.to_owned()
+            .axis_iter(Axis(0))
+            .map(| row | format_latlng(row[0], row[1], style, precision))
+            .collect();
+}
@@ -0,0 +1,193 @@
+/// Python compatibility layer for the `coordinates` module.
+///
+/// The functions here are registered into a `coordinates` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.coordinates`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::Ix2;
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+use rayon::prelude::*;
+
+use ndarray_numeric::F64LatLngArray;
+
+use super::{
+    CoordinateError,
+    Style,
+};
+
+/// Maximum number of failed rows listed in an error message.
+const MAX_REPORTED_ROWS:usize = 10;
+
+impl From<CoordinateError> for PyErr {
+    fn from(err: CoordinateError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+/// A row of input: either a single text, or separate latitude and longitude.
+enum Row {
+    LatLng(String),
+    Pair(String, String),
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(texts, errors)")]
+/// Parse human readable coordinates into an array of lat-long coordinates.
+///
+/// Each latitude or longitude may be written as signed decimal degrees
+/// (``-0.1276``), degrees and decimal minutes (``51 30.433``, ``51°30.433'``) or
+/// degrees, minutes and decimal seconds (``51°30'26"``), optionally with a
+/// hemisphere letter before or after it (``N51.5072``, ``0°7'39"W``).
+///
+/// Parameters
+/// ----------
+/// texts: List[Union[str, Tuple[str, str]]]
+///     Each item is either a text containing both latitude and longitude, e.g.
+///     ``51°30'26"N 0°7'39"W`` or ``51.5072, -0.1276``; or a tuple of separate
+///     latitude and longitude texts, e.g. ``("51 30.433 N", "-0.1276")``.
+///
+///     Without hemisphere letters, latitude is assumed to come first.
+///
+/// errors: Optional[str]
+///     Either:
+///
+///     - ``"raise"`` (default): raise :class:`ValueError` listing the rows that
+///       cannot be parsed, or
+///     - ``"coerce"``: set rows that cannot be parsed to ``nan``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// Example
+/// -------
+/// ::
+///
+///     >>> from rust_geodistances import parse_coordinates
+///     >>> parse_coordinates(["51°30'26\"N 0°7'39\"W", "N51.5072 W0.1276"])
+///     array([[51.50722222, -0.1275    ],
+///            [51.5072    , -0.1276    ]])
+fn parse_coordinates(
+    texts: Vec<&PyAny>,
+    errors: Option<&str>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let coerce = match errors.unwrap_or("raise") {
+        "raise" => false,
+        "coerce" => true,
+        other => return Err(PyValueError::new_err(
+            format!("`errors` must be either \"raise\" or \"coerce\", yet {:?} provided.", other)
+        )),
+    };
+
+    let rows = texts.into_iter()
+                    .map(
+                        | item | match item.extract::<String>() {
+                            Ok(text) => Ok(Row::LatLng(text)),
+                            Err(_) => item.extract::<(String, String)>()
+                                          .map(| (lat, lng) | Row::Pair(lat, lng))
+                                          .map_err(| _ | PyValueError::new_err(
+                                              "`texts` must contain either `str` or a tuple of 2 `str`."
+                                          )),
+                        }
+                    )
+                    .collect::<PyResult<Vec<Row>>>()?;
+
+    let results: Vec<Result<[f64; 2], CoordinateError>> = {
+        rows.par_iter()
+            .map(
+                | row | match row {
+                    Row::LatLng(text) => super::parse_latlng(text),
+                    Row::Pair(lat, lng) => super::parse_pair(lat, lng),
+                }
+            )
+            .collect()
+    };
+
+    if !coerce {
+        let failures: Vec<String> = {
+            results.iter()
+                   .enumerate()
+                   .filter_map(| (k, result) | result.as_ref().err().map(| err | format!("row {}: {}", k, err)))
+                   .collect()
+        };
+
+        if !failures.is_empty() {
+            return Err(PyValueError::new_err(
+                format!(
+                    "{} of {} rows could not be parsed; {}{}",
+                    failures.len(), results.len(),
+                    failures[..failures.len().min(MAX_REPORTED_ROWS)].join("; "),
+                    if failures.len() > MAX_REPORTED_ROWS { "; ..." } else { "" },
+                )
+            ));
+        }
+    }
+
+    let flat: Vec<f64> = results.iter()
+                                .flat_map(| result | result.clone().unwrap_or([f64::NAN, f64::NAN]))
+                                .collect();
+
+    return Ok(
+        F64LatLngArray::from_shape_vec((results.len(), 2), flat)
+                       .unwrap()
+                       .to_pyarray(py)
+                       .into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, style, precision)")]
+/// Format an array of lat-long coordinates as human readable text.
+///
+/// The output can be read back by :func:`parse_coordinates`.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// style: Optional[str]
+///     One of:
+///
+///     - ``"dms"`` (default): degrees, minutes and seconds, e.g.
+///       ``51°30'25.9"N 0°07'39.4"W``,
+///     - ``"ddm"``: degrees and decimal minutes, e.g. ``51°30.432'N 0°07.656'W``,
+///       or
+///     - ``"decimal"``: signed decimal degrees, e.g. ``51.507200, -0.127600``.
+///
+/// precision: Optional[int]
+///     Number of decimal places of the last component. Defaults to ``1`` for
+///     ``"dms"``, ``3`` for ``"ddm"`` and ``6`` for ``"decimal"``.
+///
+/// Returns
+/// -------
+/// List[str]
+///     Text of each point in ``s``.
+fn format_coordinates(
+    s: &PyArray<f64, Ix2>,
+    style: Option<&str>,
+    precision: Option<usize>,
+) -> PyResult<Vec<String>> {
+    let style: Style = style.unwrap_or("dms").parse()?;
+
+    return Ok(super::format_coordinates(&s.to_owned_array(), style, precision));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse_coordinates, m)?)?;
+    m.add_function(wrap_pyfunction!(format_coordinates, m)?)?;
+
+    Ok(())
+}
@@ -2,3 +2,7 @@
 ///  Data Structs and definitions
 /// ==============================
 pub mod structs;
+
+pub use structs::{
+    DisjointSet,
+};
@@ -0,0 +1,53 @@
+/// Disjoint-set (union-find) forest.
+///
+/// Used by algorithms that incrementally merge points into clusters or
+/// components, e.g. spanning trees and single-linkage hierarchies.
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+impl DisjointSet {
+    /// Create `n` singleton sets.
+    pub fn new(n:usize) -> Self {
+        return Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        };
+    }
+
+    /// Find the root of the set containing `i`, halving the path along the way.
+    pub fn find(&mut self, i:usize) -> usize {
+        let mut i = i;
+
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+
+        return i;
+    }
+
+    /// Number of elements in the set containing `i`.
+    pub fn size(&mut self, i:usize) -> usize {
+        let root = self.find(i);
+        return self.size[root];
+    }
+
+    /// Merge the sets containing `i` and `j`.
+    ///
+    /// Returns the new root, or `None` if `i` and `j` were already in the same set.
+    pub fn union(&mut self, i:usize, j:usize) -> Option<usize> {
+        let (mut root_i, mut root_j) = (self.find(i), self.find(j));
+
+        if root_i == root_j { return None }
+
+        if self.size[root_i] < self.size[root_j] {
+            std::mem::swap(&mut root_i, &mut root_j);
+        }
+
+        self.parent[root_j] = root_i;
+        self.size[root_i] += self.size[root_j];
+
+        return Some(root_i);
+    }
+}
@@ -0,0 +1,203 @@
+/// Long-lived index of facilities for nearest-facility queries.
+///
+/// A :class:`FacilityIndex` holds a changing set of facilities, each identified by
+/// an id assigned on insertion. Queries for the nearest facility of a batch of
+/// points go through a :class:`~spatial::UnitVectorTree`, so each point costs a
+/// tree search rather than a distance to every facility.
+///
+/// The tree is not rebuilt on every change. Inserted facilities are kept aside
+/// and checked exhaustively until there are more than :const:`MAX_UNINDEXED` of
+/// them; removed facilities are skipped in the tree until they make up more than
+/// a half of it. Either way the tree is then rebuilt from the live facilities only.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+use ndarray::Axis;
+use rayon::prelude::*;
+
+use ndarray_numeric::F64LatLngArray;
+
+use crate::calc_models::traits::LatLngArray;
+use crate::spatial::UnitVectorTree;
+
+use crate::compatibility::{
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+// Import this if you want the FacilityIndex class to be available in Python.
+pub mod python;
+
+/// Maximum number of facilities inserted since the tree was last built, before
+/// the tree is rebuilt.
+pub const MAX_UNINDEXED:usize = 256;
+
+/// Errors from facility indices.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FacilityError {
+    /// Arrays of the wrong dimensions or lengths.
+    Shape(String),
+}
+impl fmt::Display for FacilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+        };
+    }
+}
+impl std::error::Error for FacilityError {}
+
+/// Changing set of facilities, indexed for nearest-facility queries.
+pub struct FacilityIndex {
+    /// Coordinates of every slot; the first ``indexed`` rows are in the tree.
+    coords: F64LatLngArray,
+    ids: Vec<u64>,
+    alive: Vec<bool>,
+    slots: HashMap<u64, usize>,
+
+    tree: UnitVectorTree,
+    indexed: usize,
+    next_id: u64,
+}
+impl Default for FacilityIndex {
+    fn default() -> Self {
+        return Self {
+            coords: F64LatLngArray::zeros((0, 2)),
+            ids: Vec::new(),
+            alive: Vec::new(),
+            slots: HashMap::new(),
+            tree: UnitVectorTree::from_vectors(Vec::new()),
+            indexed: 0,
+            next_id: 0,
+        };
+    }
+}
+impl FacilityIndex {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Number of live facilities.
+    pub fn len(&self) -> usize {
+        return self.slots.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.slots.is_empty();
+    }
+
+    /// Whether a facility of ``id`` is in the index.
+    pub fn contains(&self, id:u64) -> bool {
+        return self.slots.contains_key(&id);
+    }
+
+    /// Coordinates of the facility of ``id``, if it is in the index.
+    pub fn get(&self, id:u64) -> Option<(f64, f64)> {
+        return self.slots.get(&id).map(| slot | (self.coords[[*slot, 0]], self.coords[[*slot, 1]]));
+    }
+
+    /// Add facilities; returns their ids, in order of the rows of ``s``.
+    ///
+    /// Ids are never reused, even after the facility is removed.
+    pub fn insert(&mut self, s:&dyn LatLngArray) -> Result<Vec<u64>, FacilityError> {
+        let s_owned: F64LatLngArray = s.to_owned();
+        if s_owned.shape()[1] != 2 {
+            return Err(FacilityError::Shape(
+                format!("facilities must be of dimension (n, 2), yet {:?} provided.", s_owned.shape())
+            ));
+        }
+
+        let n = s_owned.shape()[0];
+        let first = self.ids.len();
+        let ids: Vec<u64> = (self.next_id..self.next_id + n as u64).collect();
+
+        self.coords = ndarray::concatenate(Axis(0), &[self.coords.view(), s_owned.view()]).unwrap();
+        self.ids.extend(ids.iter().cloned());
+        self.alive.extend(std::iter::repeat(true).take(n));
+        self.slots.extend(ids.iter().cloned().zip(first..first + n));
+        self.next_id += n as u64;
+
+        if self.ids.len() - self.indexed > MAX_UNINDEXED { self.rebuild() }
+
+        return Ok(ids);
+    }
+
+    /// Remove facilities by id; ids not in the index are ignored.
+    ///
+    /// Returns the number of facilities removed.
+    pub fn remove(&mut self, ids:&[u64]) -> usize {
+        let mut removed: usize = 0;
+        for id in ids {
+            if let Some(slot) = self.slots.remove(id) {
+                self.alive[slot] = false;
+                removed += 1;
+            }
+        }
+
+        if self.ids.len() - self.slots.len() > self.indexed / 2 { self.rebuild() }
+
+        return removed;
+    }
+
+    /// Drop removed facilities and rebuild the tree over all live facilities.
+    pub fn rebuild(&mut self) {
+        let live: Vec<usize> = (0..self.ids.len()).filter(| slot | self.alive[*slot]).collect();
+
+        self.coords = self.coords.select(Axis(0), &live);
+        self.ids = live.iter().map(| slot | self.ids[*slot]).collect();
+        self.alive = vec![true; live.len()];
+        self.slots = self.ids.iter().cloned().enumerate().map(| (slot, id) | (id, slot)).collect();
+
+        self.tree = UnitVectorTree::new(&self.coords);
+        self.indexed = live.len();
+    }
+
+    /// Nearest live facility of each point of ``s``, as ``(id, distance)``.
+    ///
+    /// ``None`` for every point if the index is empty. The points are spread
+    /// over :attr:`CalculationSettings.workers` threads.
+    pub fn nearest(
+        &self,
+        method:&CalculationMethod,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Option<(u64, f64)>> {
+        let s_owned: F64LatLngArray = s.to_owned();
+        let n = s_owned.shape()[0];
+
+        let workers: usize = settings.unwrap_or(
+            &CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+        let unindexed: Vec<usize> = (self.indexed..self.ids.len()).filter(| slot | self.alive[*slot]).collect();
+
+        return (0..n).into_par_iter()
+                     .with_min_len(chunk_size)
+                     .map(
+                         | i | {
+                             let p = s_owned.row(i);
+
+                             let mut candidates = if self.indexed > 0 {
+                                 method._nearest_from_tree(
+                                     &p, &self.coords, &self.tree,
+                                     1,
+                                     | slot | self.alive[slot],
+                                     settings,
+                                 )
+                             } else {
+                                 Vec::new()
+                             };
+                             candidates.extend(method._distance_to_indices(&p, &self.coords, &unindexed, settings));
+
+                             candidates.into_iter()
+                                       .map(| (slot, d) | (self.ids[slot], d))
+                                       .min_by(| a, b | a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+                         }
+                     )
+                     .collect();
+    }
+}
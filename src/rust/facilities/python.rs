@@ -0,0 +1,175 @@
+/// Python compatibility layer for the `facilities` module.
+///
+/// The class here is registered into a `facilities` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.facilities`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each method; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    FacilityError,
+    FacilityIndex,
+};
+
+impl From<FacilityError> for PyErr {
+    fn from(err: FacilityError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyclass(module="rust_geodistances.facilities", name="FacilityIndex")]
+/// A changing set of facilities, indexed for nearest-facility queries.
+///
+/// Meant to be kept alive across many queries, e.g. in a dispatch service:
+/// facilities are inserted and removed as they come and go, and each batch of
+/// incoming points is answered by a spatial index search rather than a distance
+/// to every facility.
+///
+/// Parameters
+/// ----------
+/// facilities: Optional[numpy.ndarray]
+///     Initial facilities, of dimension ``(k, 2)``, in degrees. In order
+///     ``(latitude, longitude)``. Their ids are ``0`` to ``k-1``.
+///
+/// Example
+/// -------
+/// Dispatch each incoming job to the nearest available vehicle::
+///
+///     >>> from rust_geodistances.facilities import FacilityIndex
+///     >>> index = FacilityIndex(vehicles)
+///     >>> ids, distances = index.nearest(jobs)
+///     >>> index.remove(ids)
+///     3
+pub struct PyFacilityIndex {
+    index: FacilityIndex,
+}
+
+#[pymethods]
+impl PyFacilityIndex {
+    #[new]
+    fn new(facilities:Option<&PyArray<f64, Ix2>>) -> PyResult<Self> {
+        let mut index = FacilityIndex::new();
+        if let Some(facilities) = facilities {
+            index.insert(&facilities.to_owned_array())?;
+            index.rebuild();
+        }
+
+        return Ok(Self { index });
+    }
+
+    #[pyo3(text_signature = "($self, s)")]
+    /// Add facilities.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(k, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray
+    ///     Of dimension ``(k)``, the id of each new facility. Ids are never reused.
+    fn insert(&mut self, s:&PyArray<f64, Ix2>, py: Python<'_>) -> PyResult<PyObject> {
+        let ids = self.index.insert(&s.to_owned_array())?;
+
+        return Ok(Array1::from_vec(ids).to_pyarray(py).into_py(py));
+    }
+
+    #[pyo3(text_signature = "($self, ids)")]
+    /// Remove facilities by id.
+    ///
+    /// Parameters
+    /// ----------
+    /// ids: List[int]
+    ///     Ids of the facilities to remove; ids not in the index are ignored.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     The number of facilities removed.
+    fn remove(&mut self, ids:Vec<u64>) -> usize {
+        return self.index.remove(&ids);
+    }
+
+    #[pyo3(text_signature = "($self, s, method, *, settings)")]
+    /// Nearest facility of each of a batch of points.
+    ///
+    /// The points are spread over :attr:`CalculationSettings.workers` threads.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///
+    /// method: Optional[CalculationMethod]
+    ///     Calculation method for the distances. Defaults to
+    ///     :attr:`CalculationMethod.HAVERSINE`.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// Tuple[numpy.ndarray, numpy.ndarray]
+    ///     ``(ids, distances)``, both of dimension ``(n)``: the id of the nearest
+    ///     facility of each point and the distance to it, or ``-1`` and ``inf`` if
+    ///     there are no facilities.
+    fn nearest(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        method: Option<&enums::CalculationMethod>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let method_default = &enums::CalculationMethod::default();
+
+        let nearest = self.index.nearest(method.unwrap_or(method_default), &s.to_owned_array(), settings);
+
+        let ids = Array1::from_iter(nearest.iter().map(| result | result.map_or(-1, | (id, _) | id as i64)));
+        let distances = Array1::from_iter(nearest.iter().map(| result | result.map_or(f64::INFINITY, | (_, d) | d)));
+
+        return Ok((ids.to_pyarray(py), distances.to_pyarray(py)).into_py(py));
+    }
+
+    #[pyo3(text_signature = "($self)")]
+    /// Drop removed facilities and rebuild the spatial index.
+    ///
+    /// This is done automatically as facilities are inserted and removed; call
+    /// this to have the index fully built ahead of a burst of queries.
+    fn rebuild(&mut self) {
+        self.index.rebuild();
+    }
+
+    /// Number of facilities.
+    fn __len__(&self) -> usize {
+        return self.index.len();
+    }
+
+    /// Whether a facility of ``id`` is in the index.
+    fn __contains__(&self, id:u64) -> bool {
+        return self.index.contains(id);
+    }
+}
+
+/// Add all the classes of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyFacilityIndex>()?;
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+/// Apache Arrow and Parquet batch processing.
+///
+/// Instead of ``(n, 2)`` arrays, the functions here take Arrow ``RecordBatch``es
+/// with separate latitude and longitude columns, and return the same batches with
+/// new columns of results appended. Rows with a null latitude or longitude produce
+/// null results.
+///
+/// Parquet files can be processed chunk by chunk through :func:`process_parquet`,
+/// so that files much larger than memory can be processed.
+///
+/// Only available with the ``arrow`` feature.
+
+use std::cmp;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array,
+    ArrayRef,
+    Float64Array,
+    UInt64Array,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    DataType,
+    Field,
+    Schema,
+    SchemaRef,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::errors::ParquetError;
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceNeighbours,
+    CalculationInterfacePairwise,
+    CalculationMethod,
+    CalculationSettings,
+};
+use crate::spatial::UnitVectorTree;
+
+use super::FormatError;
+
+// Import this if you want the arrow module to be available in Python.
+pub mod python;
+
+/// Default number of rows read from a Parquet file at a time.
+pub const DEFAULT_BATCH_SIZE:usize = 65536;
+
+impl From<ArrowError> for FormatError {
+    fn from(err: ArrowError) -> Self {
+        return match err {
+            ArrowError::IoError(msg) => Self::Io(msg),
+            err => Self::Parse(err.to_string()),
+        };
+    }
+}
+
+impl From<ParquetError> for FormatError {
+    fn from(err: ParquetError) -> Self {
+        return Self::Parse(err.to_string());
+    }
+}
+
+/// A pair of latitude and longitude columns, in degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatLngColumns {
+    pub lat: String,
+    pub lng: String,
+}
+impl LatLngColumns {
+    pub fn new(lat:&str, lng:&str) -> Self {
+        return Self { lat: lat.to_string(), lng: lng.to_string() };
+    }
+
+    /// Check that both columns exist in ``schema`` and are numeric.
+    fn check(&self, schema:&Schema) -> Result<(), FormatError> {
+        for name in [&self.lat, &self.lng] {
+            let field = schema.field_with_name(name).map_err(
+                | _ | FormatError::Schema(format!("column {:?} not found.", name))
+            )?;
+
+            if !DataType::is_numeric(field.data_type()) {
+                return Err(FormatError::Schema(
+                    format!("column {:?} must be numeric, yet {:?} found.", name, field.data_type())
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Extract the columns as an array of dimension ``(n, 2)``, and whether each
+    /// row is valid.
+    ///
+    /// Rows with a null latitude or longitude are filled with ``0.``.
+    fn extract(&self, batch:&RecordBatch) -> Result<(F64LatLngArray, Vec<bool>), FormatError> {
+        self.check(&batch.schema())?;
+
+        let column = | name:&String | -> Result<ArrayRef, FormatError> {
+            let index = batch.schema().index_of(name)?;
+
+            return Ok(cast(batch.column(index), &DataType::Float64)?);
+        };
+        let (lat, lng) = (column(&self.lat)?, column(&self.lng)?);
+        let (lat, lng) = (
+            lat.as_any().downcast_ref::<Float64Array>().unwrap(),
+            lng.as_any().downcast_ref::<Float64Array>().unwrap(),
+        );
+
+        let n = batch.num_rows();
+        let valid: Vec<bool> = (0..n).map(| i | lat.is_valid(i) && lng.is_valid(i)).collect();
+
+        let flat: Vec<f64> = {
+            (0..n)
+            .flat_map(| i | if valid[i] { [lat.value(i), lng.value(i)] } else { [0., 0.] })
+            .collect()
+        };
+
+        return Ok((F64LatLngArray::from_shape_vec((n, 2), flat).unwrap(), valid));
+    }
+}
+
+/// An operation to be performed on each row of a batch.
+pub enum Operation {
+    /// Distance from each row to the point in ``other`` columns of the same row.
+    Distance(LatLngColumns),
+
+    /// Initial bearing in degrees from each row to the point in ``other`` columns
+    /// of the same row.
+    Bearing(LatLngColumns),
+
+    /// Index of, and distance to the closest point of ``targets``.
+    Nearest {
+        targets: F64LatLngArray,
+        tree: UnitVectorTree,
+    },
+}
+impl Operation {
+    /// Nearest neighbour among ``targets``, an array of dimension ``(m, 2)``.
+    pub fn nearest(targets:F64LatLngArray) -> Self {
+        let tree = UnitVectorTree::new(&targets);
+
+        return Self::Nearest { targets, tree };
+    }
+}
+
+/// Processes batches by performing an :class:`Operation` on latitude and longitude
+/// columns, appending the result as new columns.
+///
+/// The new columns are named ``output``; :attr:`Operation::Nearest` also appends
+/// ``<output>_distance``.
+pub struct BatchProcessor<'a> {
+    pub method: &'a CalculationMethod,
+    pub columns: LatLngColumns,
+    pub operation: Operation,
+    pub output: String,
+    pub settings: Option<&'a CalculationSettings>,
+}
+impl<'a> BatchProcessor<'a> {
+    /// Fields appended by this processor.
+    fn output_fields(&self) -> Vec<Field> {
+        return match self.operation {
+            Operation::Distance(_) | Operation::Bearing(_) => vec![
+                Field::new(&self.output, DataType::Float64, true),
+            ],
+            Operation::Nearest { .. } => vec![
+                Field::new(&self.output, DataType::UInt64, true),
+                Field::new(&format!("{}_distance", self.output), DataType::Float64, true),
+            ],
+        };
+    }
+
+    /// Schema of the batches produced from batches of ``schema``.
+    pub fn output_schema(&self, schema:&Schema) -> Result<SchemaRef, FormatError> {
+        self.columns.check(schema)?;
+        if let Operation::Distance(other) | Operation::Bearing(other) = &self.operation {
+            other.check(schema)?;
+        }
+
+        let new_fields = self.output_fields();
+        if let Some(field) = new_fields.iter().find(| field | schema.field_with_name(field.name()).is_ok()) {
+            return Err(FormatError::Schema(format!("column {:?} already exists.", field.name())));
+        }
+
+        let mut fields = schema.fields().clone();
+        fields.extend(new_fields);
+
+        return Ok(Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())));
+    }
+
+    /// Perform the operation on a batch, returning a new batch with the results
+    /// appended.
+    pub fn process(&self, batch:&RecordBatch) -> Result<RecordBatch, FormatError> {
+        let schema = self.output_schema(&batch.schema())?;
+        let (s, valid) = self.columns.extract(batch)?;
+
+        let new_columns: Vec<ArrayRef> = match &self.operation {
+            Operation::Distance(other) | Operation::Bearing(other) => {
+                let (e, e_valid) = other.extract(batch)?;
+                let (distances, bearings) = self.method._inverse_pairwise(&s, &e, self.settings);
+
+                let values = match self.operation {
+                    Operation::Distance(_) => distances,
+                    _ => bearings,
+                };
+
+                vec![
+                    Arc::new(
+                        values.into_iter()
+                              .zip(valid.iter().zip(e_valid.iter()))
+                              .map(| (value, (s_valid, e_valid)) | if *s_valid && *e_valid { Some(value) } else { None })
+                              .collect::<Float64Array>()
+                    ),
+                ]
+            },
+            Operation::Nearest { targets, tree } => {
+                let n = s.shape()[0];
+
+                let workers: usize = self.settings.unwrap_or(
+                    &CalculationSettings::default()
+                ).workers;
+                let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+                let nearest: Vec<Option<(usize, f64)>> = {
+                    (0..n)
+                    .into_par_iter()
+                    .with_min_len(chunk_size)
+                    .map(
+                        | i | if valid[i] {
+                            self.method._nearest_from_tree(
+                                &s.row(i), targets, tree,
+                                1,
+                                | _ | true,
+                                self.settings,
+                            )
+                            .first()
+                            .copied()
+                        } else {
+                            None
+                        }
+                    )
+                    .collect()
+                };
+
+                vec![
+                    Arc::new(nearest.iter().map(| item | item.map(| (j, _) | j as u64)).collect::<UInt64Array>()),
+                    Arc::new(nearest.iter().map(| item | item.map(| (_, d) | d)).collect::<Float64Array>()),
+                ]
+            },
+        };
+
+        let mut columns = batch.columns().to_vec();
+        columns.extend(new_columns);
+
+        return Ok(RecordBatch::try_new(schema, columns)?);
+    }
+}
+
+/// Process a Parquet file chunk by chunk, writing the results to another Parquet
+/// file.
+///
+/// At most ``batch_size`` rows are held in memory at a time. Returns the number of
+/// rows written.
+pub fn process_parquet(
+    input:&Path,
+    output:&Path,
+    batch_size:usize,
+    processor:&BatchProcessor,
+) -> Result<usize, FormatError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(input)?)?
+                  .with_batch_size(batch_size);
+
+    let schema = processor.output_schema(builder.schema())?;
+    let reader = builder.build()?;
+
+    let mut writer = ArrowWriter::try_new(File::create(output)?, schema, None)?;
+    let mut rows: usize = 0;
+
+    for batch in reader {
+        let batch = processor.process(&batch?)?;
+        rows += batch.num_rows();
+
+        writer.write(&batch)?;
+    }
+
+    writer.close()?;
+
+    return Ok(rows);
+}
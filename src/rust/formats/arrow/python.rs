@@ -0,0 +1,254 @@
+/// Python compatibility layer for the `formats.arrow` module.
+///
+/// The functions here are registered into a `formats.arrow` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.arrow`.
+///
+/// Batches are exchanged with ``pyarrow`` through the Arrow C data interface, so
+/// no data is copied on the way in or out.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use std::path::Path;
+
+use arrow::datatypes::Schema;
+use arrow::pyarrow::PyArrowConvert;
+use arrow::record_batch::RecordBatch;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Ix2,
+};
+use numpy::{
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    BatchProcessor,
+    LatLngColumns,
+    Operation,
+    DEFAULT_BATCH_SIZE,
+};
+
+/// Build a :class:`BatchProcessor` from Python arguments.
+#[allow(clippy::too_many_arguments)]
+fn processor_from_py<'a>(
+    operation: &str,
+    lat: &str,
+    lng: &str,
+    other: Option<(String, String)>,
+    targets: Option<&PyArray<f64, Ix2>>,
+    column: Option<String>,
+    method: &'a enums::CalculationMethod,
+    settings: Option<&'a config::CalculationSettings>,
+) -> PyResult<BatchProcessor<'a>> {
+    let other_columns = | other:Option<(String, String)> | other.map(
+        | (lat, lng) | LatLngColumns::new(&lat, &lng)
+    ).ok_or_else(
+        || PyValueError::new_err(format!("`other` columns must be provided for {:?}.", operation))
+    );
+
+    let operation = match operation {
+        "distance" => Operation::Distance(other_columns(other)?),
+        "bearing" => Operation::Bearing(other_columns(other)?),
+        "nearest" => Operation::nearest(
+            targets.ok_or_else(
+                || PyValueError::new_err("`targets` must be provided for \"nearest\".")
+            )?
+            .to_owned_array()
+        ),
+        _ => return Err(PyValueError::new_err(
+            format!("`operation` must be one of \"distance\", \"bearing\" or \"nearest\", yet {:?} provided.", operation)
+        )),
+    };
+
+    let column = column.unwrap_or_else(
+        || match operation {
+            Operation::Distance(_) => "distance",
+            Operation::Bearing(_) => "bearing",
+            Operation::Nearest { .. } => "nearest",
+        }.to_string()
+    );
+
+    return Ok(
+        BatchProcessor {
+            method,
+            columns: LatLngColumns::new(lat, lng),
+            operation,
+            output: column,
+            settings,
+        }
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(data, operation, lat, lng, other, targets, column, method, *, settings)")]
+/// Perform an operation on the latitude and longitude columns of a table.
+///
+/// Parameters
+/// ----------
+/// data: Union[pyarrow.RecordBatch, pyarrow.Table]
+///     Table containing latitude and longitude columns in degrees. Any numeric
+///     type is accepted. Rows with a null latitude or longitude produce null
+///     results.
+///
+/// operation: str
+///     One of:
+///
+///     - ``"distance"``: distance from each row to the point in ``other`` columns
+///       of the same row, as :meth:`CalculationMethod.distance_pairwise`,
+///     - ``"bearing"``: initial bearing in degrees from each row to the point in
+///       ``other`` columns of the same row, as
+///       :meth:`CalculationMethod.bearing_pairwise`, or
+///     - ``"nearest"``: index of the closest point of ``targets``, and the
+///       distance to it.
+///
+/// lat: str
+///     Name of the latitude column.
+///
+/// lng: str
+///     Name of the longitude column.
+///
+/// other: Optional[Tuple[str, str]]
+///     Names of the ``(latitude, longitude)`` columns of the other point; required
+///     for ``"distance"`` and ``"bearing"``.
+///
+/// targets: Optional[numpy.ndarray]
+///     Of dimension ``(m, 2)``, in degrees. In order ``(latitude, longitude)``;
+///     required for ``"nearest"``.
+///
+/// column: Optional[str]
+///     Name of the new column; defaults to ``operation``. ``"nearest"`` also adds
+///     a ``<column>_distance`` column.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method. Defaults to :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Union[pyarrow.RecordBatch, pyarrow.Table]
+///     Same type as ``data``, with the new columns appended.
+///
+/// Example
+/// -------
+/// Distance of each trip::
+///
+///     >>> from rust_geodistances.formats import arrow
+///     >>> arrow.process(trips, "distance", "pickup_lat", "pickup_lng",
+///     ...               other=("dropoff_lat", "dropoff_lng"))
+#[allow(clippy::too_many_arguments)]
+fn process(
+    data: &PyAny,
+    operation: &str,
+    lat: &str,
+    lng: &str,
+    other: Option<(String, String)>,
+    targets: Option<&PyArray<f64, Ix2>>,
+    column: Option<String>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let processor = processor_from_py(
+        operation, lat, lng, other, targets, column,
+        method.unwrap_or(method_default),
+        settings,
+    )?;
+
+    // A `pyarrow.Table` is processed batch by batch and reassembled.
+    if data.hasattr("to_batches")? {
+        let schema = processor.output_schema(&Schema::from_pyarrow(data.getattr("schema")?)?)?;
+
+        let batches = data.call_method0("to_batches")?
+                          .iter()?
+                          .map(
+                              | batch | {
+                                  let batch = processor.process(&RecordBatch::from_pyarrow(batch?)?)?;
+                                  batch.to_pyarrow(py)
+                              }
+                          )
+                          .collect::<PyResult<Vec<PyObject>>>()?;
+
+        return Ok(
+            py.import("pyarrow")?
+              .getattr("Table")?
+              .call_method1("from_batches", (batches, schema.to_pyarrow(py)?))?
+              .into_py(py)
+        );
+    }
+
+    return processor.process(&RecordBatch::from_pyarrow(data)?)?.to_pyarrow(py);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(input, output, operation, lat, lng, other, targets, column, batch_size, method, *, settings)")]
+/// Perform an operation on a Parquet file chunk by chunk, writing the results to
+/// another Parquet file.
+///
+/// At most ``batch_size`` rows are held in memory at a time, so that files much
+/// larger than memory can be processed.
+///
+/// Parameters
+/// ----------
+/// input: str
+///     Path of the Parquet file to read.
+///
+/// output: str
+///     Path of the Parquet file to write; it is overwritten if it exists.
+///
+/// operation, lat, lng, other, targets, column, method, settings:
+///     See :func:`process`.
+///
+/// batch_size: Optional[int]
+///     Number of rows read at a time. Defaults to ``65536``.
+///
+/// Returns
+/// -------
+/// int
+///     Number of rows written.
+#[allow(clippy::too_many_arguments)]
+fn process_parquet(
+    input: &str,
+    output: &str,
+    operation: &str,
+    lat: &str,
+    lng: &str,
+    other: Option<(String, String)>,
+    targets: Option<&PyArray<f64, Ix2>>,
+    column: Option<String>,
+    batch_size: Option<usize>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<usize> {
+    let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+    if batch_size == 0 {
+        return Err(PyValueError::new_err("`batch_size` must be positive."));
+    }
+
+    let method_default = &enums::CalculationMethod::default();
+    let processor = processor_from_py(
+        operation, lat, lng, other, targets, column,
+        method.unwrap_or(method_default),
+        settings,
+    )?;
+
+    return Ok(super::process_parquet(Path::new(input), Path::new(output), batch_size, &processor)?);
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(process, m)?)?;
+    m.add_function(wrap_pyfunction!(process_parquet, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+/// CSV input and output, streamed in chunks.
+///
+/// Coordinates are read from CSV files with a header row, and an ID, latitude and
+/// longitude column each. IDs are kept as text and copied verbatim to the output.
+///
+/// Files are never read into memory in full; at most two chunks of
+/// ``chunk_size`` rows are held in memory at a time, so that joins between files
+/// of tens of millions of rows can be performed. Joins also hold the pairs found
+/// for at most :const:`JOIN_BATCH_SIZE` rows before writing them out.
+
+use std::cmp;
+use std::fs::File;
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+use crate::spatial::UnitVectorTree;
+
+use super::FormatError;
+
+// Import this if you want the csv module to be available in Python.
+pub mod python;
+
+/// Default number of rows read at a time.
+pub const DEFAULT_CHUNK_SIZE:usize = 1_000_000;
+
+/// Number of rows of a chunk searched in parallel in a join, before their pairs
+/// are written and the next rows are searched.
+pub const JOIN_BATCH_SIZE:usize = 4_096;
+
+impl From<csv::Error> for FormatError {
+    fn from(err: csv::Error) -> Self {
+        return if err.is_io_error() {
+            Self::Io(err.to_string())
+        } else {
+            Self::Parse(err.to_string())
+        };
+    }
+}
+
+/// Names of the ID, latitude and longitude columns of a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvColumns {
+    pub id: String,
+    pub lat: String,
+    pub lng: String,
+}
+impl CsvColumns {
+    pub fn new(id:&str, lat:&str, lng:&str) -> Self {
+        return Self { id: id.to_string(), lat: lat.to_string(), lng: lng.to_string() };
+    }
+}
+impl Default for CsvColumns {
+    fn default() -> Self { Self::new("id", "lat", "lng") }
+}
+
+/// A chunk of rows of a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub ids: Vec<String>,
+
+    /// Array of dimension ``(n, 2)``, in order ``(latitude, longitude)``.
+    pub points: F64LatLngArray,
+}
+impl Chunk {
+    pub fn len(&self) -> usize {
+        return self.ids.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.ids.is_empty();
+    }
+}
+
+/// Iterator over the chunks of a CSV file.
+pub struct Chunks {
+    records: csv::StringRecordsIntoIter<File>,
+
+    /// Positions of the ID, latitude and longitude columns.
+    positions: [usize; 3],
+    chunk_size: usize,
+}
+impl Chunks {
+    /// Open a CSV file, locating ``columns`` in its header row.
+    pub fn open(path:&Path, columns:&CsvColumns, chunk_size:usize) -> Result<Self, FormatError> {
+        assert!(chunk_size > 0, "`chunk_size` must be positive, yet {:?} provided.", chunk_size);
+
+        let mut reader = csv::Reader::from_path(path)?;
+        let headers = reader.headers()?.clone();
+
+        let position = | name:&String | headers.iter().position(| header | header.trim() == name).ok_or_else(
+            || FormatError::Schema(format!("column {:?} not found in {:?}.", name, path))
+        );
+        let positions = [position(&columns.id)?, position(&columns.lat)?, position(&columns.lng)?];
+
+        return Ok(
+            Self {
+                records: reader.into_records(),
+                positions,
+                chunk_size,
+            }
+        );
+    }
+}
+impl Iterator for Chunks {
+    type Item = Result<Chunk, FormatError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut ids, mut flat) = (Vec::new(), Vec::new());
+
+        while ids.len() < self.chunk_size {
+            let record = match self.records.next() {
+                Some(Ok(record)) => record,
+                Some(Err(err)) => return Some(Err(err.into())),
+                None => break,
+            };
+
+            let [id, lat, lng] = self.positions.map(| position | record.get(position).unwrap_or(""));
+
+            for value in [lat, lng] {
+                match value.trim().parse::<f64>() {
+                    Ok(value) => flat.push(value),
+                    Err(_) => return Some(Err(FormatError::Parse(
+                        format!(
+                            "{:?} is not a valid coordinate on line {:?}.",
+                            value, record.position().map_or(0, | position | position.line())
+                        )
+                    ))),
+                }
+            }
+            ids.push(id.to_string());
+        }
+
+        if ids.is_empty() { return None }
+
+        return Some(Ok(
+            Chunk {
+                points: F64LatLngArray::from_shape_vec((ids.len(), 2), flat).unwrap(),
+                ids,
+            }
+        ));
+    }
+}
+
+/// Join two CSV files of coordinates, writing every pair within ``distance`` of
+/// each other to an output CSV with columns ``id_s,id_e,distance``.
+///
+/// ``e_path`` is read once, and ``s_path`` once per chunk of ``e_path``; hence
+/// ``e_path`` should be the smaller file. Within each chunk of ``e_path``, pairs
+/// are written in the order of ``s_path``.
+///
+/// Returns the number of pairs written.
+#[allow(clippy::too_many_arguments)]
+pub fn within_distance_join(
+    method:&CalculationMethod,
+    s_path:&Path,
+    e_path:&Path,
+    output:&Path,
+    s_columns:&CsvColumns,
+    e_columns:&CsvColumns,
+    distance:f64,
+    chunk_size:usize,
+    settings: Option<&CalculationSettings>,
+) -> Result<usize, FormatError> {
+    let mut writer = csv::Writer::from_path(output)?;
+    writer.write_record(["id_s", "id_e", "distance"])?;
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+
+    let mut count: usize = 0;
+
+    for e_chunk in Chunks::open(e_path, e_columns, chunk_size)? {
+        let e_chunk = e_chunk?;
+        let tree = UnitVectorTree::new(&e_chunk.points);
+
+        for s_chunk in Chunks::open(s_path, s_columns, chunk_size)? {
+            let s_chunk = s_chunk?;
+            let n = s_chunk.len();
+
+            // Pairs are buffered for one batch of rows at a time only; dense joins
+            // can find many pairs per row.
+            for start in (0..n).step_by(JOIN_BATCH_SIZE) {
+                let end = cmp::min(start + JOIN_BATCH_SIZE, n);
+                let min_len: usize = cmp::max(((end - start) as f32 / workers as f32).ceil() as usize, 1);
+
+                let found: Vec<Vec<(usize, f64)>> = {
+                    (start..end)
+                    .into_par_iter()
+                    .with_min_len(min_len)
+                    .map(
+                        | i | method._within_distance_from_tree(
+                            &s_chunk.points.row(i), &e_chunk.points, &tree,
+                            distance,
+                            | _ | true,
+                            settings,
+                        )
+                    )
+                    .collect()
+                };
+
+                for (i, pairs) in (start..end).zip(found.iter()) {
+                    for (j, d) in pairs {
+                        writer.write_record([s_chunk.ids[i].as_str(), e_chunk.ids[*j].as_str(), d.to_string().as_str()])?;
+                    }
+                    count += pairs.len();
+                }
+            }
+        }
+    }
+
+    writer.flush()?;
+
+    return Ok(count);
+}
@@ -0,0 +1,123 @@
+/// Python compatibility layer for the `formats.csv` module.
+///
+/// The functions here are registered into a `formats.csv` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.csv`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use std::path::Path;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    CsvColumns,
+    DEFAULT_CHUNK_SIZE,
+};
+
+/// Convert a tuple of ``(id, lat, lng)`` column names.
+fn columns_from_py(columns: Option<(String, String, String)>) -> CsvColumns {
+    return columns.map_or_else(
+        CsvColumns::default,
+        | (id, lat, lng) | CsvColumns::new(&id, &lat, &lng),
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s_path, e_path, output, distance, s_columns, e_columns, chunk_size, method, *, settings)")]
+/// Write every pair of points between two CSV files within ``distance`` of each
+/// other to an output CSV.
+///
+/// Unlike :meth:`CalculationMethod.within_distance`, neither file is read into
+/// memory in full: at most two chunks of ``chunk_size`` rows are held at a time.
+/// ``e_path`` is read once, and ``s_path`` once per chunk of ``e_path``; hence
+/// ``e_path`` should be the smaller file.
+///
+/// Parameters
+/// ----------
+/// s_path: str
+///     Path of a CSV file with a header row, containing ID, latitude and longitude
+///     columns. Coordinates are in degrees.
+///
+/// e_path: str
+///     Path of another CSV file in the same form as ``s_path``.
+///
+/// output: str
+///     Path of the CSV file to write, with columns ``id_s,id_e,distance``; it is
+///     overwritten if it exists.
+///
+/// distance: numpy.float64
+///     Maximum distance between pairs.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// s_columns: Optional[Tuple[str, str, str]]
+///     Names of the ``(id, latitude, longitude)`` columns of ``s_path``. Defaults
+///     to ``("id", "lat", "lng")``.
+///
+/// e_columns: Optional[Tuple[str, str, str]]
+///     Names of the ``(id, latitude, longitude)`` columns of ``e_path``. Defaults
+///     to ``("id", "lat", "lng")``.
+///
+/// chunk_size: Optional[int]
+///     Number of rows read at a time. Defaults to ``1000000``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method. Defaults to :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method;
+///     :attr:`CalculationSettings.workers` threads are used for each chunk.
+///
+/// Returns
+/// -------
+/// int
+///     Number of pairs written.
+#[allow(clippy::too_many_arguments)]
+fn within_distance_join(
+    s_path: &str,
+    e_path: &str,
+    output: &str,
+    distance: f64,
+    s_columns: Option<(String, String, String)>,
+    e_columns: Option<(String, String, String)>,
+    chunk_size: Option<usize>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<usize> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    if chunk_size == 0 {
+        return Err(PyValueError::new_err("`chunk_size` must be positive."));
+    }
+
+    let method_default = &enums::CalculationMethod::default();
+
+    return Ok(
+        super::within_distance_join(
+            method.unwrap_or(method_default),
+            Path::new(s_path), Path::new(e_path), Path::new(output),
+            &columns_from_py(s_columns), &columns_from_py(e_columns),
+            distance,
+            chunk_size,
+            settings,
+        )?
+    );
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(within_distance_join, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,343 @@
+/// GeoJSON input and output.
+///
+/// Reads ``Point``, ``MultiPoint``, ``LineString`` and ``Polygon`` features as
+/// specified by `RFC 7946 <https://datatracker.ietf.org/doc/html/rfc7946>`_ into
+/// :class:`Feature`s, and writes them back out as ``FeatureCollection``s.
+///
+/// Features of ``null`` geometry, which RFC 7946 allows for unlocated features,
+/// are kept without a geometry, so that features keep their indices.
+///
+/// GeoJSON positions are in ``[longitude, latitude]`` order, with an optional
+/// altitude which is discarded. All arrays in this module are in the
+/// ``(latitude, longitude)`` column order used by the rest of this crate.
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use ndarray::{
+    Axis,
+    concatenate,
+};
+use rayon::prelude::*;
+use serde_json::{
+    json,
+    Map,
+    Value,
+};
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+    LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+use crate::spatial::UnitVectorTree;
+
+use super::FormatError;
+use super::geometry::stack_points;
+
+pub use super::geometry::Geometry;
+
+// Import this if you want the geojson module to be available in Python.
+pub mod python;
+
+/// Default number of vertices of a buffer polygon.
+pub const BUFFER_SEGMENTS:usize = 64;
+
+/// Properties of a feature, i.e. any JSON object.
+pub type Properties = Map<String, Value>;
+
+impl Geometry {
+    /// Parse a GeoJSON geometry object.
+    pub fn from_value(value:&Value) -> Result<Self, FormatError> {
+        let kind = value.get("type")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| FormatError::InvalidGeometry("geometry has no `type`.".to_string()))?;
+        let coordinates = value.get("coordinates")
+                               .ok_or_else(|| FormatError::InvalidGeometry(format!("{} has no `coordinates`.", kind)));
+
+        let geometry = match kind {
+            "Point" => {
+                let [lat, lng] = position_from_value(coordinates?)?;
+                Self::Point(F64LatLngArray::from_shape_vec((1, 2), vec![lat, lng]).unwrap())
+            },
+            "MultiPoint" => Self::MultiPoint(positions_from_value(coordinates?)?),
+            "LineString" => Self::LineString(positions_from_value(coordinates?)?),
+            "Polygon" => Self::Polygon(
+                coordinates?.as_array()
+                            .ok_or_else(|| FormatError::InvalidGeometry("Polygon must be an array of rings.".to_string()))?
+                            .iter()
+                            .map(positions_from_value)
+                            .collect::<Result<Vec<_>, _>>()?
+            ),
+            other => return Err(FormatError::UnsupportedGeometry(other.to_string())),
+        };
+
+        geometry.validate()?;
+
+        return Ok(geometry);
+    }
+
+    /// This geometry as a GeoJSON geometry object.
+    pub fn to_value(&self) -> Value {
+        let coordinates = match self {
+            Self::Point(s) => positions_to_value(s)[0].clone(),
+            Self::MultiPoint(s) | Self::LineString(s) => Value::Array(positions_to_value(s)),
+            Self::Polygon(rings) => Value::Array(
+                rings.iter().map(| ring | Value::Array(positions_to_value(ring))).collect()
+            ),
+        };
+
+        return json!({
+            "type": self.kind(),
+            "coordinates": coordinates,
+        });
+    }
+}
+
+/// A GeoJSON feature with a supported geometry, or none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    /// ``None`` for an unlocated feature, i.e. of ``null`` geometry.
+    pub geometry: Option<Geometry>,
+    pub properties: Properties,
+}
+impl Feature {
+    pub fn new(geometry:Geometry, properties:Option<Properties>) -> Self {
+        return Self {
+            geometry: Some(geometry),
+            properties: properties.unwrap_or_default(),
+        };
+    }
+
+    /// Parse a GeoJSON feature object; ``null`` properties become empty.
+    pub fn from_value(value:&Value) -> Result<Self, FormatError> {
+        let geometry = match value.get("geometry") {
+            Some(Value::Null) => None,
+            Some(geometry) => Some(Geometry::from_value(geometry)?),
+            None => return Err(FormatError::InvalidGeometry("Feature has no `geometry`.".to_string())),
+        };
+
+        let properties = match value.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            Some(Value::Null) | None => Properties::new(),
+            Some(_) => return Err(FormatError::Parse("Feature `properties` must be an object.".to_string())),
+        };
+
+        return Ok(Self { geometry, properties });
+    }
+
+    /// This feature as a GeoJSON feature object.
+    pub fn to_value(&self) -> Value {
+        return json!({
+            "type": "Feature",
+            "geometry": self.geometry.as_ref().map(Geometry::to_value),
+            "properties": self.properties,
+        });
+    }
+}
+
+/// Parse a ``[longitude, latitude, ...]`` position into ``[latitude, longitude]``.
+fn position_from_value(value:&Value) -> Result<[f64; 2], FormatError> {
+    let invalid = || FormatError::InvalidGeometry(format!("{} is not a valid position.", value));
+
+    let position = value.as_array().ok_or_else(invalid)?;
+    if position.len() < 2 { return Err(invalid()) }
+
+    let lng = position[0].as_f64().ok_or_else(invalid)?;
+    let lat = position[1].as_f64().ok_or_else(invalid)?;
+
+    return Ok([lat, lng]);
+}
+
+/// Parse an array of positions into an array of dimension ``(n, 2)``.
+fn positions_from_value(value:&Value) -> Result<F64LatLngArray, FormatError> {
+    let positions = value.as_array()
+                         .ok_or_else(|| FormatError::InvalidGeometry(format!("{} is not an array of positions.", value)))?;
+
+    let flat = positions.iter()
+                        .map(position_from_value)
+                        .collect::<Result<Vec<_>, _>>()?
+                        .concat();
+
+    return Ok(F64LatLngArray::from_shape_vec((positions.len(), 2), flat).unwrap());
+}
+
+/// Convert an array of dimension ``(n, 2)`` into ``[longitude, latitude]`` positions.
+fn positions_to_value(s:&F64LatLngArray) -> Vec<Value> {
+    return s.axis_iter(Axis(0))
+            .map(| row | json!([row[1], row[0]]))
+            .collect();
+}
+
+/// Read all features from a GeoJSON text.
+///
+/// The text may be a ``FeatureCollection``, a single ``Feature`` or a bare
+/// geometry; the latter is returned as a feature without properties.
+pub fn read(text:&str) -> Result<Vec<Feature>, FormatError> {
+    let value: Value = serde_json::from_str(text).map_err(| err | FormatError::Parse(err.to_string()))?;
+
+    return match value.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => {
+            value.get("features")
+                 .and_then(Value::as_array)
+                 .ok_or_else(|| FormatError::Parse("FeatureCollection has no `features`.".to_string()))?
+                 .iter()
+                 .map(Feature::from_value)
+                 .collect()
+        },
+        Some("Feature") => Ok(vec![Feature::from_value(&value)?]),
+        Some(_) => Ok(vec![Feature::new(Geometry::from_value(&value)?, None)]),
+        None => Err(FormatError::Parse("GeoJSON object has no `type`.".to_string())),
+    };
+}
+
+/// Read all features from a GeoJSON file.
+pub fn read_file(path:&Path) -> Result<Vec<Feature>, FormatError> {
+    return read(&fs::read_to_string(path)?);
+}
+
+/// All vertices of all features stacked into a single array.
+///
+/// Returns a tuple of the array of dimension ``(n, 2)``, and the index of the
+/// feature each row belongs to. Unlocated features have no rows.
+pub fn read_points(features:&[Feature]) -> (F64LatLngArray, Vec<usize>) {
+    let located: Vec<usize> = (0..features.len()).filter(| idx | features[*idx].geometry.is_some()).collect();
+
+    let (points, indices) = stack_points(features.iter().filter_map(| feature | feature.geometry.as_ref()));
+
+    return (points, indices.into_iter().map(| idx | located[idx]).collect());
+}
+
+/// Write features as a GeoJSON ``FeatureCollection``.
+pub fn write(features:&[Feature]) -> String {
+    return json!({
+        "type": "FeatureCollection",
+        "features": features.iter().map(Feature::to_value).collect::<Vec<_>>(),
+    })
+    .to_string();
+}
+
+/// A ``Point`` feature for each row of ``s``.
+///
+/// Only the latitude and longitude columns are used; heights in column 2 of an
+/// ``(n, 3)`` array are discarded. If provided, ``properties`` must be of the
+/// same length as ``s``.
+pub fn points_to_features(
+    s:&dyn LatLngArray,
+    properties:Option<Vec<Properties>>,
+) -> Vec<Feature> {
+    let s_owned: F64LatLngArray = s.to_owned();
+    let n = s_owned.shape()[0];
+
+    let properties = properties.unwrap_or_else(|| vec![Properties::new(); n]);
+    assert!(properties.len() == n, "`properties` must be of the same length as `s`.");
+
+    return s_owned.axis_iter(Axis(0))
+                  .zip(properties.into_iter())
+                  .map(
+                      | (row, properties) | Feature::new(
+                          Geometry::Point(row.slice(ndarray::s![..2]).to_owned().into_shape((1, 2)).unwrap()),
+                          Some(properties),
+                      )
+                  )
+                  .collect();
+}
+
+/// A polygon approximating the geodesic circle of ``radius`` around ``centre``.
+///
+/// The vertices are found by displacing ``centre`` along ``segments`` evenly
+/// spaced bearings, starting due north and proceeding clockwise. The ring is
+/// closed, i.e. it has ``segments + 1`` vertices.
+pub fn buffer(
+    method:&CalculationMethod,
+    centre:&dyn LatLng,
+    radius:f64,
+    segments:usize,
+    settings: Option<&CalculationSettings>,
+) -> Geometry {
+    assert!(segments >= 3, "`segments` must be at least 3, yet {:?} provided.", segments);
+
+    let centres = F64LatLngArray::from_shape_fn((segments, 2), | (_, col) | centre[col]);
+    let distances = F64Array1::from_elem(segments, radius);
+    let bearings: F64Array1 = (0..segments).map(| k | 360. * k as f64 / segments as f64).collect();
+
+    let vertices = CalculationInterfaceInternal::<&F64Array1>::_displace(
+        method,
+        &centres,
+        &distances, &bearings,
+        settings,
+    );
+
+    let ring = concatenate(Axis(0), &[vertices.view(), vertices.slice(ndarray::s![0..1, ..])]).unwrap();
+
+    return Geometry::Polygon(vec![ring]);
+}
+
+/// Pairs of features from ``a`` and ``b`` with any vertices within ``distance``.
+///
+/// Returns a `Vec` of ``(a_index, b_index, distance)`` sorted by indices, where
+/// ``distance`` is the shortest distance between any vertex pair of the features.
+///
+/// .. note::
+///     Only vertices are compared; a point close to the middle of a long edge, or
+///     inside a polygon, is not considered within distance.
+pub fn within_distance(
+    method:&CalculationMethod,
+    a:&[Feature],
+    b:&[Feature],
+    distance:f64,
+    settings: Option<&CalculationSettings>,
+) -> Vec<(usize, usize, f64)> {
+    let (a_points, a_features) = read_points(a);
+    let (b_points, b_features) = read_points(b);
+
+    let tree = UnitVectorTree::new(&b_points);
+
+    let n = a_points.shape()[0];
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+    let min_len: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+    let found: Vec<Vec<(usize, f64)>> = {
+        (0..n)
+        .into_par_iter()
+        .with_min_len(min_len)
+        .map(
+            | i | method._within_distance_from_tree(
+                &a_points.row(i), &b_points, &tree,
+                distance,
+                | _ | true,
+                settings,
+            )
+        )
+        .collect()
+    };
+
+    let mut pairs: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+
+    for (i, distances) in found.into_iter().enumerate() {
+        for (j, d) in distances {
+            let entry = pairs.entry((a_features[i], b_features[j])).or_insert(f64::INFINITY);
+            *entry = entry.min(d);
+        }
+    }
+
+    return pairs.into_iter()
+                .map(| ((i, j), d) | (i, j, d))
+                .collect();
+}
@@ -0,0 +1,338 @@
+/// Python compatibility layer for the `formats.geojson` module.
+///
+/// The functions here are registered into a `formats.geojson` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.geojson`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyList;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+use serde_json::Value;
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+use crate::compatibility::python::check_latlng_columns;
+
+use crate::formats::python::geometry_to_py;
+
+use super::{
+    Feature,
+    Properties,
+    BUFFER_SEGMENTS,
+};
+
+/// Convert a list of Python `dict`s into feature properties, via `json`.
+pub fn properties_from_py(
+    properties: Option<&PyList>,
+    py: Python<'_>,
+) -> PyResult<Option<Vec<Properties>>> {
+    let json = py.import("json")?;
+
+    return properties.map(
+        | properties | {
+            properties.iter()
+                      .map(
+                          | item | {
+                              let text: String = json.call_method1("dumps", (item,))?.extract()?;
+
+                              match serde_json::from_str::<Value>(&text) {
+                                  Ok(Value::Object(properties)) => Ok(properties),
+                                  _ => Err(PyValueError::new_err("`properties` must be a list of JSON serialisable `dict`s.")),
+                              }
+                          }
+                      )
+                      .collect()
+        }
+    )
+    .transpose();
+}
+
+/// Convert feature properties into a Python `dict`, via `json`.
+pub fn properties_to_py(
+    properties: &Properties,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let text = Value::Object(properties.clone()).to_string();
+
+    return Ok(py.import("json")?.call_method1("loads", (text,))?.into_py(py));
+}
+
+/// Convert a feature into a tuple of ``(type, coordinates, properties)``.
+///
+/// ``type`` and ``coordinates`` are ``None`` for an unlocated feature.
+fn feature_to_py(
+    feature: &Feature,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    return Ok(
+        (
+            feature.geometry.as_ref().map(| geometry | geometry.kind()),
+            feature.geometry.as_ref().map(| geometry | geometry_to_py(geometry, py)),
+            properties_to_py(&feature.properties, py)?,
+        ).into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Read all features from a GeoJSON text.
+///
+/// Supports ``Point``, ``MultiPoint``, ``LineString`` and ``Polygon`` geometries.
+/// The text may be a ``FeatureCollection``, a single ``Feature`` or a bare
+/// geometry.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     GeoJSON text.
+///
+/// Returns
+/// -------
+/// List[Tuple[Optional[str], Optional[Union[numpy.ndarray, List[numpy.ndarray]]], dict]]
+///     ``(type, coordinates, properties)`` of each feature. ``coordinates`` are in
+///     order ``(latitude, longitude)``:
+///
+///     - of dimension ``(2)`` for ``Point``,
+///     - of dimension ``(n, 2)`` for ``MultiPoint`` and ``LineString``, or
+///     - a list of closed rings, each of dimension ``(n, 2)``, for ``Polygon``.
+///
+///     Both ``type`` and ``coordinates`` are ``None`` for a feature of ``null``
+///     geometry.
+fn read(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<Vec<PyObject>> {
+    return super::read(text)?
+                 .iter()
+                 .map(| feature | feature_to_py(feature, py))
+                 .collect();
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Read all vertices of all features from a GeoJSON text into a single array.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     GeoJSON text.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, features)``: ``points`` is of dimension ``(n, 2)`` in order
+///     ``(latitude, longitude)``, and ``features`` of dimension ``(n)`` contains the
+///     index of the feature each point belongs to.
+fn read_points(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let (points, feature_indices) = super::read_points(&super::read(text)?);
+
+    return Ok(
+        (
+            points.to_pyarray(py),
+            Array1::from_vec(feature_indices).to_pyarray(py),
+        ).into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, properties)")]
+/// Write an array of lat-long coordinates as ``Point`` features.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///     This can be the output of e.g. :meth:`CalculationMethod.displace`. Heights
+///     in column 2 of an ``(n, 3)`` array are discarded.
+///
+/// properties: Optional[List[dict]]
+///     JSON serialisable properties of each point, of the same length as ``s``.
+///
+/// Returns
+/// -------
+/// str
+///     GeoJSON ``FeatureCollection``.
+fn write_points(
+    s: &PyArray<f64, Ix2>,
+    properties: Option<&PyList>,
+    py: Python<'_>,
+) -> PyResult<String> {
+    check_latlng_columns("s", s)?;
+    let properties = properties_from_py(properties, py)?;
+
+    if let Some(properties) = &properties {
+        if properties.len() != s.shape()[0] {
+            return Err(PyValueError::new_err("`properties` must be of the same length as `s`."));
+        }
+    }
+
+    return Ok(super::write(&super::points_to_features(&s.to_owned_array(), properties)));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(centres, radius, segments, method, properties, *, settings)")]
+/// Write geodesic buffers around an array of lat-long coordinates as ``Polygon``
+/// features.
+///
+/// Parameters
+/// ----------
+/// centres: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// radius: numpy.float64
+///     Radius of each buffer.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// segments: Optional[int]
+///     Number of vertices of each buffer. Defaults to ``64``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the buffers. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// properties: Optional[List[dict]]
+///     JSON serialisable properties of each buffer, of the same length as
+///     ``centres``.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// str
+///     GeoJSON ``FeatureCollection``.
+fn write_buffers(
+    centres: &PyArray<f64, Ix2>,
+    radius: f64,
+    segments: Option<usize>,
+    method: Option<&enums::CalculationMethod>,
+    properties: Option<&PyList>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<String> {
+    let segments = segments.unwrap_or(BUFFER_SEGMENTS);
+    if segments < 3 {
+        return Err(PyValueError::new_err(format!("`segments` must be at least 3, yet {:?} provided.", segments)));
+    }
+
+    check_latlng_columns("centres", centres)?;
+
+    let centres = centres.to_owned_array();
+    let n = centres.shape()[0];
+    let properties = properties_from_py(properties, py)?.unwrap_or_else(|| vec![Properties::new(); n]);
+
+    if properties.len() != n {
+        return Err(PyValueError::new_err("`properties` must be of the same length as `centres`."));
+    }
+
+    let method_default = &enums::CalculationMethod::default();
+    let method = method.unwrap_or(method_default);
+
+    let features: Vec<Feature> = centres.rows()
+                                        .into_iter()
+                                        .zip(properties.into_iter())
+                                        .map(
+                                            | (centre, properties) | Feature::new(
+                                                super::buffer(method, &centre, radius, segments, settings),
+                                                Some(properties),
+                                            )
+                                        )
+                                        .collect();
+
+    return Ok(super::write(&features));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(a, b, distance, method, *, settings)")]
+/// Pairs of features between two GeoJSON layers that are within ``distance``.
+///
+/// All vertices of the features are compared; a pair of features is included if
+/// any pair of their vertices are within ``distance``.
+///
+/// Parameters
+/// ----------
+/// a: str
+///     GeoJSON text of the first layer.
+///
+/// b: str
+///     GeoJSON text of the second layer.
+///
+/// distance: numpy.float64
+///     Distance to check against.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]
+///     ``(a_indices, b_indices, distances)`` of each pair of features, where
+///     ``distances`` are the shortest distances between their vertices.
+fn within_distance(
+    a: &str,
+    b: &str,
+    distance: f64,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let pairs = super::within_distance(
+        method.unwrap_or(method_default),
+        &super::read(a)?, &super::read(b)?,
+        distance,
+        settings,
+    );
+
+    let a_indices: Array1<usize> = pairs.iter().map(| pair | pair.0).collect();
+    let b_indices: Array1<usize> = pairs.iter().map(| pair | pair.1).collect();
+    let distances: Array1<f64> = pairs.iter().map(| pair | pair.2).collect();
+
+    return Ok((a_indices.to_pyarray(py), b_indices.to_pyarray(py), distances.to_pyarray(py)).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(read_points, m)?)?;
+    m.add_function(wrap_pyfunction!(write_points, m)?)?;
+    m.add_function(wrap_pyfunction!(write_buffers, m)?)?;
+    m.add_function(wrap_pyfunction!(within_distance, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+/// Geometries shared by all formats.
+
+use ndarray::{
+    Axis,
+    concatenate,
+};
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use super::FormatError;
+
+/// A supported geometry, with coordinates in ``(latitude, longitude)``.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// Array of dimension ``(1, 2)``.
+    Point(F64LatLngArray),
+    MultiPoint(F64LatLngArray),
+    LineString(F64LatLngArray),
+
+    /// Linear rings, each closed; the first ring is the exterior and the rest are
+    /// holes.
+    Polygon(Vec<F64LatLngArray>),
+}
+impl Geometry {
+    /// The type name of this geometry, as used by GeoJSON.
+    pub fn kind(&self) -> &'static str {
+        return match self {
+            Self::Point(_) => "Point",
+            Self::MultiPoint(_) => "MultiPoint",
+            Self::LineString(_) => "LineString",
+            Self::Polygon(_) => "Polygon",
+        };
+    }
+
+    /// Check that this geometry is well formed:
+    ///
+    /// - all arrays are of dimension ``(n, 2)``,
+    /// - a ``Point`` has exactly 1 position,
+    /// - a ``LineString`` has at least 2 positions, and
+    /// - each ring of a ``Polygon`` is closed, with at least 4 positions.
+    pub fn validate(&self) -> Result<(), FormatError> {
+        let invalid = | msg:&str | Err(FormatError::InvalidGeometry(msg.to_string()));
+
+        let arrays: Vec<&F64LatLngArray> = match self {
+            Self::Point(s) | Self::MultiPoint(s) | Self::LineString(s) => vec![s],
+            Self::Polygon(rings) => rings.iter().collect(),
+        };
+        if arrays.iter().any(| s | s.shape()[1] != 2) {
+            return invalid("coordinates must be of dimension (n, 2).");
+        }
+
+        return match self {
+            Self::Point(s) if s.shape()[0] != 1 => invalid("Point must have exactly 1 position."),
+            Self::LineString(s) if s.shape()[0] < 2 => invalid("LineString must have at least 2 positions."),
+            Self::Polygon(rings) if rings.iter().any(
+                | ring | {
+                    let n = ring.shape()[0];
+                    n < 4 || ring.row(0) != ring.row(n-1)
+                }
+            ) => invalid("Polygon rings must be closed, with at least 4 positions."),
+            _ => Ok(()),
+        };
+    }
+
+    /// All vertices of this geometry as a single array.
+    ///
+    /// For polygons, the rings are stacked in order, including their closing
+    /// vertices.
+    pub fn points(&self) -> F64LatLngArray {
+        return match self {
+            Self::Point(s) | Self::MultiPoint(s) | Self::LineString(s) => s.clone(),
+            Self::Polygon(rings) => stack(rings.iter()),
+        };
+    }
+}
+
+/// Stack arrays of dimension ``(n, 2)`` into one.
+fn stack<'a, I>(arrays:I) -> F64LatLngArray
+where I: Iterator<Item=&'a F64LatLngArray>
+{
+    return concatenate(
+        Axis(0),
+        &arrays.map(| s | s.view()).collect::<Vec<_>>(),
+    )
+    .unwrap_or_else(| _ | F64LatLngArray::zeros((0, 2)));
+}
+
+/// All vertices of all geometries stacked into a single array.
+///
+/// Returns a tuple of the array of dimension ``(n, 2)``, and the index of the
+/// geometry each row belongs to.
+pub fn stack_points<'a, I>(geometries:I) -> (F64LatLngArray, Vec<usize>)
+where I: IntoIterator<Item=&'a Geometry>
+{
+    let arrays: Vec<F64LatLngArray> = geometries.into_iter().map(Geometry::points).collect();
+
+    let indices = arrays.iter()
+                        .enumerate()
+                        .flat_map(| (idx, s) | std::iter::repeat(idx).take(s.shape()[0]))
+                        .collect();
+
+    return (stack(arrays.iter()), indices);
+}
@@ -0,0 +1,195 @@
+/// GPX input.
+///
+/// Reads waypoints ``<wpt>``, routes ``<rte>`` and tracks ``<trk>`` of a
+/// `GPX 1.1 <https://www.topografix.com/GPX/1/1/>`_ (or 1.0) document, together
+/// with the elevation ``<ele>``, time ``<time>`` and name ``<name>`` of each
+/// point.
+///
+/// Elevations are in metres as stored in the file. Times are in seconds since the
+/// Unix epoch; see :mod:`~formats::time`. Missing elevations and times are
+/// ``NaN``.
+
+use std::fs;
+use std::path::Path;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationMethod,
+    CalculationSettings,
+};
+use crate::trajectory;
+
+use super::FormatError;
+use super::time::parse_timestamp;
+use super::xml::{
+    self,
+    Element,
+};
+
+// Import this if you want the gpx module to be available in Python.
+pub mod python;
+
+/// A sequence of points with per-point metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Points {
+    /// Array of dimension ``(n, 2)``, in order ``(latitude, longitude)``.
+    pub points: F64LatLngArray,
+
+    /// Array of dimension ``(n)``.
+    pub elevations: F64Array1,
+
+    /// Array of dimension ``(n)``, in seconds since the Unix epoch.
+    pub times: F64Array1,
+
+    pub names: Vec<Option<String>>,
+}
+impl Points {
+    /// Read points from elements such as ``<trkpt>``.
+    fn from_elements<'a, I>(elements:I) -> Result<Self, FormatError>
+    where I: IntoIterator<Item=&'a Element>
+    {
+        let (mut flat, mut elevations, mut times, mut names) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+        for element in elements {
+            for name in ["lat", "lon"] {
+                let value: f64 = element.attribute(name)
+                                        .and_then(| value | value.trim().parse().ok())
+                                        .ok_or_else(
+                                            || FormatError::InvalidGeometry(
+                                                format!("<{}> must have a numeric `{}` attribute.", element.name, name)
+                                            )
+                                        )?;
+                flat.push(value);
+            }
+
+            elevations.push(
+                element.child_text("ele")
+                       .and_then(| ele | ele.parse().ok())
+                       .unwrap_or(f64::NAN)
+            );
+            times.push(
+                match element.child_text("time") {
+                    Some(time) => parse_timestamp(time)?,
+                    None => f64::NAN,
+                }
+            );
+            names.push(element.child_text("name").map(str::to_string));
+        }
+
+        return Ok(
+            Self {
+                points: F64LatLngArray::from_shape_vec((names.len(), 2), flat).unwrap(),
+                elevations: F64Array1::from_vec(elevations),
+                times: F64Array1::from_vec(times),
+                names,
+            }
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        return self.names.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Distances between consecutive points, of dimension ``(n-1)``.
+    pub fn segment_distances(
+        &self,
+        method:&CalculationMethod,
+        settings: Option<&CalculationSettings>,
+    ) -> F64Array1 {
+        return trajectory::segment_lengths(method, &self.points, settings);
+    }
+
+    /// Total distance along the points.
+    pub fn length(
+        &self,
+        method:&CalculationMethod,
+        settings: Option<&CalculationSettings>,
+    ) -> f64 {
+        return self.segment_distances(method, settings).sum();
+    }
+}
+
+/// A track or route.
+///
+/// A route has exactly one segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub name: Option<String>,
+    pub segments: Vec<Points>,
+}
+impl Track {
+    /// Total distance along all segments; the gaps between segments are not
+    /// counted.
+    pub fn length(
+        &self,
+        method:&CalculationMethod,
+        settings: Option<&CalculationSettings>,
+    ) -> f64 {
+        return self.segments
+                   .iter()
+                   .map(| segment | segment.length(method, settings))
+                   .sum();
+    }
+}
+
+/// Contents of a GPX document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gpx {
+    pub waypoints: Points,
+    pub routes: Vec<Track>,
+    pub tracks: Vec<Track>,
+}
+
+/// Read a GPX text.
+pub fn read(text:&str) -> Result<Gpx, FormatError> {
+    let root = xml::parse(text)?;
+
+    if root.name != "gpx" {
+        return Err(FormatError::Parse(format!("root element must be <gpx>, yet <{}> found.", root.name)));
+    }
+
+    let routes = root.children_named("rte")
+                     .map(
+                         | route | Ok(
+                             Track {
+                                 name: route.child_text("name").map(str::to_string),
+                                 segments: vec![Points::from_elements(route.children_named("rtept"))?],
+                             }
+                         )
+                     )
+                     .collect::<Result<_, FormatError>>()?;
+
+    let tracks = root.children_named("trk")
+                     .map(
+                         | track | Ok(
+                             Track {
+                                 name: track.child_text("name").map(str::to_string),
+                                 segments: track.children_named("trkseg")
+                                                .map(| segment | Points::from_elements(segment.children_named("trkpt")))
+                                                .collect::<Result<_, FormatError>>()?,
+                             }
+                         )
+                     )
+                     .collect::<Result<_, FormatError>>()?;
+
+    return Ok(
+        Gpx {
+            waypoints: Points::from_elements(root.children_named("wpt"))?,
+            routes,
+            tracks,
+        }
+    );
+}
+
+/// Read a GPX file.
+pub fn read_file(path:&Path) -> Result<Gpx, FormatError> {
+    return read(&fs::read_to_string(path)?);
+}
@@ -0,0 +1,160 @@
+/// Python compatibility layer for the `formats.gpx` module.
+///
+/// The functions here are registered into a `formats.gpx` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.gpx`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use numpy::ndarray::Array1;
+use numpy::ToPyArray;
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    Points,
+    Track,
+};
+
+/// Convert points into a `dict` of ``points``, ``elevations``, ``times`` and
+/// ``names``.
+fn points_to_py(
+    points: &Points,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+
+    dict.set_item("points", points.points.to_pyarray(py))?;
+    dict.set_item("elevations", points.elevations.to_pyarray(py))?;
+    dict.set_item("times", points.times.to_pyarray(py))?;
+    dict.set_item("names", points.names.clone())?;
+
+    return Ok(dict.into_py(py));
+}
+
+/// Convert a track into a `dict` of ``name`` and ``segments``.
+fn track_to_py(
+    track: &Track,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+
+    dict.set_item("name", track.name.clone())?;
+    dict.set_item(
+        "segments",
+        track.segments
+             .iter()
+             .map(| segment | points_to_py(segment, py))
+             .collect::<PyResult<Vec<_>>>()?,
+    )?;
+
+    return Ok(dict.into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Read the waypoints, routes and tracks of a GPX text.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     GPX text.
+///
+/// Returns
+/// -------
+/// dict
+///     With keys:
+///
+///     - ``waypoints``: a points ``dict``,
+///     - ``routes``: a list of track ``dict``\s, each with exactly one segment,
+///       and
+///     - ``tracks``: a list of track ``dict``\s.
+///
+///     A track ``dict`` contains its ``name`` and a list of ``segments``, each
+///     a points ``dict``. A points ``dict`` contains:
+///
+///     - ``points``: of dimension ``(n, 2)``, in order ``(latitude, longitude)``,
+///     - ``elevations``: of dimension ``(n)``, in metres,
+///     - ``times``: of dimension ``(n)``, in seconds since the Unix epoch, and
+///     - ``names``: a list of the name of each point, if any.
+///
+///     Missing elevations and times are ``nan``.
+///
+/// Example
+/// -------
+/// Distances between consecutive points of the first track::
+///
+///     >>> from rust_geodistances import vincenty
+///     >>> from rust_geodistances.formats import gpx
+///     >>> points = gpx.read(text)["tracks"][0]["segments"][0]["points"]
+///     >>> vincenty.distance_pairwise(points[:-1], points[1:])
+fn read(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let gpx = super::read(text)?;
+    let dict = PyDict::new(py);
+
+    dict.set_item("waypoints", points_to_py(&gpx.waypoints, py)?)?;
+    dict.set_item(
+        "routes",
+        gpx.routes.iter().map(| route | track_to_py(route, py)).collect::<PyResult<Vec<_>>>()?,
+    )?;
+    dict.set_item(
+        "tracks",
+        gpx.tracks.iter().map(| track | track_to_py(track, py)).collect::<PyResult<Vec<_>>>()?,
+    )?;
+
+    return Ok(dict.into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text, method, *, settings)")]
+/// Total length of each track of a GPX text.
+///
+/// Gaps between the segments of a track are not counted.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     GPX text.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method. Defaults to :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.ndarray (dtype=numpy.float64)
+///     Dimension ``(t)``, the length of each track.
+fn track_lengths(
+    text: &str,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let method = method.unwrap_or(method_default);
+
+    let lengths: Array1<f64> = super::read(text)?.tracks
+                                                 .iter()
+                                                 .map(| track | track.length(method, settings))
+                                                 .collect();
+
+    return Ok(lengths.to_pyarray(py).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(track_lengths, m)?)?;
+
+    Ok(())
+}
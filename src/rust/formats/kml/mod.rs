@@ -0,0 +1,218 @@
+/// KML input.
+///
+/// Reads the ``<Placemark>``s of a `KML 2.2 <https://developers.google.com/kml/documentation/kmlreference>`_
+/// document, at any depth of ``<Document>`` and ``<Folder>``, together with their
+/// ``<name>`` and ``<description>``. Supported geometries are:
+///
+/// - ``<Point>``,
+/// - ``<LineString>`` and ``<LinearRing>``, both read as ``LineString``,
+/// - ``<Polygon>``, whose rings are closed if they are not already, and
+/// - ``<gx:Track>``, read as a ``LineString`` with a time for each point.
+///
+/// ``<MultiGeometry>`` and ``<gx:MultiTrack>`` are flattened into one
+/// :class:`Placemark` per geometry, sharing the same name and description.
+/// Placemarks without a geometry are skipped.
+///
+/// KML coordinates are in ``longitude,latitude[,altitude]`` order. All arrays in
+/// this module are in the ``(latitude, longitude)`` column order used by the rest
+/// of this crate.
+
+use std::fs;
+use std::path::Path;
+
+use ndarray::{
+    Axis,
+    concatenate,
+};
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use super::{
+    FormatError,
+    Geometry,
+};
+use super::time::parse_timestamp;
+use super::xml::{
+    self,
+    Element,
+};
+
+// Import this if you want the kml module to be available in Python.
+pub mod python;
+
+/// A placemark with a single geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placemark {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub geometry: Geometry,
+
+    /// Altitudes of each vertex, in the same order as :meth:`Geometry::points`;
+    /// ``NaN`` if absent.
+    pub elevations: F64Array1,
+
+    /// Times of each vertex in seconds since the Unix epoch, in the same order as
+    /// :meth:`Geometry::points`; ``NaN`` except for ``<gx:Track>``.
+    pub times: F64Array1,
+}
+
+/// Parse a list of ``x,y[,z]`` tuples into coordinates and altitudes.
+fn parse_coordinates(text:&str) -> Result<(F64LatLngArray, Vec<f64>), FormatError> {
+    let (mut flat, mut elevations) = (Vec::new(), Vec::new());
+
+    for tuple in text.split_whitespace() {
+        let values = tuple.split(',')
+                          .map(| value | value.parse::<f64>())
+                          .collect::<Result<Vec<f64>, _>>()
+                          .map_err(| _ | FormatError::Parse(format!("{:?} is not a KML coordinate.", tuple)))?;
+
+        if !(2..=3).contains(&values.len()) {
+            return Err(FormatError::Parse(format!("{:?} is not a KML coordinate.", tuple)));
+        }
+
+        flat.extend([values[1], values[0]]);
+        elevations.push(values.get(2).copied().unwrap_or(f64::NAN));
+    }
+
+    return Ok((F64LatLngArray::from_shape_vec((elevations.len(), 2), flat).unwrap(), elevations));
+}
+
+/// Parse the ``<coordinates>`` of an element.
+fn coordinates_of(element:&Element) -> Result<(F64LatLngArray, Vec<f64>), FormatError> {
+    return parse_coordinates(
+        element.child_text("coordinates")
+               .ok_or_else(|| FormatError::InvalidGeometry(format!("<{}> has no <coordinates>.", element.name)))?
+    );
+}
+
+/// Parse a ``<LinearRing>``, closing it if necessary.
+fn ring_of(element:&Element) -> Result<(F64LatLngArray, Vec<f64>), FormatError> {
+    let (ring, mut elevations) = coordinates_of(element)?;
+    let n = ring.shape()[0];
+
+    if n > 0 && ring.row(0) != ring.row(n-1) {
+        elevations.push(elevations[0]);
+        return Ok((concatenate(Axis(0), &[ring.view(), ring.slice(ndarray::s![..1, ..])]).unwrap(), elevations));
+    }
+
+    return Ok((ring, elevations));
+}
+
+/// Parse a ``<gx:Track>`` into its coordinates, altitudes and times.
+fn track_of(element:&Element) -> Result<(F64LatLngArray, Vec<f64>, Vec<f64>), FormatError> {
+    let (mut flat, mut elevations) = (Vec::new(), Vec::new());
+
+    for coord in element.children_named("coord") {
+        let values = coord.text
+                          .split_whitespace()
+                          .map(| value | value.parse::<f64>())
+                          .collect::<Result<Vec<f64>, _>>()
+                          .ok()
+                          .filter(| values | (2..=3).contains(&values.len()))
+                          .ok_or_else(|| FormatError::Parse(format!("{:?} is not a gx:coord.", coord.text)))?;
+
+        flat.extend([values[1], values[0]]);
+        elevations.push(values.get(2).copied().unwrap_or(f64::NAN));
+    }
+
+    let times = element.children_named("when")
+                       .map(| when | parse_timestamp(&when.text))
+                       .collect::<Result<Vec<f64>, FormatError>>()?;
+
+    if times.len() != elevations.len() {
+        return Err(FormatError::InvalidGeometry("<gx:Track> must have as many <when> as <gx:coord>.".to_string()));
+    }
+
+    return Ok((F64LatLngArray::from_shape_vec((elevations.len(), 2), flat).unwrap(), elevations, times));
+}
+
+/// All geometries in an element, with their altitudes and times.
+fn geometries_of(element:&Element) -> Result<Vec<(Geometry, Vec<f64>, Vec<f64>)>, FormatError> {
+    let mut geometries = Vec::new();
+
+    // Only ``<gx:Track>`` has times.
+    let untimed = | elevations:Vec<f64> | {
+        let times = vec![f64::NAN; elevations.len()];
+        (elevations, times)
+    };
+
+    for child in element.children.iter() {
+        let (geometry, (elevations, times)) = match child.name.as_str() {
+            "Point" => {
+                let (point, elevations) = coordinates_of(child)?;
+                (Geometry::Point(point), untimed(elevations))
+            },
+            "LineString" | "LinearRing" => {
+                let (line, elevations) = coordinates_of(child)?;
+                (Geometry::LineString(line), untimed(elevations))
+            },
+            "Polygon" => {
+                let boundaries = child.children_named("outerBoundaryIs")
+                                      .chain(child.children_named("innerBoundaryIs"))
+                                      .map(
+                                          | boundary | ring_of(
+                                              boundary.child("LinearRing").ok_or_else(
+                                                  || FormatError::InvalidGeometry(format!("<{}> has no <LinearRing>.", boundary.name))
+                                              )?
+                                          )
+                                      )
+                                      .collect::<Result<Vec<_>, FormatError>>()?;
+
+                let elevations = boundaries.iter().flat_map(| (_, elevations) | elevations.iter().copied()).collect();
+                (Geometry::Polygon(boundaries.into_iter().map(| (ring, _) | ring).collect()), untimed(elevations))
+            },
+            "Track" => {
+                let (track, elevations, times) = track_of(child)?;
+                (Geometry::LineString(track), (elevations, times))
+            },
+            "MultiGeometry" | "MultiTrack" => {
+                geometries.extend(geometries_of(child)?);
+                continue;
+            },
+            _ => continue,
+        };
+
+        geometry.validate()?;
+        geometries.push((geometry, elevations, times));
+    }
+
+    return Ok(geometries);
+}
+
+/// Read all placemarks from a KML text.
+pub fn read(text:&str) -> Result<Vec<Placemark>, FormatError> {
+    let root = xml::parse(text)?;
+
+    if root.name != "kml" {
+        return Err(FormatError::Parse(format!("root element must be <kml>, yet <{}> found.", root.name)));
+    }
+
+    let mut placemarks = Vec::new();
+
+    for placemark in root.descendants_named("Placemark") {
+        let name = placemark.child_text("name").map(str::to_string);
+        let description = placemark.child_text("description").map(str::to_string);
+
+        for (geometry, elevations, times) in geometries_of(placemark)? {
+            placemarks.push(
+                Placemark {
+                    name: name.clone(),
+                    description: description.clone(),
+                    geometry,
+                    elevations: F64Array1::from_vec(elevations),
+                    times: F64Array1::from_vec(times),
+                }
+            );
+        }
+    }
+
+    return Ok(placemarks);
+}
+
+/// Read all placemarks from a KML file.
+pub fn read_file(path:&Path) -> Result<Vec<Placemark>, FormatError> {
+    return read(&fs::read_to_string(path)?);
+}
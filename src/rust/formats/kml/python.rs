@@ -0,0 +1,106 @@
+/// Python compatibility layer for the `formats.kml` module.
+///
+/// The functions here are registered into a `formats.kml` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.kml`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use numpy::ndarray::Array1;
+use numpy::ToPyArray;
+
+use crate::formats::geometry::stack_points;
+use crate::formats::python::geometry_to_py;
+
+use super::Placemark;
+
+/// Convert a placemark into a `dict`.
+fn placemark_to_py(
+    placemark: &Placemark,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+
+    dict.set_item("name", placemark.name.clone())?;
+    dict.set_item("description", placemark.description.clone())?;
+    dict.set_item("type", placemark.geometry.kind())?;
+    dict.set_item("coordinates", geometry_to_py(&placemark.geometry, py))?;
+    dict.set_item("elevations", placemark.elevations.to_pyarray(py))?;
+    dict.set_item("times", placemark.times.to_pyarray(py))?;
+
+    return Ok(dict.into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Read all placemarks from a KML text.
+///
+/// Supports ``Point``, ``LineString``, ``LinearRing``, ``Polygon`` and
+/// ``gx:Track`` geometries. ``MultiGeometry`` and ``gx:MultiTrack`` are flattened
+/// into one placemark per geometry.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     KML text.
+///
+/// Returns
+/// -------
+/// List[dict]
+///     Each with keys:
+///
+///     - ``name`` and ``description``, if any,
+///     - ``type``: one of ``Point``, ``LineString`` or ``Polygon``,
+///     - ``coordinates``: in the same form as returned by
+///       :func:`rust_geodistances.formats.geojson.read`,
+///     - ``elevations``: of dimension ``(n)``, the altitude of each vertex, and
+///     - ``times``: of dimension ``(n)``, the time of each vertex in seconds
+///       since the Unix epoch; only available for ``gx:Track``.
+///
+///     Missing elevations and times are ``nan``.
+fn read(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<Vec<PyObject>> {
+    return super::read(text)?
+                 .iter()
+                 .map(| placemark | placemark_to_py(placemark, py))
+                 .collect();
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Read all vertices of all placemarks from a KML text into a single array.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     KML text.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, placemarks)``: ``points`` is of dimension ``(n, 2)`` in order
+///     ``(latitude, longitude)``, and ``placemarks`` of dimension ``(n)``
+///     contains the index of the placemark each point belongs to.
+fn read_points(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let placemarks = super::read(text)?;
+    let (points, indices) = stack_points(placemarks.iter().map(| placemark | &placemark.geometry));
+
+    return Ok((points.to_pyarray(py), Array1::from_vec(indices).to_pyarray(py)).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(read_points, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+/// Readers and writers of geospatial file formats.
+///
+/// Each format lives in its own submodule, converting between the format and the
+/// ``(latitude, longitude)`` arrays used throughout this crate. Beware that most
+/// formats store coordinates in ``(longitude, latitude)`` order instead; the
+/// submodules take care of swapping the columns.
+
+use std::error::Error;
+use std::fmt;
+
+pub mod geometry;
+pub use geometry::Geometry;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod csv;
+pub mod geojson;
+pub mod gpx;
+pub mod kml;
+pub mod time;
+pub mod wkb;
+pub mod wkt;
+pub mod xml;
+
+// Import this if you want the formats module to be available in Python.
+pub mod python;
+
+/// Errors raised while reading or writing a format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    /// The input could not be parsed at all.
+    Parse(String),
+
+    /// The input was parsed, but a geometry in it is malformed.
+    InvalidGeometry(String),
+
+    /// The geometry is valid, but not supported by this crate.
+    UnsupportedGeometry(String),
+
+    /// The input does not have the expected columns.
+    Schema(String),
+
+    /// Reading or writing a file failed.
+    Io(String),
+}
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Self::Parse(msg) => write!(f, "Failed to parse input: {}", msg),
+            Self::InvalidGeometry(msg) => write!(f, "Invalid geometry: {}", msg),
+            Self::UnsupportedGeometry(kind) => write!(f, "Unsupported geometry type {:?}.", kind),
+            Self::Schema(msg) => write!(f, "Invalid schema: {}", msg),
+            Self::Io(msg) => write!(f, "I/O error: {}", msg),
+        };
+    }
+}
+impl Error for FormatError {}
+
+impl From<std::io::Error> for FormatError {
+    fn from(err: std::io::Error) -> Self {
+        return Self::Io(err.to_string());
+    }
+}
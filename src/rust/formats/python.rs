@@ -0,0 +1,116 @@
+/// Python compatibility layer for the `formats` module.
+///
+/// Each format is registered as a further submodule of `formats`, e.g.
+/// :mod:`rust_geodistances.formats.geojson`.
+///
+/// Geometries are represented in Python as ``(type, coordinates)``, where
+/// ``coordinates`` are in order ``(latitude, longitude)``:
+///
+/// - of dimension ``(2)`` for ``Point``,
+/// - of dimension ``(n, 2)`` for ``MultiPoint`` and ``LineString``, or
+/// - a list of closed rings, each of dimension ``(n, 2)``, for ``Polygon``.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use super::{
+    FormatError,
+    Geometry,
+};
+
+impl From<FormatError> for PyErr {
+    fn from(err: FormatError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+/// Convert the coordinates of a geometry into `numpy` arrays.
+pub fn geometry_to_py(
+    geometry: &Geometry,
+    py: Python<'_>,
+) -> PyObject {
+    return match geometry {
+        Geometry::Point(s) => s.row(0).to_pyarray(py).into_py(py),
+        Geometry::MultiPoint(s) | Geometry::LineString(s) => s.to_pyarray(py).into_py(py),
+        Geometry::Polygon(rings) => {
+            rings.iter()
+                 .map(| ring | ring.to_pyarray(py))
+                 .collect::<Vec<_>>()
+                 .into_py(py)
+        },
+    };
+}
+
+/// Convert a geometry type and its coordinates from Python.
+pub fn geometry_from_py(
+    kind: &str,
+    coordinates: &PyAny,
+) -> PyResult<Geometry> {
+    let geometry = match kind {
+        "Point" => {
+            let point = coordinates.extract::<&PyArray<f64, Ix1>>()?.to_owned_array();
+            if point.len() != 2 {
+                return Err(PyValueError::new_err("`coordinates` of a Point must be of dimension (2)."));
+            }
+            Geometry::Point(point.into_shape((1, 2)).unwrap())
+        },
+        "MultiPoint" => Geometry::MultiPoint(coordinates.extract::<&PyArray<f64, Ix2>>()?.to_owned_array()),
+        "LineString" => Geometry::LineString(coordinates.extract::<&PyArray<f64, Ix2>>()?.to_owned_array()),
+        "Polygon" => Geometry::Polygon(
+            coordinates.extract::<Vec<&PyArray<f64, Ix2>>>()?
+                       .into_iter()
+                       .map(| ring | ring.to_owned_array())
+                       .collect()
+        ),
+        other => return Err(FormatError::UnsupportedGeometry(other.to_string()).into()),
+    };
+
+    geometry.validate()?;
+
+    return Ok(geometry);
+}
+
+/// Add all the format submodules to a Python module.
+pub fn register(py: Python, m: &PyModule) -> PyResult<()> {
+    #[cfg(feature = "arrow")]
+    {
+        let arrow_module = PyModule::new(py, "arrow")?;
+        super::arrow::python::register(py, arrow_module)?;
+        m.add_submodule(arrow_module)?;
+    }
+
+    let csv_module = PyModule::new(py, "csv")?;
+    super::csv::python::register(py, csv_module)?;
+    m.add_submodule(csv_module)?;
+
+    let geojson_module = PyModule::new(py, "geojson")?;
+    super::geojson::python::register(py, geojson_module)?;
+    m.add_submodule(geojson_module)?;
+
+    let gpx_module = PyModule::new(py, "gpx")?;
+    super::gpx::python::register(py, gpx_module)?;
+    m.add_submodule(gpx_module)?;
+
+    let kml_module = PyModule::new(py, "kml")?;
+    super::kml::python::register(py, kml_module)?;
+    m.add_submodule(kml_module)?;
+
+    let wkb_module = PyModule::new(py, "wkb")?;
+    super::wkb::python::register(py, wkb_module)?;
+    m.add_submodule(wkb_module)?;
+
+    let wkt_module = PyModule::new(py, "wkt")?;
+    super::wkt::python::register(py, wkt_module)?;
+    m.add_submodule(wkt_module)?;
+
+    Ok(())
+}
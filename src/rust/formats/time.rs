@@ -0,0 +1,86 @@
+/// Timestamps shared by all formats.
+///
+/// Times are represented as ``f64`` seconds since the Unix epoch in UTC, with
+/// ``NaN`` for missing values, so that they can be stored in arrays alongside
+/// coordinates.
+
+use super::FormatError;
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+///
+/// Algorithm from `Howard Hinnant <https://howardhinnant.github.io/date_algorithms.html>`_.
+fn days_from_civil(year:i64, month:i64, day:i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    return era * 146097 + day_of_era - 719468;
+}
+
+/// Parse an ISO 8601 / RFC 3339 timestamp into seconds since the Unix epoch.
+///
+/// Accepts ``YYYY-MM-DDTHH:MM:SS`` with optional fractional seconds and an
+/// optional ``Z`` or ``±HH:MM`` offset; timestamps without an offset are taken as
+/// UTC. A space is also accepted in place of ``T``, and a date alone is taken as
+/// midnight.
+pub fn parse_timestamp(text:&str) -> Result<f64, FormatError> {
+    let text = text.trim();
+    let invalid = || FormatError::Parse(format!("{:?} is not an ISO 8601 timestamp.", text));
+
+    let number = | start:usize, len:usize | -> Result<i64, FormatError> {
+        let digits = text.get(start..start+len).ok_or_else(invalid)?;
+        if !digits.bytes().all(| c | c.is_ascii_digit()) { return Err(invalid()) }
+
+        return digits.parse().map_err(| _ | invalid());
+    };
+    let separator = | pos:usize, allowed:&[u8] | -> Result<(), FormatError> {
+        return match text.as_bytes().get(pos) {
+            Some(c) if allowed.contains(c) => Ok(()),
+            _ => Err(invalid()),
+        };
+    };
+
+    let (year, month, day) = (number(0, 4)?, number(5, 2)?, number(8, 2)?);
+    separator(4, b"-")?;
+    separator(7, b"-")?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) { return Err(invalid()) }
+
+    let mut seconds = (days_from_civil(year, month, day) * 86400) as f64;
+    if text.len() == 10 { return Ok(seconds) }
+
+    separator(10, b"Tt ")?;
+    separator(13, b":")?;
+    separator(16, b":")?;
+    let (hour, minute, second) = (number(11, 2)?, number(14, 2)?, number(17, 2)?);
+    if hour > 23 || minute > 59 || second > 60 { return Err(invalid()) }
+
+    seconds += (hour * 3600 + minute * 60 + second) as f64;
+
+    let mut rest = &text[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.find(| c:char | !c.is_ascii_digit()).unwrap_or(fraction.len());
+        if len == 0 { return Err(invalid()) }
+
+        seconds += format!("0.{}", &fraction[..len]).parse::<f64>().map_err(| _ | invalid())?;
+        rest = &fraction[len..];
+    }
+
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return Err(invalid()),
+            };
+            let digits = rest[1..].replace(':', "");
+            if digits.len() != 4 || !digits.bytes().all(| c | c.is_ascii_digit()) { return Err(invalid()) }
+
+            sign * (digits[..2].parse::<i64>().unwrap() * 3600 + digits[2..].parse::<i64>().unwrap() * 60)
+        },
+    };
+
+    return Ok(seconds - offset as f64);
+}
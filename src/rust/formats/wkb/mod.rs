@@ -0,0 +1,246 @@
+/// Well-Known Binary input and output.
+///
+/// Parses ``Point``, ``MultiPoint``, ``LineString`` and ``Polygon`` geometries in
+/// either byte order, encoded as:
+///
+/// - ISO WKB, where Z, M and ZM dimensions are flagged by adding ``1000``,
+///   ``2000`` and ``3000`` to the geometry type, or
+/// - PostGIS Extended WKB (EWKB), where the dimensions and an embedded SRID are
+///   flagged by the high bits of the geometry type.
+///
+/// Any Z and M values are discarded. Geometries are written as little-endian WKB,
+/// or EWKB if an SRID is provided. PostGIS commonly returns WKB as hexadecimal
+/// text; see :func:`parse_hex` and :func:`to_hex`.
+///
+/// WKB positions are in ``x y`` i.e. ``longitude latitude`` order. All arrays in
+/// this module are in the ``(latitude, longitude)`` column order used by the rest
+/// of this crate.
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use super::{
+    FormatError,
+    Geometry,
+};
+
+// Import this if you want the wkb module to be available in Python.
+pub mod python;
+
+const WKB_POINT:u32 = 1;
+const WKB_LINESTRING:u32 = 2;
+const WKB_POLYGON:u32 = 3;
+const WKB_MULTIPOINT:u32 = 4;
+
+const EWKB_Z_FLAG:u32 = 0x80000000;
+const EWKB_M_FLAG:u32 = 0x40000000;
+const EWKB_SRID_FLAG:u32 = 0x20000000;
+
+/// A cursor over WKB bytes.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+impl<'a> Reader<'a> {
+    fn take(&mut self, len:usize) -> Result<&'a [u8], FormatError> {
+        if self.pos + len > self.data.len() {
+            return Err(FormatError::Parse(format!("unexpected end of WKB at byte {:?}.", self.pos)));
+        }
+
+        let bytes = &self.data[self.pos..self.pos+len];
+        self.pos += len;
+
+        return Ok(bytes);
+    }
+
+    fn u8(&mut self) -> Result<u8, FormatError> {
+        return Ok(self.take(1)?[0]);
+    }
+
+    fn u32(&mut self, little_endian:bool) -> Result<u32, FormatError> {
+        let bytes: [u8; 4] = self.take(4)?.try_into().unwrap();
+
+        return Ok(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) });
+    }
+
+    fn f64(&mut self, little_endian:bool) -> Result<f64, FormatError> {
+        let bytes: [u8; 8] = self.take(8)?.try_into().unwrap();
+
+        return Ok(if little_endian { f64::from_le_bytes(bytes) } else { f64::from_be_bytes(bytes) });
+    }
+
+    /// Read ``count`` positions of ``dimensions`` values each.
+    fn positions(&mut self, count:usize, dimensions:usize, little_endian:bool) -> Result<F64LatLngArray, FormatError> {
+        let mut flat: Vec<f64> = Vec::new();
+
+        for _ in 0..count {
+            let (lng, lat) = (self.f64(little_endian)?, self.f64(little_endian)?);
+            for _ in 2..dimensions { self.f64(little_endian)?; }
+
+            flat.extend([lat, lng]);
+        }
+
+        return Ok(F64LatLngArray::from_shape_vec((count, 2), flat).unwrap());
+    }
+
+    /// Read a geometry and its SRID, if any.
+    fn geometry(&mut self) -> Result<(Geometry, Option<u32>), FormatError> {
+        let little_endian = match self.u8()? {
+            0 => false,
+            1 => true,
+            other => return Err(FormatError::Parse(format!("invalid byte order {:?}.", other))),
+        };
+
+        let raw_type = self.u32(little_endian)?;
+        let base_type = raw_type & 0x0FFFFFFF;
+        let (kind, iso_dimensions) = (base_type % 1000, base_type / 1000);
+
+        let has_z = raw_type & EWKB_Z_FLAG != 0 || iso_dimensions == 1 || iso_dimensions == 3;
+        let has_m = raw_type & EWKB_M_FLAG != 0 || iso_dimensions == 2 || iso_dimensions == 3;
+        let dimensions = 2 + has_z as usize + has_m as usize;
+
+        let srid = if raw_type & EWKB_SRID_FLAG != 0 {
+            Some(self.u32(little_endian)?)
+        } else {
+            None
+        };
+
+        let geometry = match kind {
+            WKB_POINT => {
+                let point = self.positions(1, dimensions, little_endian)?;
+                if point.iter().any(| v | v.is_nan()) {
+                    return Err(FormatError::InvalidGeometry("empty Point is not supported.".to_string()));
+                }
+                Geometry::Point(point)
+            },
+            WKB_LINESTRING => {
+                let count = self.u32(little_endian)? as usize;
+                Geometry::LineString(self.positions(count, dimensions, little_endian)?)
+            },
+            WKB_POLYGON => {
+                let count = self.u32(little_endian)? as usize;
+                let rings = (0..count).map(
+                    | _ | {
+                        let len = self.u32(little_endian)? as usize;
+                        self.positions(len, dimensions, little_endian)
+                    }
+                )
+                .collect::<Result<Vec<_>, _>>()?;
+                Geometry::Polygon(rings)
+            },
+            WKB_MULTIPOINT => {
+                let count = self.u32(little_endian)? as usize;
+                let mut flat: Vec<f64> = Vec::new();
+
+                for _ in 0..count {
+                    match self.geometry()? {
+                        (Geometry::Point(point), _) => flat.extend(point.iter()),
+                        (other, _) => return Err(
+                            FormatError::InvalidGeometry(format!("MultiPoint cannot contain {}.", other.kind()))
+                        ),
+                    }
+                }
+                Geometry::MultiPoint(F64LatLngArray::from_shape_vec((count, 2), flat).unwrap())
+            },
+            other => return Err(FormatError::UnsupportedGeometry(format!("WKB type {}", other))),
+        };
+
+        return Ok((geometry, srid));
+    }
+}
+
+/// Parse WKB or EWKB bytes into a geometry and its SRID, if any.
+pub fn parse(data:&[u8]) -> Result<(Geometry, Option<u32>), FormatError> {
+    let mut reader = Reader { data, pos: 0 };
+    let (geometry, srid) = reader.geometry()?;
+
+    if reader.pos != data.len() {
+        return Err(FormatError::Parse(format!("{:?} trailing bytes after WKB.", data.len() - reader.pos)));
+    }
+
+    geometry.validate()?;
+
+    return Ok((geometry, srid));
+}
+
+/// Decode hexadecimal text, as returned by PostGIS, into bytes.
+pub fn parse_hex(text:&str) -> Result<Vec<u8>, FormatError> {
+    let text = text.trim();
+    let text = text.strip_prefix("\\x").unwrap_or(text);
+
+    if !text.is_ascii() || text.len() % 2 != 0 {
+        return Err(FormatError::Parse("hexadecimal WKB must have an even number of ASCII digits.".to_string()));
+    }
+
+    return (0..text.len())
+           .step_by(2)
+           .map(
+               | i | u8::from_str_radix(&text[i..i+2], 16)
+                         .map_err(| _ | FormatError::Parse(format!("{:?} is not hexadecimal.", &text[i..i+2])))
+           )
+           .collect();
+}
+
+/// Encode bytes as upper case hexadecimal text.
+pub fn to_hex(data:&[u8]) -> String {
+    return data.iter().map(| byte | format!("{:02X}", byte)).collect();
+}
+
+/// Append the header of a geometry.
+fn write_header(buffer:&mut Vec<u8>, kind:u32, srid:Option<u32>) {
+    buffer.push(1);
+
+    match srid {
+        Some(srid) => {
+            buffer.extend((kind | EWKB_SRID_FLAG).to_le_bytes());
+            buffer.extend(srid.to_le_bytes());
+        },
+        None => buffer.extend(kind.to_le_bytes()),
+    }
+}
+
+/// Append an array of dimension ``(n, 2)`` as ``x y`` positions.
+fn write_positions(buffer:&mut Vec<u8>, s:&F64LatLngArray) {
+    for row in s.axis_iter(Axis(0)) {
+        buffer.extend(row[1].to_le_bytes());
+        buffer.extend(row[0].to_le_bytes());
+    }
+}
+
+/// Write a geometry as little-endian WKB; if ``srid`` is provided, as EWKB.
+pub fn write(geometry:&Geometry, srid:Option<u32>) -> Vec<u8> {
+    let mut buffer: Vec<u8> = Vec::new();
+
+    match geometry {
+        Geometry::Point(s) => {
+            write_header(&mut buffer, WKB_POINT, srid);
+            write_positions(&mut buffer, s);
+        },
+        Geometry::LineString(s) => {
+            write_header(&mut buffer, WKB_LINESTRING, srid);
+            buffer.extend((s.shape()[0] as u32).to_le_bytes());
+            write_positions(&mut buffer, s);
+        },
+        Geometry::Polygon(rings) => {
+            write_header(&mut buffer, WKB_POLYGON, srid);
+            buffer.extend((rings.len() as u32).to_le_bytes());
+            for ring in rings {
+                buffer.extend((ring.shape()[0] as u32).to_le_bytes());
+                write_positions(&mut buffer, ring);
+            }
+        },
+        Geometry::MultiPoint(s) => {
+            write_header(&mut buffer, WKB_MULTIPOINT, srid);
+            buffer.extend((s.shape()[0] as u32).to_le_bytes());
+            for k in 0..s.shape()[0] {
+                write_header(&mut buffer, WKB_POINT, None);
+                write_positions(&mut buffer, &s.slice(ndarray::s![k..k+1, ..]).to_owned());
+            }
+        },
+    }
+
+    return buffer;
+}
@@ -0,0 +1,180 @@
+/// Python compatibility layer for the `formats.wkb` module.
+///
+/// The functions here are registered into a `formats.wkb` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.wkb`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyTypeError;
+use pyo3::types::PyBytes;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::formats::geometry::stack_points;
+use crate::formats::python::{
+    geometry_from_py,
+    geometry_to_py,
+};
+use crate::formats::{
+    FormatError,
+    Geometry,
+};
+
+/// Parse WKB from either `bytes` or hexadecimal `str`.
+fn parse_py(data: &PyAny) -> PyResult<(Geometry, Option<u32>)> {
+    let result: Result<_, FormatError> = if let Ok(bytes) = data.extract::<&[u8]>() {
+        super::parse(bytes)
+    } else if let Ok(text) = data.extract::<&str>() {
+        super::parse(&super::parse_hex(text)?)
+    } else {
+        return Err(PyTypeError::new_err("WKB must be `bytes` or a hexadecimal `str`."));
+    };
+
+    return Ok(result?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(data)")]
+/// Parse a WKB or EWKB geometry.
+///
+/// Supports ``Point``, ``MultiPoint``, ``LineString`` and ``Polygon`` in either
+/// byte order; any Z and M values are discarded.
+///
+/// Parameters
+/// ----------
+/// data: Union[bytes, str]
+///     WKB bytes, or hexadecimal text as returned by PostGIS.
+///
+/// Returns
+/// -------
+/// Tuple[str, Union[numpy.ndarray, List[numpy.ndarray]], Optional[int]]
+///     ``(type, coordinates, srid)``. ``coordinates`` are in order
+///     ``(latitude, longitude)``:
+///
+///     - of dimension ``(2)`` for ``Point``,
+///     - of dimension ``(n, 2)`` for ``MultiPoint`` and ``LineString``, or
+///     - a list of closed rings, each of dimension ``(n, 2)``, for ``Polygon``.
+fn read(
+    data: &PyAny,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let (geometry, srid) = parse_py(data)?;
+
+    return Ok((geometry.kind(), geometry_to_py(&geometry, py), srid).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(column)")]
+/// Parse a column of WKB geometries into a single array of all their vertices.
+///
+/// This allows a WKB column from PostGIS to be passed directly to any
+/// :class:`CalculationMethod` method.
+///
+/// Parameters
+/// ----------
+/// column: List[Union[bytes, str]]
+///     WKB bytes, or hexadecimal text as returned by PostGIS.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, geometries)``: ``points`` is of dimension ``(n, 2)`` in order
+///     ``(latitude, longitude)``, and ``geometries`` of dimension ``(n)`` contains
+///     the index of the geometry each point belongs to.
+fn read_points(
+    column: Vec<&PyAny>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let geometries = column.into_iter()
+                           .map(| data | Ok(parse_py(data)?.0))
+                           .collect::<PyResult<Vec<Geometry>>>()?;
+
+    let (points, indices) = stack_points(geometries.iter());
+
+    return Ok((points.to_pyarray(py), Array1::from_vec(indices).to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(kind, coordinates, srid)")]
+/// Write a geometry as little-endian WKB.
+///
+/// Parameters
+/// ----------
+/// kind: str
+///     One of ``Point``, ``MultiPoint``, ``LineString`` or ``Polygon``.
+///
+/// coordinates: Union[numpy.ndarray, List[numpy.ndarray]]
+///     In the same form as returned by :func:`read`.
+///
+/// srid: Optional[int]
+///     If provided, EWKB is written with this SRID.
+///
+/// Returns
+/// -------
+/// bytes
+///     WKB bytes.
+fn write(
+    kind: &str,
+    coordinates: &PyAny,
+    srid: Option<u32>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let data = super::write(&geometry_from_py(kind, coordinates)?, srid);
+
+    return Ok(PyBytes::new(py, &data).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, srid)")]
+/// Write each row of an array of lat-long coordinates as a WKB ``Point``.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// srid: Optional[int]
+///     If provided, EWKB is written with this SRID.
+///
+/// Returns
+/// -------
+/// List[bytes]
+///     WKB bytes of each point.
+fn write_points(
+    s: &PyArray<f64, Ix2>,
+    srid: Option<u32>,
+    py: Python<'_>,
+) -> PyResult<Vec<PyObject>> {
+    return Ok(
+        s.to_owned_array()
+         .rows()
+         .into_iter()
+         .map(
+             | row | {
+                 let data = super::write(&Geometry::Point(row.to_owned().into_shape((1, 2)).unwrap()), srid);
+                 PyBytes::new(py, &data).into_py(py)
+             }
+         )
+         .collect()
+    );
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(read_points, m)?)?;
+    m.add_function(wrap_pyfunction!(write, m)?)?;
+    m.add_function(wrap_pyfunction!(write_points, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+/// Well-Known Text input and output.
+///
+/// Parses ``POINT``, ``MULTIPOINT``, ``LINESTRING`` and ``POLYGON`` geometries,
+/// optionally with ``Z``, ``M`` or ``ZM`` dimensions which are discarded, and
+/// optionally prefixed with an EWKT ``SRID=<srid>;``.
+///
+/// WKT positions are in ``x y`` i.e. ``longitude latitude`` order. All arrays in
+/// this module are in the ``(latitude, longitude)`` column order used by the rest
+/// of this crate.
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64LatLngArray,
+};
+
+use super::{
+    FormatError,
+    Geometry,
+};
+
+// Import this if you want the wkt module to be available in Python.
+pub mod python;
+
+/// A cursor over a WKT text.
+struct Tokens<'a> {
+    text: &'a str,
+    pos: usize,
+}
+impl<'a> Tokens<'a> {
+    fn new(text:&'a str) -> Self {
+        return Self { text, pos: 0 };
+    }
+
+    fn error(&self, expected:&str) -> FormatError {
+        return FormatError::Parse(
+            format!("expected {} at position {:?} of {:?}.", expected, self.pos, self.text)
+        );
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+
+        return self.text[self.pos..].chars().next();
+    }
+
+    /// Consume ``c`` if it is the next character.
+    fn accept(&mut self, c:char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+
+        return false;
+    }
+
+    fn expect(&mut self, c:char) -> Result<(), FormatError> {
+        return if self.accept(c) { Ok(()) } else { Err(self.error(&format!("{:?}", c))) };
+    }
+
+    /// Consume characters while ``f`` holds.
+    fn take_while<F>(&mut self, f:F) -> &'a str
+    where F: Fn(char) -> bool
+    {
+        self.skip_whitespace();
+
+        let rest = &self.text[self.pos..];
+        let len = rest.find(| c:char | !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+
+        return &rest[..len];
+    }
+
+    /// Consume a keyword, in upper case.
+    fn word(&mut self) -> String {
+        return self.take_while(| c | c.is_ascii_alphabetic()).to_uppercase();
+    }
+
+    fn number(&mut self) -> Result<f64, FormatError> {
+        let token = self.take_while(| c | c.is_ascii_digit() || "+-.eE".contains(c));
+
+        return token.parse().map_err(| _ | self.error("a number"));
+    }
+
+    fn at_number(&mut self) -> bool {
+        return self.peek().map_or(false, | c | c.is_ascii_digit() || "+-.".contains(c));
+    }
+
+    fn at_end(&mut self) -> bool {
+        return self.peek().is_none();
+    }
+
+    /// Parse ``x y [z [m]]`` into ``[latitude, longitude]``.
+    fn position(&mut self) -> Result<[f64; 2], FormatError> {
+        let (lng, lat) = (self.number()?, self.number()?);
+
+        // Discard any Z and M values.
+        while self.at_number() { self.number()?; }
+
+        return Ok([lat, lng]);
+    }
+
+    /// Parse ``(x y, x y, ...)``.
+    ///
+    /// If ``bracketed`` is ``true``, each position may also be wrapped in its own
+    /// brackets, as allowed for ``MULTIPOINT``.
+    fn positions(&mut self, bracketed:bool) -> Result<F64LatLngArray, FormatError> {
+        let mut flat: Vec<f64> = Vec::new();
+
+        self.expect('(')?;
+        loop {
+            let position = if bracketed && self.accept('(') {
+                let position = self.position()?;
+                self.expect(')')?;
+                position
+            } else {
+                self.position()?
+            };
+            flat.extend(position);
+
+            if !self.accept(',') { break }
+        }
+        self.expect(')')?;
+
+        return Ok(F64LatLngArray::from_shape_vec((flat.len() / 2, 2), flat).unwrap());
+    }
+
+    /// Parse ``((x y, ...), (x y, ...))``.
+    fn rings(&mut self) -> Result<Vec<F64LatLngArray>, FormatError> {
+        let mut rings = Vec::new();
+
+        self.expect('(')?;
+        loop {
+            rings.push(self.positions(false)?);
+
+            if !self.accept(',') { break }
+        }
+        self.expect(')')?;
+
+        return Ok(rings);
+    }
+}
+
+/// Parse a WKT or EWKT text into a geometry and its SRID, if any.
+pub fn parse(text:&str) -> Result<(Geometry, Option<u32>), FormatError> {
+    let mut tokens = Tokens::new(text);
+
+    let mut kind = tokens.word();
+    let mut srid = None;
+
+    if kind == "SRID" {
+        tokens.expect('=')?;
+        srid = Some(
+            tokens.take_while(| c | c.is_ascii_digit())
+                  .parse::<u32>()
+                  .map_err(| _ | tokens.error("an SRID"))?
+        );
+        tokens.expect(';')?;
+        kind = tokens.word();
+    }
+
+    // Optional dimensions, which are discarded.
+    if tokens.peek().map_or(false, | c | c.is_ascii_alphabetic()) {
+        let dimensions = tokens.word();
+
+        if dimensions == "EMPTY" {
+            return match kind.as_str() {
+                "MULTIPOINT" => Ok((Geometry::MultiPoint(F64LatLngArray::zeros((0, 2))), srid)),
+                _ => Err(FormatError::InvalidGeometry(format!("empty {} is not supported.", kind))),
+            };
+        }
+        if !["Z", "M", "ZM"].contains(&dimensions.as_str()) {
+            return Err(tokens.error("Z, M, ZM or EMPTY"));
+        }
+        if tokens.peek().map_or(false, | c | c.is_ascii_alphabetic()) && tokens.word() == "EMPTY" {
+            return Err(FormatError::InvalidGeometry(format!("empty {} is not supported.", kind)));
+        }
+    }
+
+    let geometry = match kind.as_str() {
+        "POINT" => {
+            tokens.expect('(')?;
+            let [lat, lng] = tokens.position()?;
+            tokens.expect(')')?;
+            Geometry::Point(F64LatLngArray::from_shape_vec((1, 2), vec![lat, lng]).unwrap())
+        },
+        "MULTIPOINT" => Geometry::MultiPoint(tokens.positions(true)?),
+        "LINESTRING" => Geometry::LineString(tokens.positions(false)?),
+        "POLYGON" => Geometry::Polygon(tokens.rings()?),
+        "" => return Err(tokens.error("a geometry type")),
+        other => return Err(FormatError::UnsupportedGeometry(other.to_string())),
+    };
+
+    if !tokens.at_end() {
+        return Err(tokens.error("end of text"));
+    }
+
+    geometry.validate()?;
+
+    return Ok((geometry, srid));
+}
+
+/// Format an array of dimension ``(n, 2)`` as ``x y, x y, ...``.
+fn format_positions(s:&F64LatLngArray, bracketed:bool) -> String {
+    return s.axis_iter(Axis(0))
+            .map(
+                | row | if bracketed {
+                    format!("({} {})", row[1], row[0])
+                } else {
+                    format!("{} {}", row[1], row[0])
+                }
+            )
+            .collect::<Vec<_>>()
+            .join(", ");
+}
+
+/// Write a geometry as WKT; if ``srid`` is provided, as EWKT.
+pub fn write(geometry:&Geometry, srid:Option<u32>) -> String {
+    let body = match geometry {
+        Geometry::Point(s) => format!("POINT ({})", format_positions(s, false)),
+        Geometry::MultiPoint(s) if s.shape()[0] == 0 => "MULTIPOINT EMPTY".to_string(),
+        Geometry::MultiPoint(s) => format!("MULTIPOINT ({})", format_positions(s, true)),
+        Geometry::LineString(s) => format!("LINESTRING ({})", format_positions(s, false)),
+        Geometry::Polygon(rings) => format!(
+            "POLYGON ({})",
+            rings.iter()
+                 .map(| ring | format!("({})", format_positions(ring, false)))
+                 .collect::<Vec<_>>()
+                 .join(", ")
+        ),
+    };
+
+    return match srid {
+        Some(srid) => format!("SRID={};{}", srid, body),
+        None => body,
+    };
+}
@@ -0,0 +1,150 @@
+/// Python compatibility layer for the `formats.wkt` module.
+///
+/// The functions here are registered into a `formats.wkt` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.formats.wkt`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::formats::geometry::stack_points;
+use crate::formats::python::{
+    geometry_from_py,
+    geometry_to_py,
+};
+use crate::formats::Geometry;
+
+#[pyfunction]
+#[pyo3(text_signature = "(text)")]
+/// Parse a WKT or EWKT geometry.
+///
+/// Supports ``POINT``, ``MULTIPOINT``, ``LINESTRING`` and ``POLYGON``; any Z and M
+/// values are discarded.
+///
+/// Parameters
+/// ----------
+/// text: str
+///     WKT text, optionally prefixed by ``SRID=<srid>;``.
+///
+/// Returns
+/// -------
+/// Tuple[str, Union[numpy.ndarray, List[numpy.ndarray]], Optional[int]]
+///     ``(type, coordinates, srid)``. ``coordinates`` are in order
+///     ``(latitude, longitude)``:
+///
+///     - of dimension ``(2)`` for ``Point``,
+///     - of dimension ``(n, 2)`` for ``MultiPoint`` and ``LineString``, or
+///     - a list of closed rings, each of dimension ``(n, 2)``, for ``Polygon``.
+fn read(
+    text: &str,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let (geometry, srid) = super::parse(text)?;
+
+    return Ok((geometry.kind(), geometry_to_py(&geometry, py), srid).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(texts)")]
+/// Parse a column of WKT geometries into a single array of all their vertices.
+///
+/// Parameters
+/// ----------
+/// texts: List[str]
+///     WKT or EWKT texts.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, geometries)``: ``points`` is of dimension ``(n, 2)`` in order
+///     ``(latitude, longitude)``, and ``geometries`` of dimension ``(n)`` contains
+///     the index of the geometry each point belongs to.
+fn read_points(
+    texts: Vec<&str>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let geometries = texts.into_iter()
+                          .map(| text | Ok(super::parse(text)?.0))
+                          .collect::<PyResult<Vec<Geometry>>>()?;
+
+    let (points, indices) = stack_points(geometries.iter());
+
+    return Ok((points.to_pyarray(py), Array1::from_vec(indices).to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(kind, coordinates, srid)")]
+/// Write a geometry as WKT.
+///
+/// Parameters
+/// ----------
+/// kind: str
+///     One of ``Point``, ``MultiPoint``, ``LineString`` or ``Polygon``.
+///
+/// coordinates: Union[numpy.ndarray, List[numpy.ndarray]]
+///     In the same form as returned by :func:`read`.
+///
+/// srid: Optional[int]
+///     If provided, EWKT is written with this SRID.
+///
+/// Returns
+/// -------
+/// str
+///     WKT text.
+fn write(
+    kind: &str,
+    coordinates: &PyAny,
+    srid: Option<u32>,
+) -> PyResult<String> {
+    return Ok(super::write(&geometry_from_py(kind, coordinates)?, srid));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, srid)")]
+/// Write each row of an array of lat-long coordinates as a WKT ``POINT``.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// srid: Optional[int]
+///     If provided, EWKT is written with this SRID.
+///
+/// Returns
+/// -------
+/// List[str]
+///     WKT text of each point.
+fn write_points(
+    s: &PyArray<f64, Ix2>,
+    srid: Option<u32>,
+) -> PyResult<Vec<String>> {
+    return Ok(
+        s.to_owned_array()
+         .rows()
+         .into_iter()
+         .map(| row | super::write(&Geometry::Point(row.to_owned().into_shape((1, 2)).unwrap()), srid))
+         .collect()
+    );
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(read_points, m)?)?;
+    m.add_function(wrap_pyfunction!(write, m)?)?;
+    m.add_function(wrap_pyfunction!(write_points, m)?)?;
+
+    Ok(())
+}
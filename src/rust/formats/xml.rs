@@ -0,0 +1,136 @@
+/// A minimal XML element tree shared by the XML based formats.
+///
+/// Documents are read with ``quick-xml`` into a tree of :class:`Element`s, which
+/// the formats then walk. Namespace prefixes are dropped, so that e.g.
+/// ``<gx:Track>`` is simply named ``Track``.
+
+use quick_xml::events::{
+    BytesStart,
+    Event,
+};
+use quick_xml::Reader;
+
+use super::FormatError;
+
+impl From<quick_xml::Error> for FormatError {
+    fn from(err: quick_xml::Error) -> Self {
+        return Self::Parse(err.to_string());
+    }
+}
+
+/// An XML element with its attributes, children and text content.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Element {
+    /// Local name, without any namespace prefix.
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+
+    /// All text directly inside this element, concatenated.
+    pub text: String,
+}
+impl Element {
+    fn from_start(start:&BytesStart) -> Result<Self, FormatError> {
+        let attributes = start.attributes()
+                              .map(
+                                  | attribute | {
+                                      let attribute = attribute.map_err(| err | FormatError::Parse(err.to_string()))?;
+
+                                      Ok((
+                                          String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned(),
+                                          attribute.unescape_value()?.into_owned(),
+                                      ))
+                                  }
+                              )
+                              .collect::<Result<_, FormatError>>()?;
+
+        return Ok(
+            Self {
+                name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+                attributes,
+                ..Self::default()
+            }
+        );
+    }
+
+    /// Value of an attribute, if present.
+    pub fn attribute(&self, name:&str) -> Option<&str> {
+        return self.attributes
+                   .iter()
+                   .find(| (key, _) | key == name)
+                   .map(| (_, value) | value.as_str());
+    }
+
+    /// First child of this name, if any.
+    pub fn child(&self, name:&str) -> Option<&Element> {
+        return self.children.iter().find(| child | child.name == name);
+    }
+
+    /// All children of this name.
+    pub fn children_named<'a>(&'a self, name:&'a str) -> impl Iterator<Item=&'a Element> {
+        return self.children.iter().filter(move | child | child.name == name);
+    }
+
+    /// Trimmed text of the first child of this name, if any.
+    pub fn child_text(&self, name:&str) -> Option<&str> {
+        return self.child(name).map(| child | child.text.trim());
+    }
+
+    /// All descendants of this name, in document order.
+    ///
+    /// Descendants of a matching element are not searched.
+    pub fn descendants_named<'a>(&'a self, name:&str) -> Vec<&'a Element> {
+        let mut found = Vec::new();
+
+        for child in self.children.iter() {
+            if child.name == name {
+                found.push(child);
+            } else {
+                found.extend(child.descendants_named(name));
+            }
+        }
+
+        return found;
+    }
+}
+
+/// Parse an XML document into its root element.
+pub fn parse(text:&str) -> Result<Element, FormatError> {
+    let mut reader = Reader::from_str(text);
+    reader.trim_text(true);
+
+    // The bottom of the stack collects the root element.
+    let mut stack: Vec<Element> = vec![Element::default()];
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(Element::from_start(&start)?),
+            Event::Empty(start) => {
+                let element = Element::from_start(&start)?;
+                stack.last_mut().unwrap().children.push(element);
+            },
+            Event::End(_) => {
+                let element = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Err(FormatError::Parse("unexpected closing tag.".to_string())),
+                }
+            },
+            Event::Text(text) => stack.last_mut().unwrap().text.push_str(&text.unescape()?),
+            Event::CData(text) => stack.last_mut().unwrap().text.push_str(&String::from_utf8_lossy(&text.into_inner())),
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(FormatError::Parse(format!("unclosed element {:?}.", stack.last().unwrap().name)));
+    }
+
+    return stack.pop()
+                .unwrap()
+                .children
+                .into_iter()
+                .next()
+                .ok_or_else(|| FormatError::Parse("document has no root element.".to_string()));
+}
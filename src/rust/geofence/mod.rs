@@ -0,0 +1,257 @@
+/// Geofence monitoring of tracks.
+///
+/// A :class:`Geofence` is a set of fences, each either:
+///
+/// - a circle: a point is inside if it is within the radius of the centre, with
+///   the same semantics as :meth:`CalculationInterfaceInternal::_within_distance_of_point`,
+///   i.e. the boundary is inside; or
+/// - a polygon with great circle edges, as :class:`~cells::Polygon`.
+///
+/// A track is checked against every fence, and each change of state becomes an
+/// :class:`Event`. Tracks are assumed to start outside of every fence, so a track
+/// that starts inside a fence enters it at its first point.
+
+use std::fmt;
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLng,
+};
+
+use crate::calc_models::traits::LatLngArray;
+use crate::cells::Polygon;
+use crate::spatial::to_unit_vector;
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+// Import this if you want the Geofence class to be available in Python.
+pub mod python;
+
+/// Errors from geofence checks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeofenceError {
+    /// Arrays of the wrong dimensions or lengths.
+    Shape(String),
+
+    /// Timestamps are missing or decreasing.
+    Times(String),
+
+    /// A parameter is out of its valid range.
+    Parameter(String),
+}
+impl fmt::Display for GeofenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+            Self::Times(msg) => write!(f, "Invalid timestamps: {}", msg),
+            Self::Parameter(msg) => write!(f, "Invalid parameter: {}", msg),
+        };
+    }
+}
+impl std::error::Error for GeofenceError {}
+
+/// A single fence.
+#[derive(Clone, Debug)]
+pub enum Fence {
+    /// Centre ``(lat, lng)`` in degrees, and radius in the unit of the calculation
+    /// method.
+    Circle {
+        centre: (f64, f64),
+        radius: f64,
+    },
+    Polygon(Polygon),
+}
+impl Fence {
+    /// Whether each point of ``s`` is inside this fence.
+    fn contains(
+        &self,
+        method:&CalculationMethod,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<bool> {
+        return match self {
+            Self::Circle { centre, radius } => {
+                let centre: F64LatLng = F64LatLng::from_vec(vec![centre.0, centre.1]);
+
+                CalculationInterfaceInternal::<f64>::_within_distance_of_point(
+                    method,
+                    &centre, s,
+                    *radius,
+                    settings,
+                )
+                .to_vec()
+            },
+            Self::Polygon(polygon) => {
+                s.to_owned()
+                 .axis_iter(Axis(0))
+                 .map(| row | polygon.contains_point(&to_unit_vector(row[0], row[1])))
+                 .collect()
+            },
+        };
+    }
+}
+
+/// Kind of a change of state of a track with respect to a fence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventKind {
+    Enter,
+
+    /// The track has stayed inside the fence for the dwell time since entering.
+    Dwell,
+    Exit,
+}
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", match self {
+            Self::Enter => "enter",
+            Self::Dwell => "dwell",
+            Self::Exit => "exit",
+        });
+    }
+}
+
+/// A change of state of a track with respect to a fence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Event {
+    pub fence: usize,
+    pub kind: EventKind,
+
+    /// Index of the point of the track at which the event happened.
+    pub index: usize,
+    pub time: f64,
+}
+
+/// Set of fences.
+#[derive(Clone, Debug, Default)]
+pub struct Geofence {
+    fences: Vec<Fence>,
+}
+impl Geofence {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Add a circle of ``radius`` around ``centre``; returns the index of the new
+    /// fence.
+    pub fn add_circle(&mut self, centre:(f64, f64), radius:f64) -> Result<usize, GeofenceError> {
+        if !(radius >= 0. && radius.is_finite()) {
+            return Err(GeofenceError::Parameter(
+                format!("`radius` must be non-negative, yet {:?} provided.", radius)
+            ));
+        }
+
+        self.fences.push(Fence::Circle { centre, radius });
+
+        return Ok(self.fences.len() - 1);
+    }
+
+    /// Add a polygon of lat-long vertices; returns the index of the new fence.
+    pub fn add_polygon(&mut self, vertices:&dyn LatLngArray) -> Result<usize, GeofenceError> {
+        let polygon = Polygon::new(vertices);
+        if polygon.vertices.len() < 3 {
+            return Err(GeofenceError::Shape("polygon must have at least 3 vertices.".to_string()));
+        }
+
+        self.fences.push(Fence::Polygon(polygon));
+
+        return Ok(self.fences.len() - 1);
+    }
+
+    pub fn fences(&self) -> &[Fence] {
+        return &self.fences;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.fences.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.fences.is_empty();
+    }
+
+    /// Whether each point of ``s`` is inside each fence, of dimension
+    /// ``(fences, n)``.
+    ///
+    /// The fences are spread over the threads of the rayon pool.
+    pub fn contains(
+        &self,
+        method:&CalculationMethod,
+        s:&dyn LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Vec<bool>> {
+        let s_owned = s.to_owned();
+
+        return self.fences
+                   .par_iter()
+                   .map(| fence | fence.contains(method, &s_owned, settings))
+                   .collect();
+    }
+
+    /// Events of a track against every fence, in order of the points at which
+    /// they happened, then of the fences.
+    ///
+    /// ``times`` must be non-decreasing. If ``dwell`` is provided, a
+    /// :attr:`EventKind::Dwell` event is emitted at the first point at least
+    /// ``dwell`` after the track entered a fence, if it has not left it since.
+    pub fn check(
+        &self,
+        method:&CalculationMethod,
+        s:&dyn LatLngArray,
+        times:&F64Array1,
+        dwell:Option<f64>,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Vec<Event>, GeofenceError> {
+        let n = s.shape()[0];
+        if times.len() != n {
+            return Err(GeofenceError::Shape(
+                format!("expected {:?} timestamps, yet {:?} provided.", n, times.len())
+            ));
+        }
+        if let Some(i) = (1..n).find(| i | !(times[*i] >= times[*i - 1])) {
+            return Err(GeofenceError::Times(
+                format!("timestamps must be non-decreasing, yet {:?} follows {:?}.", times[i], times[i - 1])
+            ));
+        }
+
+        let mut events: Vec<Event> = Vec::new();
+
+        for (fence, inside) in self.contains(method, s, settings).iter().enumerate() {
+            let mut entered: Option<(f64, bool)> = None;
+
+            for (index, is_inside) in inside.iter().enumerate() {
+                let time = times[index];
+
+                entered = match (entered, *is_inside) {
+                    (None, true) => {
+                        events.push(Event { fence, kind: EventKind::Enter, index, time });
+                        Some((time, false))
+                    },
+                    (Some(_), false) => {
+                        events.push(Event { fence, kind: EventKind::Exit, index, time });
+                        None
+                    },
+                    (state, _) => state,
+                };
+
+                if let (Some((since, false)), Some(dwell)) = (entered, dwell) {
+                    if time - since >= dwell {
+                        events.push(Event { fence, kind: EventKind::Dwell, index, time });
+                        entered = Some((since, true));
+                    }
+                }
+            }
+        }
+
+        events.sort_by(| a, b | (a.index, a.fence, a.kind).cmp(&(b.index, b.fence, b.kind)));
+
+        return Ok(events);
+    }
+}
@@ -0,0 +1,187 @@
+/// Python compatibility layer for the `geofence` module.
+///
+/// The class here is registered into a `geofence` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.geofence`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each method; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+
+use numpy::ndarray::{
+    Array1,
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    Geofence,
+    GeofenceError,
+};
+
+impl From<GeofenceError> for PyErr {
+    fn from(err: GeofenceError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyclass(module="rust_geodistances.geofence", name="Geofence")]
+/// A set of fences to check tracks against.
+///
+/// Fences are either circles, or polygons with great circle edges. Each fence is
+/// identified by its index, in the order it was added.
+///
+/// Example
+/// -------
+/// Watch a depot and a city centre::
+///
+///     >>> from rust_geodistances.geofence import Geofence
+///     >>> fences = Geofence()
+///     >>> fences.add_circle((51.47, -0.45), 1.5)
+///     0
+///     >>> fences.add_polygon(centre)
+///     1
+///     >>> events = fences.check(track, times, dwell=600.)
+pub struct PyGeofence {
+    geofence: Geofence,
+}
+
+#[pymethods]
+impl PyGeofence {
+    #[new]
+    fn new() -> Self {
+        return Self { geofence: Geofence::new() };
+    }
+
+    #[pyo3(text_signature = "($self, centre, radius)")]
+    /// Add a circular fence.
+    ///
+    /// Parameters
+    /// ----------
+    /// centre: Tuple[float, float]
+    ///     In degrees. In order ``(latitude, longitude)``.
+    ///
+    /// radius: numpy.float64
+    ///     Points within this distance of ``centre``, inclusive, are inside the
+    ///     fence. The unit of this must be the same as that of:
+    ///
+    ///     - :attr:`CalculationSettings.spherical_radius` or
+    ///     - :attr:`CalculationSettings.ellipse_a` and
+    ///     - :attr:`CalculationSettings.ellipse_b` and
+    ///     - :attr:`CalculationSettings.ellipse_f`
+    ///
+    ///     whichever used by the ``method`` passed to :meth:`check`.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     The index of the new fence.
+    fn add_circle(&mut self, centre:(f64, f64), radius:f64) -> PyResult<usize> {
+        return Ok(self.geofence.add_circle(centre, radius)?);
+    }
+
+    #[pyo3(text_signature = "($self, vertices)")]
+    /// Add a polygonal fence.
+    ///
+    /// Parameters
+    /// ----------
+    /// vertices: numpy.ndarray
+    ///     Of dimension ``(k, 2)``, in degrees. In order ``(latitude, longitude)``.
+    ///     The polygon is closed implicitly, and may wind either way.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///     The index of the new fence.
+    fn add_polygon(&mut self, vertices:&PyArray<f64, Ix2>) -> PyResult<usize> {
+        return Ok(self.geofence.add_polygon(&vertices.to_owned_array())?);
+    }
+
+    #[pyo3(text_signature = "($self, s, timestamps, dwell, method, *, settings)")]
+    /// Events of a track against every fence.
+    ///
+    /// The track is assumed to start outside of every fence; a track starting
+    /// inside a fence enters it at its first point.
+    ///
+    /// Parameters
+    /// ----------
+    /// s: numpy.ndarray
+    ///     The track, of dimension ``(n, 2)``, in degrees. In order
+    ///     ``(latitude, longitude)``.
+    ///
+    /// timestamps: numpy.ndarray
+    ///     Of dimension ``(n)``, non-decreasing.
+    ///
+    /// dwell: Optional[numpy.float64]
+    ///     If provided, a ``"dwell"`` event is emitted at the first point at least
+    ///     this long after entering a fence, if the track is still inside it. In
+    ///     the unit of ``timestamps``.
+    ///
+    /// method: Optional[CalculationMethod]
+    ///     Calculation method for the distances. Defaults to
+    ///     :attr:`CalculationMethod.HAVERSINE`.
+    ///
+    /// settings: CalculationSettings
+    ///     Settings to be passed on to the calculation method.
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     Containing, for ``k`` events in order of the points at which they
+    ///     happened, then of the fences:
+    ///
+    ///     - ``fence``: of dimension ``(k)``, the index of the fence,
+    ///     - ``kind``: a list of ``k`` of ``"enter"``, ``"dwell"`` or ``"exit"``,
+    ///     - ``index``: of dimension ``(k)``, the index of the point in ``s``, and
+    ///     - ``time``: of dimension ``(k)``, the timestamp of the point.
+    fn check(
+        &self,
+        s: &PyArray<f64, Ix2>,
+        timestamps: &PyArray<f64, Ix1>,
+        dwell: Option<f64>,
+        method: Option<&enums::CalculationMethod>,
+        settings: Option<&config::CalculationSettings>,
+        py: Python<'_>,
+    ) -> PyResult<PyObject> {
+        let method_default = &enums::CalculationMethod::default();
+
+        let events = self.geofence.check(
+            method.unwrap_or(method_default),
+            &s.to_owned_array(),
+            &timestamps.to_owned_array(),
+            dwell,
+            settings,
+        )?;
+
+        let dict = PyDict::new(py);
+
+        dict.set_item("fence", Array1::from_iter(events.iter().map(| event | event.fence)).to_pyarray(py))?;
+        dict.set_item("kind", events.iter().map(| event | event.kind.to_string()).collect::<Vec<String>>())?;
+        dict.set_item("index", Array1::from_iter(events.iter().map(| event | event.index)).to_pyarray(py))?;
+        dict.set_item("time", Array1::from_iter(events.iter().map(| event | event.time)).to_pyarray(py))?;
+
+        return Ok(dict.into_py(py));
+    }
+
+    /// Number of fences.
+    fn __len__(&self) -> usize {
+        return self.geofence.len();
+    }
+}
+
+/// Add all the classes of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyGeofence>()?;
+
+    Ok(())
+}
@@ -0,0 +1,389 @@
+/// Geohash encoding, decoding and neighbour lookup.
+///
+/// A geohash of precision ``p`` is a string of ``p`` base-32 characters, encoding
+/// ``5p`` bits that alternately bisect the longitude (starting with the first bit)
+/// and latitude ranges. Each hash represents a latitude-longitude rectangle, or
+/// *cell*; hashes sharing a prefix are contained within the cell of that prefix.
+///
+/// Internally a cell is represented by its row and column indices on the grid of
+/// its precision, which makes neighbour lookups simple integer arithmetic.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+    LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+// Import this if you want the geohash module to be available in Python.
+pub mod python;
+
+/// Base-32 alphabet used by geohashes.
+pub const BASE32:&[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Highest precision supported; 12 characters = 60 bits.
+pub const MAX_PRECISION:usize = 12;
+
+/// Errors from parsing geohashes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeohashError {
+    /// The hash contains a character outside of the base-32 alphabet.
+    InvalidCharacter(char),
+
+    /// The hash or requested precision is not between 1 and ``MAX_PRECISION``.
+    InvalidPrecision(usize),
+}
+impl fmt::Display for GeohashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::InvalidCharacter(c) => write!(f, "{:?} is not a valid geohash character.", c),
+            Self::InvalidPrecision(p) => write!(
+                f, "Geohash precision must be between 1 and {:?}, yet {:?} provided.", MAX_PRECISION, p
+            ),
+        };
+    }
+}
+impl std::error::Error for GeohashError {}
+
+/// Number of latitude and longitude bits at a precision.
+pub fn bits(precision:usize) -> (u32, u32) {
+    let total = (precision * 5) as u32;
+
+    return (total / 2, total - total / 2);
+}
+
+/// Height and width of a cell at a precision, in degrees.
+pub fn cell_size(precision:usize) -> (f64, f64) {
+    let (lat_bits, lng_bits) = bits(precision);
+
+    return (
+        180. / (1_u64 << lat_bits) as f64,
+        360. / (1_u64 << lng_bits) as f64,
+    );
+}
+
+/// A single geohash cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GeohashCell {
+    pub precision: usize,
+    pub lat_idx: u64,
+    pub lng_idx: u64,
+}
+impl GeohashCell {
+    /// The cell containing a latitude-longitude pair.
+    pub fn from_latlng(lat:f64, lng:f64, precision:usize) -> Result<Self, GeohashError> {
+        if precision == 0 || precision > MAX_PRECISION {
+            return Err(GeohashError::InvalidPrecision(precision));
+        }
+
+        let (lat_bits, lng_bits) = bits(precision);
+        let (lat_count, lng_count) = (1_u64 << lat_bits, 1_u64 << lng_bits);
+
+        // Wrap longitude into [-180, 180).
+        let lng = (lng + 180.).rem_euclid(360.) - 180.;
+
+        let lat_idx = (((lat + 90.) / 180. * lat_count as f64).floor().max(0.) as u64).min(lat_count - 1);
+        let lng_idx = (((lng + 180.) / 360. * lng_count as f64).floor().max(0.) as u64).min(lng_count - 1);
+
+        return Ok(Self { precision, lat_idx, lng_idx });
+    }
+
+    /// Parse a geohash string.
+    pub fn from_hash(hash:&str) -> Result<Self, GeohashError> {
+        let precision = hash.chars().count();
+        if precision == 0 || precision > MAX_PRECISION {
+            return Err(GeohashError::InvalidPrecision(precision));
+        }
+
+        let (mut lat_idx, mut lng_idx) = (0_u64, 0_u64);
+        let mut bit = 0;
+
+        for c in hash.chars() {
+            let value = BASE32.iter()
+                              .position(| b | *b as char == c.to_ascii_lowercase())
+                              .ok_or(GeohashError::InvalidCharacter(c))?;
+
+            for shift in (0..5).rev() {
+                let set = ((value >> shift) & 1) as u64;
+
+                if bit % 2 == 0 {
+                    lng_idx = (lng_idx << 1) | set;
+                } else {
+                    lat_idx = (lat_idx << 1) | set;
+                }
+                bit += 1;
+            }
+        }
+
+        return Ok(Self { precision, lat_idx, lng_idx });
+    }
+
+    /// The geohash string of this cell.
+    pub fn to_hash(&self) -> String {
+        let (lat_bits, lng_bits) = bits(self.precision);
+        let (mut lat_remaining, mut lng_remaining) = (lat_bits, lng_bits);
+
+        let mut hash = String::with_capacity(self.precision);
+        let mut value = 0_usize;
+
+        for bit in 0..(self.precision * 5) {
+            let set = if bit % 2 == 0 {
+                lng_remaining -= 1;
+                (self.lng_idx >> lng_remaining) & 1
+            } else {
+                lat_remaining -= 1;
+                (self.lat_idx >> lat_remaining) & 1
+            };
+
+            value = (value << 1) | set as usize;
+
+            if bit % 5 == 4 {
+                hash.push(BASE32[value] as char);
+                value = 0;
+            }
+        }
+
+        return hash;
+    }
+
+    /// ``(lat_min, lat_max, lng_min, lng_max)`` of this cell, in degrees.
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (height, width) = cell_size(self.precision);
+        let (lat_min, lng_min) = (
+            self.lat_idx as f64 * height - 90.,
+            self.lng_idx as f64 * width - 180.,
+        );
+
+        return (lat_min, lat_min + height, lng_min, lng_min + width);
+    }
+
+    /// ``(lat, lng)`` of the centre of this cell, in degrees.
+    pub fn centre(&self) -> (f64, f64) {
+        let (lat_min, lat_max, lng_min, lng_max) = self.bounds();
+
+        return ((lat_min + lat_max) / 2., (lng_min + lng_max) / 2.);
+    }
+
+    /// ``(lat_error, lng_error)`` of the centre of this cell, i.e. half of its
+    /// height and width, in degrees.
+    pub fn error(&self) -> (f64, f64) {
+        let (height, width) = cell_size(self.precision);
+
+        return (height / 2., width / 2.);
+    }
+
+    /// The cell ``d_lat`` rows and ``d_lng`` columns away from this one.
+    ///
+    /// Longitude wraps around the antimeridian; `None` is returned if the
+    /// latitude goes beyond either pole.
+    pub fn offset(&self, d_lat:i64, d_lng:i64) -> Option<Self> {
+        let (lat_bits, lng_bits) = bits(self.precision);
+        let (lat_count, lng_count) = (1_i64 << lat_bits, 1_i64 << lng_bits);
+
+        let lat_idx = self.lat_idx as i64 + d_lat;
+        if lat_idx < 0 || lat_idx >= lat_count { return None }
+
+        let lng_idx = (self.lng_idx as i64 + d_lng).rem_euclid(lng_count);
+
+        return Some(Self {
+            precision: self.precision,
+            lat_idx: lat_idx as u64,
+            lng_idx: lng_idx as u64,
+        });
+    }
+
+    /// The 8 neighbouring cells, in order N, NE, E, SE, S, SW, W, NW.
+    pub fn neighbours(&self) -> [Option<Self>; 8] {
+        return [
+            self.offset( 1,  0),
+            self.offset( 1,  1),
+            self.offset( 0,  1),
+            self.offset(-1,  1),
+            self.offset(-1,  0),
+            self.offset(-1, -1),
+            self.offset( 0, -1),
+            self.offset( 1, -1),
+        ];
+    }
+}
+
+/// Encode each row of an array of lat-long coordinates.
+pub fn encode(
+    s:&dyn LatLngArray,
+    precision:usize,
+) -> Result<Vec<String>, GeohashError> {
+    let s_owned: F64LatLngArray = s.to_owned();
+
+    return s_owned.axis_iter(Axis(0))
+                  .map(
+                      | row | GeohashCell::from_latlng(row[0], row[1], precision)
+                                         .map(| cell | cell.to_hash())
+                  )
+                  .collect();
+}
+
+/// Decode geohashes into the centres of their cells and the errors of the centres.
+///
+/// Returns ``(centres, errors)``, both of dimension ``(n, 2)`` in degrees, in
+/// order ``(latitude, longitude)``.
+pub fn decode<S: AsRef<str>>(
+    hashes:&[S],
+) -> Result<(F64LatLngArray, F64LatLngArray), GeohashError> {
+    let n = hashes.len();
+    let mut centres = F64LatLngArray::zeros((n, 2));
+    let mut errors = F64LatLngArray::zeros((n, 2));
+
+    for (i, hash) in hashes.iter().enumerate() {
+        let cell = GeohashCell::from_hash(hash.as_ref())?;
+        let ((lat, lng), (lat_error, lng_error)) = (cell.centre(), cell.error());
+
+        centres[[i, 0]] = lat;
+        centres[[i, 1]] = lng;
+        errors[[i, 0]] = lat_error;
+        errors[[i, 1]] = lng_error;
+    }
+
+    return Ok((centres, errors));
+}
+
+/// The 8 neighbouring geohashes of a geohash, in order N, NE, E, SE, S, SW, W, NW.
+///
+/// Neighbours beyond either pole are `None`.
+pub fn neighbours(
+    hash:&str,
+) -> Result<Vec<Option<String>>, GeohashError> {
+    let cell = GeohashCell::from_hash(hash)?;
+
+    return Ok(
+        cell.neighbours()
+            .iter()
+            .map(| neighbour | neighbour.map(| cell | cell.to_hash()))
+            .collect()
+    );
+}
+
+/// The point of a latitude-longitude rectangle ``(lat_min, lat_max, lng_min,
+/// lng_max)`` closest to ``(lat, lng)`` on a sphere, in degrees.
+///
+/// Also used by :mod:`tiles`, whose tiles are rectangles in the same sense.
+pub fn closest_point(
+    bounds:(f64, f64, f64, f64),
+    lat:f64,
+    lng:f64,
+) -> (f64, f64) {
+    let (lat_min, lat_max, lng_min, lng_max) = bounds;
+
+    // Longitudinal offset of `lng` from the edges, wrapped into [-180, 180).
+    let wrap = | d:f64 | (d + 180.).rem_euclid(360.) - 180.;
+    let (to_min, to_max) = (wrap(lng - lng_min), wrap(lng - lng_max));
+
+    if to_min >= 0. && to_max <= 0. {
+        // Within the longitude range: the closest point is on the same meridian.
+        return (lat.clamp(lat_min, lat_max), lng);
+    }
+
+    let (edge_lng, d_lng) = if to_min.abs() <= to_max.abs() {
+        (lng_min, to_min)
+    } else {
+        (lng_max, to_max)
+    };
+
+    // Closest point on the meridian great circle of the edge.
+    let closest_lat = if d_lng.abs() < 90. {
+        (lat.to_radians().tan() / d_lng.to_radians().cos()).atan().to_degrees()
+    } else if lat >= 0. {
+        90.
+    } else {
+        -90.
+    };
+
+    return (closest_lat.clamp(lat_min, lat_max), edge_lng);
+}
+
+/// All geohash cells of ``precision`` intersecting a geodesic circle.
+///
+/// A cell is included if any part of it is within ``radius`` of ``centre``,
+/// using the same distance semantics as :meth:`_within_distance_of_point` of
+/// ``method``.
+pub fn cover_circle(
+    method:&CalculationMethod,
+    centre:&dyn LatLng,
+    radius:f64,
+    precision:usize,
+    settings: Option<&CalculationSettings>,
+) -> Result<Vec<String>, GeohashError> {
+    let (lat, lng) = (centre[0], centre[1]);
+    let centre_cell = GeohashCell::from_latlng(lat, lng, precision)?;
+
+    let (radius_min, _) = method._radius_bounds(settings);
+    let ang_dist = (radius / radius_min).to_degrees();
+
+    let (lat_bits, lng_bits) = bits(precision);
+    let (height, width) = cell_size(precision);
+
+    // Bounding box of the circle in cell indices.
+    let lat_idx_min = ((lat - ang_dist + 90.) / height).floor().max(0.) as i64;
+    let lat_idx_max = (((lat + ang_dist + 90.) / height).floor() as i64).min((1_i64 << lat_bits) - 1);
+
+    // A circle containing a pole affects all longitudes.
+    let all_lngs = lat.abs() + ang_dist >= 90. || ang_dist >= 90.;
+    let lng_span = if all_lngs {
+        (1_i64 << lng_bits) - 1
+    } else {
+        let d_lng = (
+            ang_dist.to_radians().sin() / lat.to_radians().cos()
+        ).clamp(-1., 1.).asin().to_degrees();
+
+        (d_lng / width).ceil() as i64 + 1
+    };
+
+    let mut seen: HashSet<GeohashCell> = HashSet::new();
+    let mut cells: Vec<GeohashCell> = Vec::new();
+    for lat_idx in lat_idx_min..=lat_idx_max {
+        let d_lat = lat_idx - centre_cell.lat_idx as i64;
+
+        for d_lng in -lng_span..=lng_span {
+            if let Some(cell) = centre_cell.offset(d_lat, d_lng) {
+                if seen.insert(cell) { cells.push(cell) }
+            }
+        }
+    }
+
+    // Distance from the centre to the closest point of each cell.
+    let mut closest = F64LatLngArray::zeros((cells.len(), 2));
+    for (i, cell) in cells.iter().enumerate() {
+        let (closest_lat, closest_lng) = closest_point(cell.bounds(), lat, lng);
+
+        closest[[i, 0]] = closest_lat;
+        closest[[i, 1]] = closest_lng;
+    }
+
+    let distances: F64Array1 = CalculationInterfaceInternal::<f64>::_distance_from_point(
+        method,
+        centre, &closest,
+        settings,
+    );
+
+    return Ok(
+        cells.iter()
+             .zip(distances.iter())
+             .filter(| (cell, d) | **d <= radius || **cell == centre_cell)
+             .map(| (cell, _) | cell.to_hash())
+             .collect()
+    );
+}
@@ -0,0 +1,167 @@
+/// Python compatibility layer for the `geohash` module.
+///
+/// The functions here are registered into a `geohash` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.geohash`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::GeohashError;
+
+impl From<GeohashError> for PyErr {
+    fn from(err: GeohashError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, precision)")]
+/// Encode an array of lat-long coordinates into geohashes.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// precision: int
+///     Number of characters of each geohash, between ``1`` and ``12``.
+///
+/// Returns
+/// -------
+/// List[str]
+///     The geohash of each point in ``s``.
+fn encode(
+    s: &PyArray<f64, Ix2>,
+    precision: usize,
+) -> PyResult<Vec<String>> {
+    return Ok(super::encode(&s.to_owned_array(), precision)?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(hashes)")]
+/// Decode geohashes into the centres of their cells.
+///
+/// Parameters
+/// ----------
+/// hashes: List[str]
+///     Geohashes of any precision between ``1`` and ``12``.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(centres, errors)``, both of dimension ``(n, 2)`` in degrees, in order
+///     ``(latitude, longitude)``. ``errors`` are the maximum differences between
+///     the centre and any point within the cell, i.e. half of the cell size.
+fn decode(
+    hashes: Vec<String>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let (centres, errors) = super::decode(&hashes)?;
+
+    return Ok((centres.to_pyarray(py), errors.to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(hash)")]
+/// The 8 neighbouring cells of a geohash.
+///
+/// Parameters
+/// ----------
+/// hash: str
+///     A geohash of any precision between ``1`` and ``12``.
+///
+/// Returns
+/// -------
+/// List[Optional[str]]
+///     The neighbouring geohashes of the same precision, in order N, NE, E, SE, S,
+///     SW, W, NW. Longitudes wrap around the antimeridian; neighbours beyond the
+///     poles are ``None``.
+fn neighbours(
+    hash: &str,
+) -> PyResult<Vec<Option<String>>> {
+    return Ok(super::neighbours(hash)?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(centre, radius, precision, method, *, settings)")]
+/// Geohash cells intersecting a geodesic circle.
+///
+/// A cell is included if any part of it is within ``radius`` of ``centre``, using
+/// the same distance semantics as
+/// :meth:`~rust_geodistances.CalculationMethod.within_distance_of_point`.
+///
+/// Parameters
+/// ----------
+/// centre: numpy.ndarray
+///     Of dimension ``(2)``, e.g. ``numpy.array([51.5072, -0.1276])``.
+///
+/// radius: numpy.float64
+///     Radius of the circle.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// precision: int
+///     Number of characters of each geohash, between ``1`` and ``12``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// List[str]
+///     Geohashes of all cells intersecting the circle.
+fn cover_circle(
+    centre: &PyArray<f64, Ix1>,
+    radius: f64,
+    precision: usize,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<Vec<String>> {
+    let method_default = &enums::CalculationMethod::default();
+
+    return Ok(
+        super::cover_circle(
+            method.unwrap_or(method_default),
+            &centre.to_owned_array(),
+            radius,
+            precision,
+            settings,
+        )?
+    );
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(decode, m)?)?;
+    m.add_function(wrap_pyfunction!(neighbours, m)?)?;
+    m.add_function(wrap_pyfunction!(cover_circle, m)?)?;
+
+    Ok(())
+}
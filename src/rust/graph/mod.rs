@@ -0,0 +1,400 @@
+/// Shortest paths over a network of coordinates.
+///
+/// A :class:`Graph` is a set of nodes, each a lat-long coordinate, joined by
+/// directed or undirected edges. Unless given explicitly, the weight of each edge
+/// is the distance between its two nodes by the chosen :class:`CalculationMethod`.
+///
+/// Shortest paths are found by Dijkstra's algorithm, or by A* with the distance
+/// from each node to the target by the same method as its heuristic. Since no path
+/// between two nodes can be shorter than the geodesic between them, the heuristic
+/// is admissible and consistent as long as no edge weighs less than the distance
+/// between its nodes; this holds for the default weights, and for the lengths of
+/// any polylines between the nodes.
+
+use std::cmp::{
+    self,
+    Ordering,
+    Reverse,
+};
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+    HashSet,
+};
+use std::fmt;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+
+use crate::compatibility::{
+    CalculationInterfacePairwise,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+// Import this if you want the graph module to be available in Python.
+pub mod python;
+
+/// Errors from graph construction and searches.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphError {
+    /// Arrays of the wrong dimensions or lengths.
+    Shape(String),
+
+    /// An edge or a search refers to a node that does not exist.
+    InvalidNode(usize),
+
+    /// An edge weight is negative or not finite.
+    InvalidWeight(f64),
+}
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+            Self::InvalidNode(node) => write!(f, "Node {:?} does not exist.", node),
+            Self::InvalidWeight(weight) => write!(f, "Edge weight {:?} must be non-negative and finite.", weight),
+        };
+    }
+}
+impl std::error::Error for GraphError {}
+
+/// Shortest path algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Dijkstra,
+
+    /// A* with the distance to the target by the calculation method as the
+    /// heuristic.
+    AStar,
+}
+impl Default for Algorithm {
+    fn default() -> Self { Self::Dijkstra }
+}
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "dijkstra" => Ok(Self::Dijkstra),
+            "astar" | "a*" => Ok(Self::AStar),
+            other => Err(
+                format!("Unknown algorithm {:?}; expected \"dijkstra\" or \"astar\".", other)
+            ),
+        };
+    }
+}
+
+/// Shortest path between two nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    /// Total weight of the path.
+    pub distance: f64,
+
+    /// Nodes along the path, from the source to the target inclusive.
+    pub nodes: Vec<usize>,
+
+    /// Edges along the path; one fewer than ``nodes``.
+    pub edges: Vec<usize>,
+}
+
+/// Node in the queue of a search; ordered by priority, then by node for
+/// determinism.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Visit {
+    priority: f64,
+    distance: f64,
+    node: usize,
+    edge: Option<usize>,
+}
+impl Eq for Visit {}
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+impl Ord for Visit {
+    fn cmp(&self, other:&Self) -> Ordering {
+        return self.priority.total_cmp(&other.priority).then(self.node.cmp(&other.node));
+    }
+}
+
+/// Graph of lat-long nodes with weighted edges.
+pub struct Graph {
+    nodes: F64LatLngArray,
+
+    /// ``(from, to, weight)`` of each edge.
+    edges: Vec<(usize, usize, f64)>,
+
+    /// ``(edge, neighbour)`` reachable from each node.
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+impl Graph {
+    /// Build a graph from nodes of dimension ``(k, 2)`` and the ``(from, to)``
+    /// nodes of each edge.
+    ///
+    /// If ``weights`` is not provided, each edge weighs the distance between its
+    /// nodes by ``method``. If ``directed`` is ``false``, every edge can be
+    /// travelled both ways.
+    pub fn new(
+        method:&CalculationMethod,
+        nodes:&dyn LatLngArray,
+        edges:&[(usize, usize)],
+        weights:Option<&[f64]>,
+        directed:bool,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Self, GraphError> {
+        let nodes = nodes.to_owned();
+
+        let weights = match weights {
+            Some(weights) if weights.len() != edges.len() => return Err(GraphError::Shape(
+                format!("expected {:?} weights, yet {:?} provided.", edges.len(), weights.len())
+            )),
+            Some(weights) => weights.to_vec(),
+            None => {
+                Self::check_nodes(&nodes, edges.iter().flat_map(| (from, to) | [*from, *to]))?;
+
+                let from: Vec<usize> = edges.iter().map(| edge | edge.0).collect();
+                let to: Vec<usize> = edges.iter().map(| edge | edge.1).collect();
+
+                method._distance_pairwise(
+                    &nodes.select(Axis(0), &from),
+                    &nodes.select(Axis(0), &to),
+                    settings,
+                ).to_vec()
+            },
+        };
+
+        return Self::from_weights(
+            &nodes,
+            &edges.iter().zip(weights).map(| ((from, to), weight) | (*from, *to, weight)).collect::<Vec<_>>(),
+            directed,
+        );
+    }
+
+    /// Build a graph from ``(from, to, weight)`` of each edge.
+    pub fn from_weights(
+        nodes:&dyn LatLngArray,
+        edges:&[(usize, usize, f64)],
+        directed:bool,
+    ) -> Result<Self, GraphError> {
+        let nodes = nodes.to_owned();
+        let k = nodes.shape()[0];
+
+        Self::check_nodes(&nodes, edges.iter().flat_map(| (from, to, _) | [*from, *to]))?;
+        if let Some((_, _, weight)) = edges.iter().find(| (_, _, weight) | !(*weight >= 0. && weight.is_finite())) {
+            return Err(GraphError::InvalidWeight(*weight));
+        }
+
+        let mut adjacency = vec![Vec::new(); k];
+        for (i, (from, to, _)) in edges.iter().enumerate() {
+            adjacency[*from].push((i, *to));
+            if !directed && to != from { adjacency[*to].push((i, *from)); }
+        }
+
+        return Ok(Self {
+            nodes,
+            edges: edges.to_vec(),
+            adjacency,
+        });
+    }
+
+    fn check_nodes<I>(nodes:&F64LatLngArray, indices:I) -> Result<(), GraphError>
+    where I: IntoIterator<Item=usize>
+    {
+        return match indices.into_iter().find(| node | *node >= nodes.shape()[0]) {
+            Some(node) => Err(GraphError::InvalidNode(node)),
+            None => Ok(()),
+        };
+    }
+
+    pub fn nodes(&self) -> &F64LatLngArray {
+        return &self.nodes;
+    }
+
+    /// ``(from, to, weight)`` of each edge.
+    pub fn edges(&self) -> &[(usize, usize, f64)] {
+        return &self.edges;
+    }
+
+    /// Best-first search from ``source``, in order of distance plus ``heuristic``.
+    ///
+    /// Stops once all of ``targets`` are settled, or if ``targets`` is empty, once
+    /// every node within ``limit`` is. Returns the distance to, and the edge leading
+    /// to, each settled node.
+    fn search<H>(
+        &self,
+        source:usize,
+        targets:&[usize],
+        limit:f64,
+        mut heuristic:H,
+    ) -> HashMap<usize, (f64, Option<usize>)>
+    where H: FnMut(usize) -> f64
+    {
+        let mut settled: HashMap<usize, (f64, Option<usize>)> = HashMap::new();
+        let mut remaining: HashSet<usize> = targets.iter().cloned().collect();
+        let mut queue = BinaryHeap::from([
+            Reverse(Visit { priority: heuristic(source), distance: 0., node: source, edge: None }),
+        ]);
+
+        while let Some(Reverse(Visit { distance, node, edge, .. })) = queue.pop() {
+            if settled.contains_key(&node) { continue }
+            settled.insert(node, (distance, edge));
+
+            if remaining.remove(&node) && remaining.is_empty() { break }
+
+            for (edge, neighbour) in self.adjacency[node].iter() {
+                let next = distance + self.edges[*edge].2;
+                if next <= limit && !settled.contains_key(neighbour) {
+                    queue.push(Reverse(Visit {
+                        priority: next + heuristic(*neighbour),
+                        distance: next,
+                        node: *neighbour,
+                        edge: Some(*edge),
+                    }));
+                }
+            }
+        }
+
+        return settled;
+    }
+
+    /// Trace the path to ``target`` back through the edges of a search.
+    fn path(&self, settled:&HashMap<usize, (f64, Option<usize>)>, target:usize) -> Option<Path> {
+        let (distance, _) = settled.get(&target)?;
+
+        let (mut nodes, mut edges) = (vec![target], Vec::new());
+        while let Some(edge) = settled[nodes.last().unwrap()].1 {
+            let (from, to, _) = self.edges[edge];
+            nodes.push(if to == *nodes.last().unwrap() { from } else { to });
+            edges.push(edge);
+        }
+        nodes.reverse();
+        edges.reverse();
+
+        return Some(Path { distance: *distance, nodes, edges });
+    }
+
+    fn check_query(&self, nodes:&[usize]) -> Result<(), GraphError> {
+        return Self::check_nodes(&self.nodes, nodes.iter().cloned());
+    }
+
+    /// Shortest path from ``source`` to ``target`` by Dijkstra's algorithm, or
+    /// ``None`` if there is none.
+    pub fn dijkstra(
+        &self,
+        source:usize,
+        target:usize,
+    ) -> Result<Option<Path>, GraphError> {
+        self.check_query(&[source, target])?;
+
+        let settled = self.search(source, &[target], f64::INFINITY, | _ | 0.);
+
+        return Ok(self.path(&settled, target));
+    }
+
+    /// Shortest path from ``source`` to ``target`` by A*, or ``None`` if there is
+    /// none.
+    ///
+    /// The heuristic of each node is its distance to ``target`` by ``method``,
+    /// calculated as the node is first reached.
+    pub fn astar(
+        &self,
+        method:&CalculationMethod,
+        source:usize,
+        target:usize,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Option<Path>, GraphError> {
+        self.check_query(&[source, target])?;
+
+        let (lat, lng) = (self.nodes[[target, 0]], self.nodes[[target, 1]]);
+        let mut cache: HashMap<usize, f64> = HashMap::new();
+
+        let settled = self.search(
+            source,
+            &[target],
+            f64::INFINITY,
+            | node | *cache.entry(node).or_insert_with(
+                | | method._inverse(self.nodes[[node, 0]], self.nodes[[node, 1]], lat, lng, settings).0
+            ),
+        );
+
+        return Ok(self.path(&settled, target));
+    }
+
+    /// Shortest path from ``source`` to ``target`` by ``algorithm``, or ``None`` if
+    /// there is none.
+    pub fn shortest_path(
+        &self,
+        method:&CalculationMethod,
+        source:usize,
+        target:usize,
+        algorithm:Algorithm,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Option<Path>, GraphError> {
+        return match algorithm {
+            Algorithm::Dijkstra => self.dijkstra(source, target),
+            Algorithm::AStar => self.astar(method, source, target, settings),
+        };
+    }
+
+    /// Shortest distances from ``source`` to every node reachable within
+    /// ``limit``.
+    pub fn distances_from(&self, source:usize, limit:f64) -> HashMap<usize, f64> {
+        return self.search(source, &[], limit, | _ | 0.)
+                   .into_iter()
+                   .map(| (node, (distance, _)) | (node, distance))
+                   .collect();
+    }
+
+    /// Shortest distances from each of ``sources`` to each of ``targets``, of
+    /// dimension ``(s, t)``; ``inf`` where there is no path.
+    ///
+    /// One search is made from each source, with the sources spread over
+    /// :attr:`CalculationSettings.workers` threads.
+    pub fn distance_matrix(
+        &self,
+        sources:&[usize],
+        targets:&[usize],
+        settings: Option<&CalculationSettings>,
+    ) -> Result<F64Array2, GraphError> {
+        self.check_query(sources)?;
+        self.check_query(targets)?;
+
+        let workers: usize = settings.unwrap_or(
+            &CalculationSettings::default()
+        ).workers;
+        let chunk_size: usize = cmp::max((sources.len() as f32 / workers as f32).ceil() as usize, 1);
+
+        let rows: Vec<F64Array1> = {
+            sources.par_iter()
+                   .with_min_len(chunk_size)
+                   .map(
+                       | source | {
+                           let settled = self.search(*source, targets, f64::INFINITY, | _ | 0.);
+
+                           targets.iter()
+                                  .map(| target | settled.get(target).map_or(f64::INFINITY, | (d, _) | *d))
+                                  .collect()
+                       }
+                   )
+                   .collect()
+        };
+
+        let mut result = F64Array2::zeros((sources.len(), targets.len()));
+        for (i, row) in rows.iter().enumerate() {
+            result.row_mut(i).assign(row);
+        }
+
+        return Ok(result);
+    }
+}
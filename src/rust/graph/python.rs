@@ -0,0 +1,239 @@
+/// Python compatibility layer for the `graph` module.
+///
+/// The functions here are registered into a `graph` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.graph`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    Algorithm,
+    Graph,
+    GraphError,
+};
+
+impl From<GraphError> for PyErr {
+    fn from(err: GraphError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+/// Convert an array of dimension ``(m, 2)`` of node indices into pairs.
+pub fn edges_from_py(edges:&PyArray<i64, Ix2>) -> PyResult<Vec<(usize, usize)>> {
+    let edges = edges.to_owned_array();
+    if edges.shape()[1] != 2 {
+        return Err(PyValueError::new_err("`edges` must be of dimension (m, 2)."));
+    }
+
+    return edges.rows()
+                .into_iter()
+                .map(
+                    | row | match (usize::try_from(row[0]), usize::try_from(row[1])) {
+                        (Ok(from), Ok(to)) => Ok((from, to)),
+                        _ => Err(PyValueError::new_err(format!("Invalid node indices {:?}.", row.to_vec()))),
+                    }
+                )
+                .collect();
+}
+
+/// Build a :class:`Graph` from the arguments shared by all functions.
+fn graph_from_py(
+    method:&enums::CalculationMethod,
+    nodes:&PyArray<f64, Ix2>,
+    edges:&PyArray<i64, Ix2>,
+    weights:Option<Vec<f64>>,
+    directed:Option<bool>,
+    settings:Option<&config::CalculationSettings>,
+) -> PyResult<Graph> {
+    return Ok(Graph::new(
+        method,
+        &nodes.to_owned_array(),
+        &edges_from_py(edges)?,
+        weights.as_deref(),
+        directed.unwrap_or(false),
+        settings,
+    )?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(nodes, edges, source, target, weights, directed, algorithm, method, *, settings)")]
+/// Shortest path between two nodes of a graph.
+///
+/// Parameters
+/// ----------
+/// nodes: numpy.ndarray
+///     Of dimension ``(k, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// edges: numpy.ndarray
+///     Of dimension ``(m, 2)`` and integer type, the indices in ``nodes`` of the
+///     two ends of each edge.
+///
+/// source: int
+///     Index of the node to start from.
+///
+/// target: int
+///     Index of the node to reach.
+///
+/// weights: Optional[List[float]]
+///     Weight of each edge, e.g. the length of the road it represents. Defaults
+///     to the distance between the two ends of each edge by ``method``.
+///
+/// directed: Optional[bool]
+///     If ``True``, each edge can only be travelled from its first node to its
+///     second. Defaults to ``False``.
+///
+/// algorithm: Optional[str]
+///     Either:
+///
+///     - ``"dijkstra"`` (default), or
+///     - ``"astar"``: A*, guided by the distance from each node to ``target`` by
+///       ``method``. This is exact as long as no edge weighs less than the
+///       distance between its two nodes, which holds for the default weights.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Optional[Tuple[float, numpy.ndarray, numpy.ndarray]]
+///     ``(distance, nodes, edges)``: the total weight of the path, and the indices
+///     of the nodes and edges along it from ``source`` to ``target``. ``None`` if
+///     ``target`` cannot be reached.
+///
+/// Example
+/// -------
+/// Route along a network of sea lanes::
+///
+///     >>> from rust_geodistances import graph
+///     >>> distance, path, _ = graph.shortest_path(
+///     ...     ports, lanes, 0, 5, algorithm="astar",
+///     ... )
+fn shortest_path(
+    nodes: &PyArray<f64, Ix2>,
+    edges: &PyArray<i64, Ix2>,
+    source: usize,
+    target: usize,
+    weights: Option<Vec<f64>>,
+    directed: Option<bool>,
+    algorithm: Option<&str>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let method = method.unwrap_or(method_default);
+    let algorithm: Algorithm = match algorithm {
+        Some(algorithm) => algorithm.parse().map_err(PyValueError::new_err)?,
+        None => Algorithm::default(),
+    };
+
+    let graph = graph_from_py(method, nodes, edges, weights, directed, settings)?;
+
+    return Ok(
+        graph.shortest_path(method, source, target, algorithm, settings)?
+             .map(
+                 | path | (
+                     path.distance,
+                     Array1::from_vec(path.nodes).to_pyarray(py),
+                     Array1::from_vec(path.edges).to_pyarray(py),
+                 )
+             )
+             .into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(nodes, edges, sources, targets, weights, directed, method, *, settings)")]
+/// Shortest distances along a graph from each of a set of nodes to each of
+/// another.
+///
+/// One search is made from each source, with the sources spread over
+/// :attr:`CalculationSettings.workers` threads.
+///
+/// Parameters
+/// ----------
+/// nodes: numpy.ndarray
+///     Of dimension ``(k, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// edges: numpy.ndarray
+///     Of dimension ``(m, 2)`` and integer type, the indices in ``nodes`` of the
+///     two ends of each edge.
+///
+/// sources: List[int]
+///     Indices of the nodes to start from.
+///
+/// targets: List[int]
+///     Indices of the nodes to reach.
+///
+/// weights: Optional[List[float]]
+///     Weight of each edge, e.g. the length of the road it represents. Defaults
+///     to the distance between the two ends of each edge by ``method``.
+///
+/// directed: Optional[bool]
+///     If ``True``, each edge can only be travelled from its first node to its
+///     second. Defaults to ``False``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(s, t)``, the total weight of the shortest path from each
+///     source to each target, or ``inf`` where there is none.
+///
+/// Example
+/// -------
+/// Road distances from depots to customers::
+///
+///     >>> from rust_geodistances import graph
+///     >>> d = graph.distance_matrix(junctions, roads, depots, customers, lengths)
+fn distance_matrix(
+    nodes: &PyArray<f64, Ix2>,
+    edges: &PyArray<i64, Ix2>,
+    sources: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Option<Vec<f64>>,
+    directed: Option<bool>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let graph = graph_from_py(method.unwrap_or(method_default), nodes, edges, weights, directed, settings)?;
+
+    return Ok(graph.distance_matrix(&sources, &targets, settings)?.to_pyarray(py).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(distance_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(shortest_path, m)?)?;
+
+    Ok(())
+}
@@ -23,6 +23,19 @@ use ndarray_numeric::{
 
 pub mod compatibility;
 pub mod calc_models;
+pub mod cells;
+pub mod coordinates;
+pub mod data;
+pub mod facilities;
+pub mod formats;
+pub mod geofence;
+pub mod geohash;
+pub mod graph;
+pub mod matching;
+pub mod spatial;
+pub mod tiles;
+pub mod trajectory;
+pub mod transforms;
 
 mod tests;
 
@@ -51,10 +64,54 @@ mod tests;
 ///
 /// This module is also accessible as :attr:`rust_geodistances.bin`.
 #[pymodule]
-fn lib_rust_geodistances(_py: Python, m: &PyModule) -> PyResult<()> {
+fn lib_rust_geodistances(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<compatibility::enums::CalculationMethod>()?;
     // Or should we compatibility::enums::CalculationSettings??
     m.add_class::<calc_models::config::CalculationSettings>()?;
 
+    let cells_module = PyModule::new(py, "cells")?;
+    cells::python::register(py, cells_module)?;
+    m.add_submodule(cells_module)?;
+
+    let coordinates_module = PyModule::new(py, "coordinates")?;
+    coordinates::python::register(py, coordinates_module)?;
+    m.add_submodule(coordinates_module)?;
+
+    let facilities_module = PyModule::new(py, "facilities")?;
+    facilities::python::register(py, facilities_module)?;
+    m.add_submodule(facilities_module)?;
+
+    let formats_module = PyModule::new(py, "formats")?;
+    formats::python::register(py, formats_module)?;
+    m.add_submodule(formats_module)?;
+
+    let geofence_module = PyModule::new(py, "geofence")?;
+    geofence::python::register(py, geofence_module)?;
+    m.add_submodule(geofence_module)?;
+
+    let geohash_module = PyModule::new(py, "geohash")?;
+    geohash::python::register(py, geohash_module)?;
+    m.add_submodule(geohash_module)?;
+
+    let graph_module = PyModule::new(py, "graph")?;
+    graph::python::register(py, graph_module)?;
+    m.add_submodule(graph_module)?;
+
+    let matching_module = PyModule::new(py, "matching")?;
+    matching::python::register(py, matching_module)?;
+    m.add_submodule(matching_module)?;
+
+    let tiles_module = PyModule::new(py, "tiles")?;
+    tiles::python::register(py, tiles_module)?;
+    m.add_submodule(tiles_module)?;
+
+    let trajectory_module = PyModule::new(py, "trajectory")?;
+    trajectory::python::register(py, trajectory_module)?;
+    m.add_submodule(trajectory_module)?;
+
+    let transforms_module = PyModule::new(py, "transforms")?;
+    transforms::python::register(py, transforms_module)?;
+    m.add_submodule(transforms_module)?;
+
     Ok(())
 }
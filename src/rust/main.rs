@@ -1,8 +1,148 @@
-/// This module is not in use.
-/// 
-/// This repo is built as a lib; `cargo run` has no meaning.
-fn main() -> Result<(), ()> {
-    println!("rust_geodistances cannot be run via `cargo run`;");
-    println!("Use `maturin build` and use in Python instead.");
+/// Command line interface.
+///
+/// This repo is primarily built as a Python library via `maturin`; the binary
+/// only exposes a few batch operations on files:
+///
+/// - ``within_distance <a> <b> <distance> [--method haversine|vincenty]``
+///
+///   Pairs of features between two GeoJSON layers within ``distance``, written to
+///   stdout as CSV with columns ``a,b,distance``.
+///
+/// - ``within_distance_csv <s> <e> <output> <distance> [--method haversine|vincenty]
+///   [--columns id,lat,lng] [--chunk-size n]``
+///
+///   Pairs of points between two CSV files within ``distance``, streamed in chunks
+///   and written to ``output`` as CSV with columns ``id_s,id_e,distance``.
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use lib_rust_geodistances::compatibility::CalculationMethod;
+use lib_rust_geodistances::formats::{
+    csv,
+    geojson,
+    FormatError,
+};
+
+const USAGE: &str = "\
+Usage:
+    rust_geodistances within_distance <a.geojson> <b.geojson> <distance> [--method haversine|vincenty]
+    rust_geodistances within_distance_csv <s.csv> <e.csv> <output.csv> <distance> [--method haversine|vincenty]
+                                          [--columns id,lat,lng] [--chunk-size n]
+
+Distances are in kilometres.";
+
+/// Parse the value of ``--method``.
+fn parse_method(name:&str) -> Result<CalculationMethod, String> {
+    return match name.to_lowercase().as_str() {
+        "haversine" => Ok(CalculationMethod::HAVERSINE),
+        "vincenty" => Ok(CalculationMethod::VINCENTY),
+        other => Err(format!("Unknown method {:?}.", other)),
+    };
+}
+
+fn within_distance(args:&[String]) -> Result<(), String> {
+    let mut positional: Vec<&String> = Vec::new();
+    let mut method = CalculationMethod::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--method" => {
+                method = parse_method(iter.next().ok_or("`--method` requires a value.")?)?;
+            },
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() != 3 {
+        return Err("`within_distance` requires exactly 3 arguments.".to_string());
+    }
+
+    let distance: f64 = positional[2].parse()
+                                     .map_err(| _ | format!("{:?} is not a valid distance.", positional[2]))?;
+
+    let read = | path:&String | -> Result<Vec<geojson::Feature>, String> {
+        return geojson::read_file(Path::new(path)).map_err(| err:FormatError | format!("{}: {}", path, err));
+    };
+    let (a, b) = (read(positional[0])?, read(positional[1])?);
+
+    println!("a,b,distance");
+    for (i, j, d) in geojson::within_distance(&method, &a, &b, distance, None) {
+        println!("{},{},{}", i, j, d);
+    }
+
+    return Ok(());
+}
+
+fn within_distance_csv(args:&[String]) -> Result<(), String> {
+    let mut positional: Vec<&String> = Vec::new();
+    let mut method = CalculationMethod::default();
+    let mut columns = csv::CsvColumns::default();
+    let mut chunk_size = csv::DEFAULT_CHUNK_SIZE;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--method" => {
+                method = parse_method(iter.next().ok_or("`--method` requires a value.")?)?;
+            },
+            "--columns" => {
+                let value = iter.next().ok_or("`--columns` requires a value.")?;
+                columns = match value.split(',').collect::<Vec<_>>()[..] {
+                    [id, lat, lng] => csv::CsvColumns::new(id, lat, lng),
+                    _ => return Err(format!("{:?} is not in the form of id,lat,lng.", value)),
+                };
+            },
+            "--chunk-size" => {
+                let value = iter.next().ok_or("`--chunk-size` requires a value.")?;
+                chunk_size = value.parse()
+                                  .ok()
+                                  .filter(| size | *size > 0)
+                                  .ok_or_else(|| format!("{:?} is not a valid chunk size.", value))?;
+            },
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() != 4 {
+        return Err("`within_distance_csv` requires exactly 4 arguments.".to_string());
+    }
+
+    let distance: f64 = positional[3].parse()
+                                     .map_err(| _ | format!("{:?} is not a valid distance.", positional[3]))?;
+
+    let count = csv::within_distance_join(
+        &method,
+        Path::new(positional[0]), Path::new(positional[1]), Path::new(positional[2]),
+        &columns, &columns,
+        distance,
+        chunk_size,
+        None,
+    )
+    .map_err(| err:FormatError | err.to_string())?;
+
+    eprintln!("{} pairs written to {}.", count, positional[2]);
+
     return Ok(());
 }
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("within_distance") => within_distance(&args[1..]),
+        Some("within_distance_csv") => within_distance_csv(&args[1..]),
+        Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(msg) = result {
+        eprintln!("{}", msg);
+        process::exit(2);
+    }
+}
@@ -0,0 +1,495 @@
+/// Map matching of noisy tracks onto a road network.
+///
+/// A :class:`Network` is a set of nodes joined by undirected edges, each of which
+/// is a polyline from one node to another. Every segment of every edge is indexed
+/// by a :class:`~spatial::UnitVectorTree` over its midpoint, so that the edges
+/// near a point can be found without scanning the whole network.
+///
+/// Tracks are matched by a hidden Markov model, after Newson & Krumm (2009):
+///
+/// - the hidden states of each point are the edges within ``radius`` of it, each
+///   at the position on the edge closest to the point;
+/// - the emission probability of a state is Gaussian in the geodesic cross-track
+///   distance from the point to the edge, with deviation ``sigma``; and
+/// - the transition probability between states of consecutive points decays
+///   exponentially, with scale ``beta``, in the difference between the distance
+///   travelled along the network and the distance between the points.
+///
+/// The most likely sequence of states is found by the Viterbi algorithm. Points
+/// with no edge within ``radius`` are left unmatched; if no route connects any
+/// state of a point to any state of the previous point, the track is matched in
+/// separate pieces either side of the break.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+use rayon::prelude::*;
+
+use ndarray::{
+    s,
+    Axis,
+};
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+use crate::graph::{
+    Graph,
+    GraphError,
+};
+use crate::spatial::{
+    UnitVectorTree,
+    to_unit_vector,
+};
+use crate::trajectory::{
+    cumulative_lengths,
+    distance_to_segment,
+    segment_lengths,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationInterfaceNeighbours,
+    CalculationInterfacePairwise,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+// Import this if you want the matching module to be available in Python.
+pub mod python;
+
+/// Longest route considered between the states of consecutive points, as a
+/// multiple of the distance between the points; longer routes are taken to be
+/// impossible.
+pub const MAX_DETOUR:f64 = 4.;
+
+/// Errors from map matching.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchingError {
+    /// Arrays of the wrong dimensions or lengths.
+    Shape(String),
+
+    /// An edge refers to a node that does not exist.
+    InvalidNode(usize),
+
+    /// A parameter is out of its valid range.
+    Parameter(String),
+
+    /// The edges do not form a valid :class:`~graph::Graph`.
+    Graph(GraphError),
+}
+impl fmt::Display for MatchingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+            Self::InvalidNode(node) => write!(f, "Edge refers to node {:?}, which does not exist.", node),
+            Self::Parameter(msg) => write!(f, "Invalid parameter: {}", msg),
+            Self::Graph(err) => write!(f, "Invalid network: {}", err),
+        };
+    }
+}
+impl std::error::Error for MatchingError {}
+impl From<GraphError> for MatchingError {
+    fn from(err: GraphError) -> Self {
+        return Self::Graph(err);
+    }
+}
+
+/// An undirected edge of a :class:`Network`.
+#[derive(Clone, Debug)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+
+    /// Polyline of dimension ``(k, 2)`` from node ``from`` to node ``to``.
+    pub geometry: F64LatLngArray,
+
+    /// Distance along the polyline up to each vertex, of dimension ``(k)``.
+    pub cumulative_lengths: F64Array1,
+}
+impl Edge {
+    /// Length of the whole polyline.
+    pub fn length(&self) -> f64 {
+        return self.cumulative_lengths[self.cumulative_lengths.len() - 1];
+    }
+}
+
+/// A possible state of a point: the closest position to it on one edge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate {
+    pub edge: usize,
+
+    /// Index of the segment of the edge that the position is on.
+    pub segment: usize,
+
+    /// Distance of the position from the start of its segment.
+    pub along: f64,
+
+    /// Distance of the position from node ``from`` of the edge.
+    pub offset: f64,
+
+    /// Cross-track distance from the point to the position.
+    pub distance: f64,
+}
+
+/// Road network indexed for map matching.
+pub struct Network {
+    edges: Vec<Edge>,
+
+    /// Undirected graph of the edges, weighted by their lengths.
+    graph: Graph,
+
+    /// ``(edge, segment)`` of each point in ``tree``.
+    segments: Vec<(usize, usize)>,
+    tree: UnitVectorTree,
+
+    /// Half the length of the longest segment.
+    max_half_segment: f64,
+}
+impl Network {
+    /// Build a network from nodes of dimension ``(k, 2)`` and the ``(from, to)``
+    /// nodes of each edge.
+    ///
+    /// If provided, ``geometries`` are the vertices of each edge between its two
+    /// nodes, each of dimension ``(j, 2)``; otherwise edges are straight between
+    /// their nodes.
+    pub fn new(
+        method:&CalculationMethod,
+        nodes:&dyn LatLngArray,
+        edges:&[(usize, usize)],
+        geometries:Option<&[F64LatLngArray]>,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Self, MatchingError> {
+        let nodes = nodes.to_owned();
+        let k = nodes.shape()[0];
+
+        if let Some(geometries) = geometries {
+            if geometries.len() != edges.len() {
+                return Err(MatchingError::Shape(
+                    format!("expected {:?} geometries, yet {:?} provided.", edges.len(), geometries.len())
+                ));
+            }
+            if let Some(i) = geometries.iter().position(| geometry | geometry.shape()[1] != 2) {
+                return Err(MatchingError::Shape(format!("geometry {:?} must be of dimension (j, 2).", i)));
+            }
+        }
+        if let Some(node) = edges.iter().flat_map(| (from, to) | [*from, *to]).find(| node | *node >= k) {
+            return Err(MatchingError::InvalidNode(node));
+        }
+
+        let edges: Vec<Edge> = edges.iter()
+                                    .enumerate()
+                                    .map(
+                                        | (i, (from, to)) | {
+                                            let mut geometry = nodes.select(Axis(0), &[*from, *to]);
+                                            if let Some(geometries) = geometries {
+                                                geometry = ndarray::concatenate(
+                                                    Axis(0),
+                                                    &[
+                                                        nodes.slice(s![*from..*from+1, ..]),
+                                                        geometries[i].view(),
+                                                        nodes.slice(s![*to..*to+1, ..]),
+                                                    ],
+                                                ).unwrap();
+                                            }
+
+                                            let lengths = segment_lengths(method, &geometry, settings);
+
+                                            Edge {
+                                                from: *from,
+                                                to: *to,
+                                                geometry,
+                                                cumulative_lengths: cumulative_lengths(&lengths),
+                                            }
+                                        }
+                                    )
+                                    .collect();
+
+        let graph = Graph::from_weights(
+            &nodes,
+            &edges.iter().map(| edge | (edge.from, edge.to, edge.length())).collect::<Vec<_>>(),
+            false,
+        )?;
+
+        let mut segments = Vec::new();
+        let mut midpoints = Vec::new();
+        let mut max_half_segment: f64 = 0.;
+        for (i, edge) in edges.iter().enumerate() {
+            for j in 0..edge.geometry.shape()[0] - 1 {
+                let a = to_unit_vector(edge.geometry[[j, 0]], edge.geometry[[j, 1]]);
+                let b = to_unit_vector(edge.geometry[[j + 1, 0]], edge.geometry[[j + 1, 1]]);
+
+                let sum = [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+                let norm = sum.iter().map(| v | v * v).sum::<f64>().sqrt();
+
+                segments.push((i, j));
+                midpoints.push(if norm > 0. { sum.map(| v | v / norm) } else { a });
+                max_half_segment = max_half_segment.max(
+                    (edge.cumulative_lengths[j + 1] - edge.cumulative_lengths[j]) / 2.
+                );
+            }
+        }
+
+        return Ok(Self {
+            edges,
+            graph,
+            segments,
+            tree: UnitVectorTree::from_vectors(midpoints),
+            max_half_segment,
+        });
+    }
+
+    pub fn nodes(&self) -> &F64LatLngArray {
+        return self.graph.nodes();
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        return &self.edges;
+    }
+
+    pub fn graph(&self) -> &Graph {
+        return &self.graph;
+    }
+
+    /// Closest position on each edge within ``radius`` of ``p``, in order of
+    /// distance.
+    pub fn candidates(
+        &self,
+        method:&CalculationMethod,
+        p:(f64, f64),
+        radius:f64,
+        settings: Option<&CalculationSettings>,
+    ) -> Vec<Candidate> {
+        // A point within `radius` of a segment is within `radius` plus half its
+        // length of its midpoint.
+        let max_chord = method._chord_bound(radius + self.max_half_segment, settings);
+        let q = to_unit_vector(p.0, p.1);
+
+        let mut closest: HashMap<usize, Candidate> = HashMap::new();
+        for (index, _) in self.tree.within(&q, max_chord, | _ | true) {
+            let (edge, segment) = self.segments[index];
+            let geometry = &self.edges[edge].geometry;
+
+            let (distance, along) = distance_to_segment(
+                method,
+                (geometry[[segment, 0]], geometry[[segment, 1]]),
+                (geometry[[segment + 1, 0]], geometry[[segment + 1, 1]]),
+                p,
+                settings,
+            );
+            if distance > radius { continue }
+
+            let candidate = Candidate {
+                edge,
+                segment,
+                along,
+                offset: self.edges[edge].cumulative_lengths[segment] + along,
+                distance,
+            };
+            closest.entry(edge)
+                   .and_modify(
+                       | best | if (distance, segment) < (best.distance, best.segment) { *best = candidate }
+                   )
+                   .or_insert(candidate);
+        }
+
+        let mut candidates: Vec<Candidate> = closest.into_values().collect();
+        candidates.sort_by(| a, b | a.distance.total_cmp(&b.distance).then(a.edge.cmp(&b.edge)));
+
+        return candidates;
+    }
+
+    /// Distances along the network between each of ``from`` and each of ``to``,
+    /// of dimension ``(from, to)``; ``inf`` where longer than ``limit``.
+    pub fn route_distances(
+        &self,
+        from:&[Candidate],
+        to:&[Candidate],
+        limit:f64,
+    ) -> Vec<Vec<f64>> {
+        // Distances from a candidate to each end of its edge.
+        let ends = | c:&Candidate | {
+            let edge = &self.edges[c.edge];
+            [(edge.from, c.offset), (edge.to, edge.length() - c.offset)]
+        };
+
+        let mut trees: HashMap<usize, HashMap<usize, f64>> = HashMap::new();
+        for c in from {
+            for (node, _) in ends(c) {
+                trees.entry(node).or_insert_with(| | self.graph.distances_from(node, limit));
+            }
+        }
+
+        return from.iter()
+                   .map(
+                       | a | to.iter()
+                               .map(
+                                   | b | {
+                                       let mut best = if a.edge == b.edge {
+                                           (b.offset - a.offset).abs()
+                                       } else {
+                                           f64::INFINITY
+                                       };
+
+                                       for (u, to_u) in ends(a) {
+                                           for (v, to_v) in ends(b) {
+                                               if let Some(d) = trees[&u].get(&v) {
+                                                   best = best.min(to_u + d + to_v);
+                                               }
+                                           }
+                                       }
+
+                                       if best <= limit { best } else { f64::INFINITY }
+                                   }
+                               )
+                               .collect()
+                   )
+                   .collect();
+    }
+}
+
+/// Check that a parameter is positive and finite.
+fn check_positive(name:&str, value:f64) -> Result<(), MatchingError> {
+    if !(value > 0. && value.is_finite()) {
+        return Err(MatchingError::Parameter(
+            format!("`{}` must be positive, yet {:?} provided.", name, value)
+        ));
+    }
+
+    return Ok(());
+}
+
+/// Follow the back pointers of the best state at step ``t`` to the start of its
+/// chain, recording the chosen state of each step.
+fn backtrack(
+    t:usize,
+    scores:&[f64],
+    back:&[Vec<Option<(usize, usize)>>],
+    chosen:&mut [Option<usize>],
+) {
+    let mut state = Some((
+        t,
+        (0..scores.len()).fold(0, | best, j | if scores[j] > scores[best] { j } else { best }),
+    ));
+
+    while let Some((t, j)) = state {
+        chosen[t] = Some(j);
+        state = back[t][j];
+    }
+}
+
+/// Match a track of dimension ``(n, 2)`` onto a network.
+///
+/// Returns a tuple of the matched edge of each point, and the position on that
+/// edge of dimension ``(n, 2)``; both are empty, i.e. ``None`` and ``NaN``, for
+/// unmatched points.
+pub fn match_track(
+    method:&CalculationMethod,
+    network:&Network,
+    s:&dyn LatLngArray,
+    radius:f64,
+    sigma:f64,
+    beta:f64,
+    settings: Option<&CalculationSettings>,
+) -> Result<(Vec<Option<usize>>, F64LatLngArray), MatchingError> {
+    check_positive("radius", radius)?;
+    check_positive("sigma", sigma)?;
+    check_positive("beta", beta)?;
+
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+    let point = | i:usize | (s_owned[[i, 0]], s_owned[[i, 1]]);
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+    let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+    let candidates: Vec<Vec<Candidate>> = {
+        (0..n).into_par_iter()
+              .with_min_len(chunk_size)
+              .map(| i | network.candidates(method, point(i), radius, settings))
+              .collect()
+    };
+
+    // Viterbi, over the points that have any candidates.
+    let mut back: Vec<Vec<Option<(usize, usize)>>> = candidates.iter().map(| c | vec![None; c.len()]).collect();
+    let mut chosen: Vec<Option<usize>> = vec![None; n];
+    let mut scores: Vec<f64> = Vec::new();
+    let mut last: Option<usize> = None;
+
+    for t in 0..n {
+        if candidates[t].is_empty() { continue }
+
+        let emissions: Vec<f64> = candidates[t].iter().map(| c | -0.5 * (c.distance / sigma).powi(2)).collect();
+        let mut next = vec![f64::NEG_INFINITY; candidates[t].len()];
+
+        if let Some(prev) = last {
+            let (p, q) = (point(prev), point(t));
+            let straight = method._inverse(p.0, p.1, q.0, q.1, settings).0;
+            let routes = network.route_distances(
+                &candidates[prev],
+                &candidates[t],
+                straight * MAX_DETOUR + 2. * radius,
+            );
+
+            for (i, row) in routes.iter().enumerate() {
+                for (j, route) in row.iter().enumerate() {
+                    if !route.is_finite() { continue }
+
+                    let score = scores[i] - (route - straight).abs() / beta + emissions[j];
+                    if score > next[j] {
+                        next[j] = score;
+                        back[t][j] = Some((prev, i));
+                    }
+                }
+            }
+        }
+
+        // First point, or a break in the chain: start a new chain from here.
+        if next.iter().all(| score | *score == f64::NEG_INFINITY) {
+            if let Some(prev) = last { backtrack(prev, &scores, &back, &mut chosen); }
+            next = emissions;
+        }
+
+        scores = next;
+        last = Some(t);
+    }
+    if let Some(prev) = last { backtrack(prev, &scores, &back, &mut chosen); }
+
+    // Snap the matched points onto their edges.
+    let mut positions = F64LatLngArray::from_elem((n, 2), f64::NAN);
+    let matched: Vec<usize> = (0..n).filter(| t | chosen[*t].is_some()).collect();
+    let states: Vec<Candidate> = matched.iter().map(| t | candidates[*t][chosen[*t].unwrap()]).collect();
+
+    let mut origins = F64LatLngArray::zeros((states.len(), 2));
+    let mut bearings = F64Array1::zeros(states.len());
+    for (k, c) in states.iter().enumerate() {
+        let geometry = &network.edges[c.edge].geometry;
+        let (a, b) = (geometry.row(c.segment), geometry.row(c.segment + 1));
+
+        origins.row_mut(k).assign(&a);
+        bearings[k] = method._inverse(a[0], a[1], b[0], b[1], settings).1;
+    }
+    let along: F64Array1 = states.iter().map(| c | c.along).collect();
+
+    if !states.is_empty() {
+        let snapped = CalculationInterfaceInternal::<&F64Array1>::_displace(method, &origins, &along, &bearings, settings);
+
+        for (k, t) in matched.iter().enumerate() {
+            positions.row_mut(*t).assign(&snapped.row(k));
+        }
+    }
+
+    return Ok((
+        chosen.iter()
+              .zip(candidates.iter())
+              .map(| (j, c) | j.map(| j | c[j].edge))
+              .collect(),
+        positions,
+    ));
+}
@@ -0,0 +1,160 @@
+/// Python compatibility layer for the `matching` module.
+///
+/// The functions here are registered into a `matching` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.matching`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use ndarray_numeric::F64LatLngArray;
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+use crate::graph::python::edges_from_py;
+
+use super::{
+    MatchingError,
+    Network,
+};
+
+impl From<MatchingError> for PyErr {
+    fn from(err: MatchingError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(nodes, edges, s, radius, sigma, beta, geometries, method, *, settings)")]
+/// Match a noisy track onto a road network.
+///
+/// The states of each point are the closest positions on the edges within
+/// ``radius`` of it. The most likely sequence of states is found by a hidden
+/// Markov model, scoring each state by its geodesic cross-track distance from the
+/// point, and each transition by how much the distance along the network differs
+/// from the distance between consecutive points.
+///
+/// Edges are undirected. Points with no edge within ``radius`` are left
+/// unmatched; if no route connects a point to the previous one, the track is
+/// matched in separate pieces either side.
+///
+/// Parameters
+/// ----------
+/// nodes: numpy.ndarray
+///     Of dimension ``(k, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// edges: numpy.ndarray
+///     Of dimension ``(m, 2)`` and integer type, the indices in ``nodes`` of the
+///     two ends of each edge.
+///
+/// s: numpy.ndarray
+///     The track, of dimension ``(n, 2)``, in degrees. In order
+///     ``(latitude, longitude)``.
+///
+/// radius: numpy.float64
+///     Maximum distance from a point to the edges it can be matched to.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// sigma: numpy.float64
+///     Standard deviation of the positioning error, in the same unit as
+///     ``radius``.
+///
+/// beta: numpy.float64
+///     Scale of the difference between route and straight distances between
+///     consecutive points, in the same unit as ``radius``. Smaller values favour
+///     direct routes more strongly.
+///
+/// geometries: Optional[List[numpy.ndarray]]
+///     The vertices of each edge between its two nodes, each of dimension
+///     ``(j, 2)``. If omitted, edges are straight between their nodes.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(edges, positions)``: ``edges`` is of dimension ``(n)``, the index of the
+///     edge each point is matched to or ``-1`` if unmatched, and ``positions`` of
+///     dimension ``(n, 2)`` the position of each point on its edge, or ``nan`` if
+///     unmatched.
+///
+/// Example
+/// -------
+/// Match a GPS track with 10 metre accuracy::
+///
+///     >>> from rust_geodistances import matching
+///     >>> edges, positions = matching.match_track(
+///     ...     nodes, edges, track, 0.05, 0.01, 0.02,
+///     ... )
+fn match_track(
+    nodes: &PyArray<f64, Ix2>,
+    edges: &PyArray<i64, Ix2>,
+    s: &PyArray<f64, Ix2>,
+    radius: f64,
+    sigma: f64,
+    beta: f64,
+    geometries: Option<Vec<&PyArray<f64, Ix2>>>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let method = method.unwrap_or(method_default);
+
+    let geometries: Option<Vec<F64LatLngArray>> = geometries.map(
+        | geometries | geometries.iter().map(| geometry | geometry.to_owned_array()).collect()
+    );
+
+    let network = Network::new(
+        method,
+        &nodes.to_owned_array(),
+        &edges_from_py(edges)?,
+        geometries.as_deref(),
+        settings,
+    )?;
+
+    let (matched, positions) = super::match_track(
+        method,
+        &network,
+        &s.to_owned_array(),
+        radius,
+        sigma,
+        beta,
+        settings,
+    )?;
+
+    let matched = Array1::from_iter(matched.iter().map(| edge | edge.map_or(-1, | edge | edge as i64)));
+
+    return Ok((matched.to_pyarray(py), positions.to_pyarray(py)).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(match_track, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,374 @@
+/// K-dimensional tree over unit vectors on the sphere.
+///
+/// Each latitude-longitude pair is converted into a 3-dimensional unit vector
+/// ``(x, y, z)``; the straight line (chord) distance between two such vectors is
+/// a monotonic function of the great-circle angle between them. This means that
+/// for the purpose of ranking neighbours on a sphere, the chord distance is exact,
+/// and unlike latitude-longitude boxes it does not break down near the poles or
+/// the antimeridian.
+///
+/// The tree is stored implicitly: the points are permuted such that the median of
+/// each range is the splitting node of that range. No nodes are allocated other
+/// than a permutation of indices and one split axis per point, so memory usage is
+/// linear to the number of points.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f64::consts::PI;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    ArrayWithF64LatLngMethods,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+    LatLngArray,
+};
+
+pub type UnitVector = [f64; 3];
+
+/// Convert a pair of latitude and longitude in radians into a unit vector.
+pub fn to_unit_vector_rad(lat_r:f64, lng_r:f64) -> UnitVector {
+    return [
+        lat_r.cos() * lng_r.cos(),
+        lat_r.cos() * lng_r.sin(),
+        lat_r.sin(),
+    ];
+}
+
+/// Convert a pair of latitude and longitude in degrees into a unit vector.
+pub fn to_unit_vector(lat:f64, lng:f64) -> UnitVector {
+    return to_unit_vector_rad(lat * PI / 180., lng * PI / 180.);
+}
+
+/// Convert a central angle in radians into the chord length on the unit sphere.
+pub fn angle_to_chord(angle_r:f64) -> f64 {
+    return 2. * (angle_r.clamp(0., PI) / 2.).sin();
+}
+
+/// Convert a chord length on the unit sphere into a central angle in radians.
+pub fn chord_to_angle(chord:f64) -> f64 {
+    return 2. * (chord.clamp(0., 2.) / 2.).asin();
+}
+
+/// Chord distance between two unit vectors.
+pub fn chord(a:&UnitVector, b:&UnitVector) -> f64 {
+    return (
+        (a[0]-b[0]).powi(2)
+        + (a[1]-b[1]).powi(2)
+        + (a[2]-b[2]).powi(2)
+    ).sqrt();
+}
+
+/// A candidate found during a search, ordered by its chord distance.
+///
+/// `f64` is not `Ord`, so this wrapper is required to keep candidates in a
+/// `BinaryHeap`.
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    chord: f64,
+    index: usize,
+}
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        return self.cmp(other) == Ordering::Equal;
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.chord.total_cmp(&other.chord)
+                         .then(self.index.cmp(&other.index));
+    }
+}
+
+/// Implicit K-dimensional tree of unit vectors.
+///
+/// Indices returned by all the search methods are the row indices of the array
+/// used to build the tree.
+pub struct UnitVectorTree {
+    vectors: Vec<UnitVector>,
+    order: Vec<usize>,
+    axes: Vec<u8>,
+}
+impl UnitVectorTree {
+    /// Build a tree from an array of lat-long coordinates.
+    pub fn new(s:&dyn LatLngArray) -> Self {
+        let s_latlng_r = s.to_rad();
+
+        let vectors = s_latlng_r.axis_iter(Axis(0))
+                                .map(
+                                    | row | to_unit_vector_rad(row[0], row[1])
+                                )
+                                .collect();
+
+        return Self::from_vectors(vectors);
+    }
+
+    /// Build a tree from a `Vec` of unit vectors.
+    pub fn from_vectors(vectors:Vec<UnitVector>) -> Self {
+        let mut order: Vec<usize> = (0..vectors.len()).collect();
+        let mut axes: Vec<u8> = vec![0; vectors.len()];
+
+        Self::build(&vectors, &mut order, &mut axes);
+
+        return Self {
+            vectors,
+            order,
+            axes,
+        };
+    }
+
+    /// Recursively partition `order` around the median of the widest axis.
+    fn build(
+        vectors:&Vec<UnitVector>,
+        order:&mut [usize],
+        axes:&mut [u8],
+    ) {
+        if order.len() <= 1 { return }
+
+        let axis = {
+            let mut spread = [0_f64; 3];
+
+            for dim in 0..3 {
+                let (min, max) = order.iter().fold(
+                    (f64::INFINITY, f64::NEG_INFINITY),
+                    | (min, max), idx | (
+                        min.min(vectors[*idx][dim]),
+                        max.max(vectors[*idx][dim]),
+                    )
+                );
+                spread[dim] = max - min;
+            }
+
+            (0..3).fold(0, | widest, dim | if spread[dim] > spread[widest] { dim } else { widest })
+        };
+
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by(
+            mid,
+            | a, b | vectors[*a][axis].total_cmp(&vectors[*b][axis])
+        );
+        axes[mid] = axis as u8;
+
+        let (order_lower, order_upper) = order.split_at_mut(mid);
+        let (axes_lower, axes_upper) = axes.split_at_mut(mid);
+
+        Self::build(vectors, order_lower, axes_lower);
+        Self::build(vectors, &mut order_upper[1..], &mut axes_upper[1..]);
+    }
+
+    /// Number of points in the tree.
+    pub fn len(&self) -> usize {
+        return self.vectors.len();
+    }
+
+    /// Whether the tree contains no points.
+    pub fn is_empty(&self) -> bool {
+        return self.vectors.is_empty();
+    }
+
+    /// The unit vector of the point at row ``index``.
+    pub fn vector(&self, index:usize) -> &UnitVector {
+        return &self.vectors[index];
+    }
+
+    /// The unit vector of a lat-long coordinate.
+    pub fn query_vector(s:&dyn LatLng) -> UnitVector {
+        return to_unit_vector(s[0], s[1]);
+    }
+
+    /// Label shared by every point of each subtree, given a ``labels`` for each
+    /// row; ``None`` where a subtree has points of different labels.
+    ///
+    /// Indexed by the root ``node`` of each subtree, as passed to the ``skip``
+    /// argument of :meth:`nearest_skipping` and :meth:`within_skipping`.
+    pub fn subtree_labels(&self, labels:&[usize]) -> Vec<Option<usize>> {
+        let mut uniform = vec![None; self.order.len()];
+
+        if !self.order.is_empty() {
+            self.label_subtree(0, self.order.len(), labels, &mut uniform);
+        }
+
+        return uniform;
+    }
+
+    fn label_subtree(
+        &self,
+        lo:usize,
+        hi:usize,
+        labels:&[usize],
+        uniform:&mut Vec<Option<usize>>,
+    ) -> Option<usize> {
+        let mid = (lo + hi) / 2;
+        let label = labels[self.order[mid]];
+
+        let lower = if lo < mid { self.label_subtree(lo, mid, labels, uniform) } else { Some(label) };
+        let upper = if mid+1 < hi { self.label_subtree(mid+1, hi, labels, uniform) } else { Some(label) };
+
+        uniform[mid] = if lower == Some(label) && upper == Some(label) { Some(label) } else { None };
+
+        return uniform[mid];
+    }
+
+    /// Find the ``k`` nearest points to ``q`` accepted by ``accept``.
+    ///
+    /// Only points with a chord distance of ``max_chord`` or less are returned;
+    /// use ``f64::INFINITY`` to search without a bound.
+    ///
+    /// Returns a `Vec` of ``(index, chord)`` sorted by ascending chord distance.
+    pub fn nearest<F>(
+        &self,
+        q:&UnitVector,
+        k:usize,
+        max_chord:f64,
+        accept:F,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool {
+        return self.nearest_skipping(q, k, max_chord, accept, | _ | false);
+    }
+
+    /// Same as :meth:`nearest`, but without descending into any subtree whose
+    /// root ``node`` has ``skip(node)``.
+    ///
+    /// ``skip`` is meant to discard whole subtrees that ``accept`` would reject
+    /// point by point, e.g. through :meth:`subtree_labels`.
+    pub fn nearest_skipping<F, G>(
+        &self,
+        q:&UnitVector,
+        k:usize,
+        max_chord:f64,
+        accept:F,
+        skip:G,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool {
+        let mut heap = BinaryHeap::with_capacity(k+1);
+
+        if k > 0 {
+            self.search_nearest(0, self.order.len(), q, k, max_chord, &accept, &skip, &mut heap);
+        }
+
+        return heap.into_sorted_vec()
+                   .into_iter()
+                   .map(| candidate | (candidate.index, candidate.chord))
+                   .collect();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_nearest<F, G>(
+        &self,
+        lo:usize,
+        hi:usize,
+        q:&UnitVector,
+        k:usize,
+        max_chord:f64,
+        accept:&F,
+        skip:&G,
+        heap:&mut BinaryHeap<Candidate>,
+    )
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool {
+        if lo >= hi || skip((lo + hi) / 2) { return }
+
+        let bound = | heap:&BinaryHeap<Candidate> | {
+            if heap.len() >= k {
+                heap.peek().map(| worst | worst.chord.min(max_chord)).unwrap_or(max_chord)
+            } else {
+                max_chord
+            }
+        };
+
+        let mid = (lo + hi) / 2;
+        let index = self.order[mid];
+        let axis = self.axes[mid] as usize;
+
+        let d = chord(q, &self.vectors[index]);
+        if d <= bound(heap) && accept(index) {
+            heap.push(Candidate{ chord: d, index });
+            if heap.len() > k { heap.pop(); }
+        }
+
+        let diff = q[axis] - self.vectors[index][axis];
+        let (near, far) = if diff < 0. {
+            ((lo, mid), (mid+1, hi))
+        } else {
+            ((mid+1, hi), (lo, mid))
+        };
+
+        self.search_nearest(near.0, near.1, q, k, max_chord, accept, skip, heap);
+
+        if diff.abs() <= bound(heap) {
+            self.search_nearest(far.0, far.1, q, k, max_chord, accept, skip, heap);
+        }
+    }
+
+    /// Find all points accepted by ``accept`` within ``max_chord`` of ``q``.
+    ///
+    /// Returns a `Vec` of ``(index, chord)`` in no particular order.
+    pub fn within<F>(
+        &self,
+        q:&UnitVector,
+        max_chord:f64,
+        accept:F,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool {
+        return self.within_skipping(q, max_chord, accept, | _ | false);
+    }
+
+    /// Same as :meth:`within`, but without descending into any subtree whose root
+    /// ``node`` has ``skip(node)``.
+    pub fn within_skipping<F, G>(
+        &self,
+        q:&UnitVector,
+        max_chord:f64,
+        accept:F,
+        skip:G,
+    ) -> Vec<(usize, f64)>
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool {
+        let mut found = Vec::new();
+
+        self.search_within(0, self.order.len(), q, max_chord, &accept, &skip, &mut found);
+
+        return found;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_within<F, G>(
+        &self,
+        lo:usize,
+        hi:usize,
+        q:&UnitVector,
+        max_chord:f64,
+        accept:&F,
+        skip:&G,
+        found:&mut Vec<(usize, f64)>,
+    )
+    where F: Fn(usize) -> bool, G: Fn(usize) -> bool {
+        if lo >= hi || skip((lo + hi) / 2) { return }
+
+        let mid = (lo + hi) / 2;
+        let index = self.order[mid];
+        let axis = self.axes[mid] as usize;
+
+        let d = chord(q, &self.vectors[index]);
+        if d <= max_chord && accept(index) {
+            found.push((index, d));
+        }
+
+        let diff = q[axis] - self.vectors[index][axis];
+
+        // Lower half has coordinates `<=` the split, upper half `>=`.
+        if diff <= max_chord {
+            self.search_within(lo, mid, q, max_chord, accept, skip, found);
+        }
+        if -diff <= max_chord {
+            self.search_within(mid+1, hi, q, max_chord, accept, skip, found);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+/// Spatial indexing structures.
+///
+/// The calculation models in `calc_models` are brute-force by design: every
+/// function maps each point of `s` against every point of `e`. This is fine for
+/// tens of thousands of points, but any algorithm that only needs the *closest*
+/// few points of each coordinate (e.g. spanning trees, nearest facilities) cannot
+/// afford an `(n, n)` array in memory.
+///
+/// The structs in this module index coordinates by their position on the unit
+/// sphere, which allows candidate points to be pruned before they are handed over
+/// to the chosen calculation model for the actual distance calculations.
+
+pub mod kdtree;
+
+pub use kdtree::{
+    UnitVectorTree,
+    angle_to_chord,
+    chord,
+    chord_to_angle,
+    to_unit_vector,
+};
@@ -252,3 +252,2271 @@ mod test_distance {
         );
     }
 }
+
+#[cfg(test)]
+mod test_spanning_tree {
+    use std::f64::consts::PI;
+
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::calc_models::config;
+    use crate::compatibility::{
+        CalculationMethod,
+        CalculationInterfaceSpanningTree,
+    };
+    use crate::spatial::{
+        UnitVectorTree,
+        chord,
+        to_unit_vector,
+    };
+
+    #[test]
+    fn test_unit_vector_tree_nearest() {
+        let s: F64LatLngArray = arr2(&[
+            [ 0.,   0.],
+            [ 0.,  10.],
+            [10.,   0.],
+            [89., 179.],
+            [-5., 170.],
+            [ 1.,   1.],
+        ]);
+        let tree = UnitVectorTree::new(&s);
+        let q = to_unit_vector(0.5, 0.6);
+
+        let found = tree.nearest(&q, 2, f64::INFINITY, | _ | true);
+        assert_eq!(found.iter().map(| (idx, _) | *idx).collect::<Vec<_>>(), vec![5, 0]);
+
+        let excluded = tree.nearest(&q, 1, f64::INFINITY, | idx | idx != 5);
+        assert_eq!(excluded[0].0, 0);
+
+        let mut within: Vec<usize> = tree.within(&q, chord(&q, &to_unit_vector(0., 10.)), | _ | true)
+                                         .into_iter()
+                                         .map(| (idx, _) | idx)
+                                         .collect();
+        within.sort();
+        assert_eq!(within, vec![0, 1, 5]);
+    }
+
+    #[test]
+    fn test_haversine_minimum_spanning_tree() {
+        let s: F64LatLngArray = arr2(&[
+            [0., 6.],
+            [0., 0.],
+            [0., 3.],
+            [0., 1.],
+        ]);
+
+        let edges = CalculationMethod::HAVERSINE._minimum_spanning_tree(&s, None);
+        let degree = config::RADIUS * PI / 180.;
+
+        assert_eq!(
+            edges.iter().map(| edge | (edge.0, edge.1)).collect::<Vec<_>>(),
+            vec![(1, 3), (2, 3), (0, 2)]
+        );
+        for (edge, expected) in edges.iter().zip([1., 2., 3.]) {
+            assert!((edge.2 - expected * degree).abs() < 1e-9);
+        }
+
+        let linkage = CalculationMethod::HAVERSINE._single_linkage(&s, None);
+        assert_eq!(linkage.shape(), &[3, 4]);
+        assert_eq!(linkage.column(0).to_vec(), vec![1., 2., 0.]);
+        assert_eq!(linkage.column(1).to_vec(), vec![3., 4., 5.]);
+        assert_eq!(linkage.column(3).to_vec(), vec![2., 3., 4.]);
+    }
+
+    #[test]
+    fn test_unit_vector_tree_skipping() {
+        // Scattered points in 4 interleaved groups.
+        let s = Array2::from_shape_fn((500, 2), | (i, j) | {
+            let x = i as f64;
+            if j == 0 { (x * 7.3) % 160. - 80. } else { (x * 13.7) % 360. - 180. }
+        });
+        let labels: Vec<usize> = (0..500).map(| i | if i < 400 { i % 2 } else { 2 + i % 2 }).collect();
+
+        let tree = UnitVectorTree::new(&s);
+        let uniform = tree.subtree_labels(&labels);
+
+        for i in (0..500).step_by(7) {
+            let q = to_unit_vector(s[[i, 0]], s[[i, 1]]);
+            let accept = | j:usize | labels[j] != labels[i];
+
+            assert_eq!(
+                tree.nearest_skipping(&q, 3, f64::INFINITY, accept, | node | uniform[node] == Some(labels[i])),
+                tree.nearest(&q, 3, f64::INFINITY, accept),
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_separated_clusters() {
+        // Two dense clusters of 10,000 points each, thousands of km apart; later
+        // rounds only work if searches skip their own, by then huge, component.
+        let half = 10_000;
+        let s = Array2::from_shape_fn((half * 2, 2), | (i, j) | {
+            let x = (i % half) as f64;
+            let offset = if i < half { 0. } else { 40. };
+            if j == 0 { offset + (x * 0.6180339887) % 1. } else { offset + (x * 0.7548776662) % 1. }
+        });
+
+        let edges = CalculationMethod::HAVERSINE._minimum_spanning_tree(&s, None);
+
+        assert_eq!(edges.len(), half * 2 - 1);
+        assert_eq!(edges.iter().filter(| edge | edge.2 > 1000.).count(), 1);
+        assert!(edges.last().unwrap().2 > 1000.);
+    }
+}
+
+#[cfg(test)]
+mod test_routing {
+    use std::f64::consts::PI;
+
+    use ndarray::arr2;
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::calc_models::config;
+    use crate::compatibility::{
+        CalculationMethod,
+        CalculationInterfaceRouting,
+    };
+
+    #[test]
+    fn test_haversine_route_order() {
+        let s: F64LatLngArray = arr2(&[
+            [0., 0.],
+            [0., 3.],
+            [0., 1.],
+            [0., 5.],
+            [0., 2.],
+        ]);
+        let degree = config::RADIUS * PI / 180.;
+
+        let (order, length) = CalculationMethod::HAVERSINE._route_order(&s, 0, false, None);
+        assert_eq!(order, vec![0, 2, 4, 1, 3]);
+        assert!((length - 5. * degree).abs() < 1e-9);
+
+        let (order, length) = CalculationMethod::HAVERSINE._route_order(&s, 1, true, None);
+        assert_eq!(order[0], 1);
+        assert!((length - 10. * degree).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod test_clustering {
+    use ndarray::{arr1, arr2};
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::compatibility::{
+        CalculationMethod,
+        CalculationInterfaceClustering,
+    };
+    use crate::compatibility::clustering::{
+        clara_sample_size,
+        CLARA_MIN_SAMPLE_SIZE,
+        PAM_MAX_POINTS,
+    };
+
+    #[allow(non_upper_case_globals)]
+    static two_towns: [[f64; 2]; 6] = [
+        [51.50,  -0.12],
+        [51.51,  -0.13],
+        [51.49,  -0.11],
+        [48.85,   2.35],
+        [48.86,   2.34],
+        [48.84,   2.36],
+    ];
+
+    #[test]
+    fn test_haversine_cluster_kmedoids() {
+        let s: F64LatLngArray = arr2(&two_towns);
+
+        let (mut medoids, labels, _) = CalculationMethod::HAVERSINE._cluster_kmedoids(&s, 2, Some(0), None);
+        medoids.sort();
+
+        assert_eq!(medoids, vec![0, 3]);
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[3], labels[4]);
+        assert_eq!(labels[3], labels[5]);
+        assert_ne!(labels[0], labels[3]);
+    }
+
+    #[test]
+    fn test_haversine_facility_location() {
+        let demand: F64LatLngArray = arr2(&two_towns);
+        let candidates: F64LatLngArray = arr2(&[
+            [50.20,  1.10],
+            [51.50, -0.12],
+            [48.85,  2.35],
+        ]);
+
+        let (facilities, _, _) = CalculationMethod::HAVERSINE._facility_location(&candidates, &demand, 1, None, None);
+        assert_eq!(facilities, vec![0]);
+
+        let weights = arr1(&[10., 10., 10., 1., 1., 1.]);
+        let (facilities, assignment, _) = CalculationMethod::HAVERSINE._facility_location(&candidates, &demand, 1, Some(&weights), None);
+        assert_eq!(facilities, vec![1]);
+        assert_eq!(assignment, vec![0; 6]);
+    }
+
+    #[test]
+    fn test_clara_sample_size() {
+        let n = PAM_MAX_POINTS * 3;
+
+        assert_eq!(clara_sample_size(n, 2), CLARA_MIN_SAMPLE_SIZE);
+        assert_eq!(clara_sample_size(n, 500), 1040);
+        assert_eq!(clara_sample_size(n, 1500), PAM_MAX_POINTS);
+
+        // Samples must hold at least `k` points, even past the usual cap.
+        for k in [PAM_MAX_POINTS - 1, PAM_MAX_POINTS, PAM_MAX_POINTS + 1, n - 1, n] {
+            let sample_size = clara_sample_size(n, k);
+            assert!(sample_size >= k && sample_size <= n, "k={:?}: {:?}", k, sample_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_geohash {
+    use ndarray::{arr1, arr2};
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::compatibility::CalculationMethod;
+    use crate::geohash;
+
+    #[test]
+    fn test_geohash_encode_decode() {
+        let s: F64LatLngArray = arr2(&[
+            [57.64911, 10.40744],
+            [42.605, -5.603],
+        ]);
+
+        assert_eq!(
+            geohash::encode(&s, 11).unwrap(),
+            vec!["u4pruydqqvj".to_string(), "ezs42s000es".to_string()]
+        );
+
+        let (centres, errors) = geohash::decode(&["ezs42"]).unwrap();
+        assert!((centres[[0, 0]] - 42.60498046875).abs() < 1e-12);
+        assert!((centres[[0, 1]] - -5.60302734375).abs() < 1e-12);
+        assert!((errors[[0, 0]] - 0.02197265625).abs() < 1e-12);
+        assert!((errors[[0, 1]] - 0.02197265625).abs() < 1e-12);
+
+        assert!(geohash::decode(&["ezs4a"]).is_err());
+    }
+
+    #[test]
+    fn test_geohash_neighbours() {
+        let neighbours = geohash::neighbours("gcpvj").unwrap();
+
+        assert_eq!(
+            neighbours,
+            ["gcpvm", "gcpvq", "gcpvn", "gcpuy", "gcpuv", "gcpuu", "gcpvh", "gcpvk"]
+                .iter()
+                .map(| hash | Some(hash.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        // No neighbours beyond the north pole.
+        let neighbours = geohash::neighbours("z").unwrap();
+        assert_eq!(neighbours[0], None);
+        assert_eq!(neighbours[2], Some("b".to_string()));
+    }
+
+    #[test]
+    fn test_geohash_cover_circle() {
+        let centre = arr1(&[51.5072, -0.1276]);
+
+        let cells = geohash::cover_circle(&CalculationMethod::HAVERSINE, &centre, 1., 6, None).unwrap();
+        let centre_hash = geohash::encode(&arr2(&[[51.5072, -0.1276]]), 6).unwrap().remove(0);
+
+        assert!(cells.contains(&centre_hash));
+        assert!(cells.iter().all(| hash | hash.starts_with("gcp")));
+
+        // Each cell at precision 6 is roughly 1.2km x 0.6km.
+        assert!(cells.len() > 4 && cells.len() < 30);
+    }
+}
+
+#[cfg(test)]
+mod test_cells {
+    use ndarray::{arr1, arr2, Array2};
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::cells::{self, Cap, CellId, Polygon, MAX_LEVEL};
+    use crate::compatibility::{CalculationInterfaceInternal, CalculationMethod};
+    use crate::spatial::to_unit_vector;
+
+    #[test]
+    fn test_cells_hierarchy() {
+        let leaf = CellId::from_latlng(51.5072, -0.1276, MAX_LEVEL);
+        assert!(leaf.is_valid());
+        assert_eq!(leaf.level(), MAX_LEVEL);
+
+        for level in 0..=MAX_LEVEL {
+            let cell = CellId::from_latlng(51.5072, -0.1276, level);
+
+            assert_eq!(cell.level(), level);
+            assert_eq!(leaf.parent(level), cell);
+            assert!(cell.contains(&leaf));
+
+            let (face, i, j, _) = cell.face_ij();
+            assert_eq!(CellId::from_face_ij(face, i, j, level), cell);
+
+            if level < MAX_LEVEL {
+                let children = cell.children();
+                assert_eq!(children.iter().filter(| child | child.contains(&leaf)).count(), 1);
+                assert!(children.iter().all(| child | child.parent(level) == cell));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cells_boundary() {
+        let cell = CellId::from_latlng(51.5072, -0.1276, 12);
+        let boundary = cell.boundary();
+
+        let lat_range = boundary.column(0).iter().fold((f64::INFINITY, f64::NEG_INFINITY), | (lo, hi), v | (lo.min(*v), hi.max(*v)));
+        let lng_range = boundary.column(1).iter().fold((f64::INFINITY, f64::NEG_INFINITY), | (lo, hi), v | (lo.min(*v), hi.max(*v)));
+
+        assert!(lat_range.0 <= 51.5072 && 51.5072 <= lat_range.1);
+        assert!(lng_range.0 <= -0.1276 && -0.1276 <= lng_range.1);
+
+        // Level 12 cells are roughly 2km across.
+        assert!(lat_range.1 - lat_range.0 < 0.05);
+    }
+
+    #[test]
+    fn test_cells_covering() {
+        let method = CalculationMethod::HAVERSINE;
+        let centre = arr1(&[51.5072, -0.1276]);
+
+        let cap = Cap::from_distance(&method, &centre, 5., None);
+        let covering = cells::covering(&cap, 8, MAX_LEVEL);
+        assert!(covering.len() <= 8);
+
+        // Every point within the circle is covered.
+        let points: F64LatLngArray = arr2(&[
+            [51.5072, -0.1276],
+            [51.5072 + 0.04, -0.1276],
+            [51.5072, -0.1276 - 0.07],
+        ]);
+        for leaf in cells::cell_of(&points, MAX_LEVEL) {
+            assert!(covering.iter().any(| cell | cell.contains(&leaf)));
+        }
+
+        // A triangle around central London.
+        let polygon = Polygon::new(&arr2(&[
+            [51.45, -0.25],
+            [51.45,  0.05],
+            [51.60, -0.10],
+        ]));
+        assert!(polygon.contains_point(&to_unit_vector(51.5072, -0.1276)));
+        assert!(!polygon.contains_point(&to_unit_vector(48.8566, 2.3522)));
+
+        let covering = cells::covering(&polygon, 16, MAX_LEVEL);
+        let leaf = CellId::from_latlng(51.5072, -0.1276, MAX_LEVEL);
+        assert!(covering.len() <= 16);
+        assert!(covering.iter().any(| cell | cell.contains(&leaf)));
+        assert!(!covering.iter().any(| cell | cell.contains(&CellId::from_latlng(48.8566, 2.3522, MAX_LEVEL))));
+    }
+
+    #[test]
+    fn test_cells_indices_within_distance() {
+        let mut grid = Array2::<f64>::zeros((400, 2));
+        for (k, mut row) in grid.rows_mut().into_iter().enumerate() {
+            row[0] = 40. + (k / 20) as f64 * 0.37;
+            row[1] = -5. + (k % 20) as f64 * 0.53;
+        }
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let expected = CalculationInterfaceInternal::<f64>::_within_distance(&method, &grid, &grid, 75., None);
+            let indices = CalculationInterfaceInternal::<f64>::_indices_within_distance(&method, &grid, &grid, 75., None);
+
+            for (row, found) in expected.rows().into_iter().zip(indices.iter()) {
+                let expected: Vec<usize> = row.iter().enumerate().filter(| (_, b) | **b).map(| (idx, _) | idx).collect();
+
+                assert_eq!(found.to_vec(), expected);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_geojson {
+    use ndarray::{arr1, arr2};
+
+    use crate::compatibility::CalculationMethod;
+    use crate::formats::{geojson, FormatError};
+
+    const LAYER_A: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {"name": "London"},
+             "geometry": {"type": "Point", "coordinates": [-0.1276, 51.5072]}},
+            {"type": "Feature", "properties": null,
+             "geometry": {"type": "LineString", "coordinates": [[2.3522, 48.8566], [4.8357, 45.7640]]}}
+        ]
+    }"#;
+
+    const LAYER_B: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {},
+             "geometry": {"type": "Polygon", "coordinates": [[[4.80, 45.70], [4.90, 45.70], [4.90, 45.80], [4.80, 45.70]]]}},
+            {"type": "Feature", "properties": {},
+             "geometry": {"type": "MultiPoint", "coordinates": [[-0.10, 51.50, 12.0], [13.40, 52.52]]}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_geojson_read() {
+        let features = geojson::read(LAYER_A).unwrap();
+
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0].geometry, Some(geojson::Geometry::Point(arr2(&[[51.5072, -0.1276]]))));
+        assert_eq!(features[0].properties["name"], "London");
+        assert_eq!(features[1].geometry.as_ref().unwrap().kind(), "LineString");
+        assert!(features[1].properties.is_empty());
+
+        let (points, feature_indices) = geojson::read_points(&geojson::read(LAYER_B).unwrap());
+        assert_eq!(points.shape(), &[6, 2]);
+        assert_eq!(feature_indices, vec![0, 0, 0, 0, 1, 1]);
+        assert_eq!(points.row(4), arr1(&[51.50, -0.10]));
+
+        // Unclosed ring.
+        assert!(matches!(
+            geojson::read(r#"{"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1]]]}"#),
+            Err(FormatError::InvalidGeometry(_))
+        ));
+        assert!(matches!(
+            geojson::read(r#"{"type": "MultiPolygon", "coordinates": []}"#),
+            Err(FormatError::UnsupportedGeometry(_))
+        ));
+    }
+
+    #[test]
+    fn test_geojson_read_null_geometry() {
+        let text = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "properties": {"name": "Unknown"}, "geometry": null},
+                {"type": "Feature", "properties": {},
+                 "geometry": {"type": "Point", "coordinates": [-0.10, 51.50]}}
+            ]
+        }"#;
+        let features = geojson::read(text).unwrap();
+
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0].geometry, None);
+        assert_eq!(features[0].properties["name"], "Unknown");
+
+        // Unlocated features keep their indices, but have no points.
+        let (points, feature_indices) = geojson::read_points(&features);
+        assert_eq!(points.shape(), &[1, 2]);
+        assert_eq!(feature_indices, vec![1]);
+
+        let pairs = geojson::within_distance(&CalculationMethod::HAVERSINE, &geojson::read(LAYER_A).unwrap(), &features, 10., None);
+        assert_eq!(pairs.iter().map(| (i, j, _) | (*i, *j)).collect::<Vec<_>>(), vec![(0, 1)]);
+
+        assert_eq!(geojson::read(&geojson::write(&features)).unwrap(), features);
+
+        // The `geometry` member is still required.
+        assert!(matches!(
+            geojson::read(r#"{"type": "Feature", "properties": {}}"#),
+            Err(FormatError::InvalidGeometry(_))
+        ));
+    }
+
+    #[test]
+    fn test_geojson_write() {
+        let features = geojson::read(LAYER_A).unwrap();
+        assert_eq!(geojson::read(&geojson::write(&features)).unwrap(), features);
+
+        // Heights are discarded.
+        let points = geojson::points_to_features(&arr2(&[[51.5072, -0.1276, 11.]]), None);
+        assert_eq!(points[0].geometry, Some(geojson::Geometry::Point(arr2(&[[51.5072, -0.1276]]))));
+
+        let method = CalculationMethod::HAVERSINE;
+        let buffer = geojson::buffer(&method, &arr1(&[51.5072, -0.1276]), 10., 16, None);
+
+        match &buffer {
+            geojson::Geometry::Polygon(rings) => {
+                assert_eq!(rings[0].shape(), &[17, 2]);
+                assert_eq!(rings[0].row(0), rings[0].row(16));
+                // First vertex is due north.
+                assert!((rings[0][[0, 0]] - (51.5072 + 10. / 6371. * 180. / std::f64::consts::PI)).abs() < 1e-9);
+            },
+            _ => panic!("Buffer is not a Polygon."),
+        }
+    }
+
+    #[test]
+    fn test_geojson_within_distance() {
+        let a = geojson::read(LAYER_A).unwrap();
+        let b = geojson::read(LAYER_B).unwrap();
+
+        let pairs = geojson::within_distance(&CalculationMethod::HAVERSINE, &a, &b, 10., None);
+
+        assert_eq!(pairs.iter().map(| (i, j, _) | (*i, *j)).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert!(pairs.iter().all(| (_, _, d) | *d <= 10.));
+    }
+}
+
+#[cfg(test)]
+mod test_wkt_wkb {
+    use ndarray::arr2;
+
+    use crate::formats::{wkb, wkt, FormatError, Geometry};
+
+    #[test]
+    fn test_wkt_parse_write() {
+        let (geometry, srid) = wkt::parse("SRID=4326;POINT Z (-0.1276 51.5072 11)").unwrap();
+        assert_eq!(geometry, Geometry::Point(arr2(&[[51.5072, -0.1276]])));
+        assert_eq!(srid, Some(4326));
+
+        let (geometry, _) = wkt::parse("multipoint ((1 2), 3 4)").unwrap();
+        assert_eq!(geometry, Geometry::MultiPoint(arr2(&[[2., 1.], [4., 3.]])));
+
+        let polygon = "POLYGON ((0 0, 10 0, 10 10, 0 0), (1 1, 2 1, 2 2, 1 1))";
+        let (geometry, srid) = wkt::parse(polygon).unwrap();
+        assert_eq!(srid, None);
+        assert_eq!(wkt::write(&geometry, None), polygon);
+        assert_eq!(wkt::write(&geometry, Some(4326)), format!("SRID=4326;{}", polygon));
+
+        assert!(matches!(wkt::parse("LINESTRING (1 2)"), Err(FormatError::InvalidGeometry(_))));
+        assert!(matches!(wkt::parse("POINT (1 2"), Err(FormatError::Parse(_))));
+        assert!(matches!(wkt::parse("CIRCULARSTRING (1 2, 3 4, 5 6)"), Err(FormatError::UnsupportedGeometry(_))));
+    }
+
+    #[test]
+    fn test_wkb_parse_write() {
+        // PostGIS: SELECT ST_AsEWKB('SRID=4326;POINT(1 2)'::geometry);
+        let data = wkb::parse_hex("0101000020E6100000000000000000F03F0000000000000040").unwrap();
+        let (geometry, srid) = wkb::parse(&data).unwrap();
+        assert_eq!(geometry, Geometry::Point(arr2(&[[2., 1.]])));
+        assert_eq!(srid, Some(4326));
+        assert_eq!(wkb::to_hex(&wkb::write(&geometry, srid)), "0101000020E6100000000000000000F03F0000000000000040");
+
+        // Big endian ISO WKB LineString Z.
+        let mut data: Vec<u8> = vec![0];
+        data.extend(1002_u32.to_be_bytes());
+        data.extend(2_u32.to_be_bytes());
+        for v in [1., 2., 3., 4., 5., 6.] { data.extend(f64::to_be_bytes(v)); }
+        let (geometry, srid) = wkb::parse(&data).unwrap();
+        assert_eq!(geometry, Geometry::LineString(arr2(&[[2., 1.], [5., 4.]])));
+        assert_eq!(srid, None);
+
+        for text in [
+            "MULTIPOINT ((1 2), (3 4))",
+            "POLYGON ((0 0, 10 0, 10 10, 0 0))",
+        ] {
+            let (geometry, _) = wkt::parse(text).unwrap();
+            assert_eq!(wkb::parse(&wkb::write(&geometry, None)).unwrap(), (geometry, None));
+        }
+
+        assert!(matches!(wkb::parse(&data[..20]), Err(FormatError::Parse(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_pairwise {
+    use ndarray::{
+        arr2,
+        s,
+    };
+    use ndarray_numeric::F64LatLngArray;
+
+    use crate::compatibility::{
+        CalculationInterfaceInternal,
+        CalculationInterfacePairwise,
+        CalculationMethod,
+        CalculationSettings,
+    };
+
+    #[test]
+    fn test_distance_pairwise() {
+        let s = arr2(&[[51.5072, -0.1276], [40.7128, -74.0060], [-33.8688, 151.2093], [0., 0.]]);
+        let e = arr2(&[[48.8566, 2.3522], [35.6762, 139.6503], [-36.8485, 174.7633], [0., 0.]]);
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let pairwise = method._distance_pairwise(&s, &e, None);
+            let full = CalculationInterfaceInternal::<f64>::_distance(&method, &s, &e, None);
+
+            for i in 0..4 {
+                assert!((pairwise[i] - full[[i, i]]).abs() < 1e-6, "{:?} != {:?}", pairwise[i], full[[i, i]]);
+            }
+        }
+    }
+
+    /// Pairs with reference distances in km and initial bearings on WGS84, and
+    /// the tolerance of each distance.
+    ///
+    /// The references are from Karney's geodesic integrals; those of the
+    /// sub-millimetre pairs from the radii of curvature.
+    fn vincenty_reference() -> (F64LatLngArray, F64LatLngArray, [(f64, f64, f64); 5]) {
+        let s = arr2(&[[0., 0.], [60., -20.], [30., 0.], [0., 0.], [45., 7.]]);
+        let e = arr2(&[[10., 0.], [-50., -20.], [-29.5, -179.], [0., 5e-9], [45.000000005, 7.]]);
+
+        let expected = [
+            // Along a meridian, both ways.
+            (1105.854833234, 0., 1e-6),
+            (12194.919861175, 180., 1e-6),
+
+            // Westwards, nearly antipodal.
+            (19908.947035518, 312.766904302, 1e-6),
+
+            // About 0.56 mm apart. Converging on `tolerance` in longitude leaves
+            // the one along the equator a few micrometres short.
+            (5.565974540e-7, 90., 1e-8),
+            (5.556585382e-7, 0., 1e-11),
+        ];
+
+        return (s, e, expected);
+    }
+
+    #[test]
+    fn test_inverse_pairwise_vincenty_reference() {
+        let (s, e, expected) = vincenty_reference();
+
+        let (distances, bearings) = CalculationMethod::VINCENTY._inverse_pairwise(&s, &e, None);
+
+        for (i, (distance, bearing, tolerance)) in expected.iter().enumerate() {
+            assert!((distances[i] - distance).abs() < *tolerance, "{:?}: {:?} != {:?}", i, distances[i], distance);
+            assert!((bearings[i] - bearing).abs() < 1e-6, "{:?}: {:?} != {:?}", i, bearings[i], bearing);
+        }
+
+        // Line (a) of Vincenty (1975) on the Bessel ellipsoid, mirrored westwards:
+        // 14110.526170 km at 96º36'08.79960" eastwards.
+        let bessel = CalculationSettings {
+            ellipse_a: 6377.397155,
+            ellipse_b: 6377.397155 * (1. - 1./299.1528128),
+            ellipse_f: 1./299.1528128,
+            ..CalculationSettings::default()
+        };
+        let (distances, bearings) = CalculationMethod::VINCENTY._inverse_pairwise(
+            &arr2(&[[55.75, 0.]]), &arr2(&[[-(33. + 26./60.), -(108. + 13./60.)]]), Some(&bessel),
+        );
+
+        assert!((distances[0] - 14110.526170).abs() < 1e-6, "{:?}", distances[0]);
+        assert!((bearings[0] - (360. - (96. + 36./60. + 8.79960/3600.))).abs() < 1e-6, "{:?}", bearings[0]);
+    }
+
+    #[test]
+    fn test_distance_vincenty_reference() {
+        let (s, e, expected) = vincenty_reference();
+
+        let full = CalculationInterfaceInternal::<f64>::_distance(&CalculationMethod::VINCENTY, &s, &e, None);
+
+        for (i, (distance, _, tolerance)) in expected.iter().enumerate() {
+            let from_point = CalculationInterfaceInternal::<f64>::_distance_from_point(
+                &CalculationMethod::VINCENTY, &s.row(i), &e.slice(s![i..i+1, ..]).to_owned(), None,
+            )[0];
+
+            assert!((full[[i, i]] - distance).abs() < *tolerance, "{:?}: {:?} != {:?}", i, full[[i, i]], distance);
+            assert!((from_point - distance).abs() < *tolerance, "{:?}: {:?} != {:?}", i, from_point, distance);
+        }
+    }
+
+    #[test]
+    fn test_bearing_pairwise() {
+        let s = arr2(&[[0., 0.], [0., 0.], [0., 0.], [0., 0.], [10., 10.]]);
+        let e = arr2(&[[1., 0.], [0., 1.], [-1., 0.], [0., -1.], [10., 10.]]);
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let bearings = method._bearing_pairwise(&s, &e, None);
+
+            for (bearing, expected) in bearings.iter().zip([0., 90., 180., 270., 0.]) {
+                assert!((bearing - expected).abs() < 1e-9, "{:?} != {:?}", bearing, expected);
+            }
+        }
+
+        // London to Paris, initial bearing of about 148º.
+        let bearing = CalculationMethod::HAVERSINE._bearing_pairwise(
+            &arr2(&[[51.5072, -0.1276]]), &arr2(&[[48.8566, 2.3522]]), None
+        )[0];
+        assert!((bearing - 148.).abs() < 1., "{:?}", bearing);
+    }
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod test_arrow {
+    use std::sync::Arc;
+
+    use arrow::array::{
+        Array,
+        Float64Array,
+        UInt64Array,
+    };
+    use arrow::datatypes::{
+        DataType,
+        Field,
+        Schema,
+    };
+    use arrow::record_batch::RecordBatch;
+    use ndarray::arr2;
+
+    use crate::compatibility::{
+        CalculationInterfacePairwise,
+        CalculationMethod,
+    };
+    use crate::formats::arrow::{
+        process_parquet,
+        BatchProcessor,
+        LatLngColumns,
+        Operation,
+    };
+    use crate::formats::FormatError;
+
+    fn trips() -> RecordBatch {
+        let schema = Schema::new(
+            ["lat", "lng", "lat2", "lng2"].iter()
+                                          .map(| name | Field::new(name, DataType::Float64, true))
+                                          .collect()
+        );
+
+        return RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(vec![Some(51.5072), Some(40.7128), None])),
+                Arc::new(Float64Array::from(vec![Some(-0.1276), Some(-74.0060), Some(0.)])),
+                Arc::new(Float64Array::from(vec![Some(48.8566), Some(35.6762), Some(0.)])),
+                Arc::new(Float64Array::from(vec![Some(2.3522), Some(139.6503), Some(0.)])),
+            ],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_arrow_distance() {
+        let method = CalculationMethod::VINCENTY;
+        let processor = BatchProcessor {
+            method: &method,
+            columns: LatLngColumns::new("lat", "lng"),
+            operation: Operation::Distance(LatLngColumns::new("lat2", "lng2")),
+            output: "distance".to_string(),
+            settings: None,
+        };
+
+        let batch = processor.process(&trips()).unwrap();
+        assert_eq!(batch.num_columns(), 5);
+
+        let distances = batch.column(4).as_any().downcast_ref::<Float64Array>().unwrap();
+        let expected = method._distance_pairwise(
+            &arr2(&[[51.5072, -0.1276], [40.7128, -74.0060]]),
+            &arr2(&[[48.8566, 2.3522], [35.6762, 139.6503]]),
+            None,
+        );
+
+        assert_eq!(distances.value(0), expected[0]);
+        assert_eq!(distances.value(1), expected[1]);
+        assert!(distances.is_null(2));
+    }
+
+    #[test]
+    fn test_arrow_nearest() {
+        let method = CalculationMethod::HAVERSINE;
+        let processor = BatchProcessor {
+            method: &method,
+            columns: LatLngColumns::new("lat2", "lng2"),
+            operation: Operation::nearest(arr2(&[[35., 139.], [48., 2.], [0., 1.]])),
+            output: "nearest".to_string(),
+            settings: None,
+        };
+
+        let batch = processor.process(&trips()).unwrap();
+        assert_eq!(batch.schema().field(5).name(), "nearest_distance");
+
+        let nearest = batch.column(4).as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!(nearest.values(), &[1, 0, 2]);
+    }
+
+    #[test]
+    fn test_arrow_errors() {
+        let method = CalculationMethod::HAVERSINE;
+        let processor = BatchProcessor {
+            method: &method,
+            columns: LatLngColumns::new("latitude", "lng"),
+            operation: Operation::Bearing(LatLngColumns::new("lat2", "lng2")),
+            output: "bearing".to_string(),
+            settings: None,
+        };
+        assert!(matches!(processor.process(&trips()), Err(FormatError::Schema(_))));
+
+        let processor = BatchProcessor {
+            columns: LatLngColumns::new("lat", "lng"),
+            output: "lat2".to_string(),
+            ..processor
+        };
+        assert!(matches!(processor.process(&trips()), Err(FormatError::Schema(_))));
+    }
+
+    #[test]
+    fn test_arrow_parquet() {
+        let dir = std::env::temp_dir();
+        let (input, output) = (dir.join("test_arrow_input.parquet"), dir.join("test_arrow_output.parquet"));
+
+        let batch = trips();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(
+            std::fs::File::create(&input).unwrap(), batch.schema(), None
+        ).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let method = CalculationMethod::HAVERSINE;
+        let processor = BatchProcessor {
+            method: &method,
+            columns: LatLngColumns::new("lat", "lng"),
+            operation: Operation::Bearing(LatLngColumns::new("lat2", "lng2")),
+            output: "bearing".to_string(),
+            settings: None,
+        };
+
+        assert_eq!(process_parquet(&input, &output, 2, &processor).unwrap(), 3);
+
+        let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            std::fs::File::open(&output).unwrap()
+        ).unwrap().build().unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 3);
+        assert_eq!(batches[0].schema().field(4).name(), "bearing");
+
+        std::fs::remove_file(input).unwrap();
+        std::fs::remove_file(output).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test_gpx_kml {
+    use ndarray::arr2;
+
+    use crate::compatibility::CalculationMethod;
+    use crate::formats::{gpx, kml, FormatError, Geometry};
+    use crate::formats::time::parse_timestamp;
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <wpt lat="51.5072" lon="-0.1276"><ele>11</ele><name>London</name></wpt>
+  <rte><name>Route</name>
+    <rtept lat="51.5" lon="0"/><rtept lat="52.5" lon="0"/>
+  </rte>
+  <trk><name>Morning &amp; Run</name>
+    <trkseg>
+      <trkpt lat="0" lon="0"><ele>10.5</ele><time>2022-11-01T08:00:00Z</time></trkpt>
+      <trkpt lat="0" lon="1"><time>2022-11-01T09:00:00.5+01:00</time></trkpt>
+    </trkseg>
+    <trkseg>
+      <trkpt lat="1" lon="1"/><trkpt lat="2" lon="1"/><trkpt lat="3" lon="1"/>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    const KML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+  <Document><Folder>
+    <Placemark><name>Pin</name><description><![CDATA[<b>Big</b> Ben]]></description>
+      <Point><coordinates>-0.1246,51.5007,96</coordinates></Point>
+    </Placemark>
+    <Placemark><name>Multi</name>
+      <MultiGeometry>
+        <LineString><coordinates>0,0 1,0
+          1,1</coordinates></LineString>
+        <Polygon><outerBoundaryIs><LinearRing>
+          <coordinates>0,0 1,0 1,1</coordinates>
+        </LinearRing></outerBoundaryIs></Polygon>
+      </MultiGeometry>
+    </Placemark>
+    <Placemark><name>Empty</name></Placemark>
+    <Placemark>
+      <gx:Track>
+        <when>2022-11-01T08:00:00Z</when><when>2022-11-01T08:01:00Z</when>
+        <gx:coord>0 0 5</gx:coord><gx:coord>0 0.001 6</gx:coord>
+      </gx:Track>
+    </Placemark>
+  </Folder></Document>
+</kml>"#;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z").unwrap(), 0.);
+        assert_eq!(parse_timestamp("2022-11-01").unwrap(), 1667260800.);
+        assert_eq!(parse_timestamp("2022-11-01 08:00:00+01:00").unwrap(), 1667260800. + 7. * 3600.);
+        assert_eq!(parse_timestamp("2022-11-01T08:00:00.25-0030").unwrap(), 1667260800. + 8.5 * 3600. + 0.25);
+
+        for text in ["2022-13-01", "2022-11-01T25:00:00", "2022-11-01T08:00:00+1", "yesterday"] {
+            assert!(parse_timestamp(text).is_err(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_gpx_read() {
+        let gpx = gpx::read(GPX).unwrap();
+
+        assert_eq!(gpx.waypoints.points, arr2(&[[51.5072, -0.1276]]));
+        assert_eq!(gpx.waypoints.elevations[0], 11.);
+        assert!(gpx.waypoints.times[0].is_nan());
+        assert_eq!(gpx.waypoints.names, vec![Some("London".to_string())]);
+
+        assert_eq!(gpx.routes.len(), 1);
+        assert_eq!(gpx.routes[0].segments[0].len(), 2);
+
+        let track = &gpx.tracks[0];
+        assert_eq!(track.name.as_deref(), Some("Morning & Run"));
+        assert_eq!(track.segments.iter().map(gpx::Points::len).collect::<Vec<_>>(), vec![2, 3]);
+
+        let first = &track.segments[0];
+        assert_eq!(first.elevations[0], 10.5);
+        assert!(first.elevations[1].is_nan());
+        assert_eq!(first.times[1] - first.times[0], 0.5);
+    }
+
+    #[test]
+    fn test_gpx_length() {
+        let gpx = gpx::read(GPX).unwrap();
+        let method = CalculationMethod::HAVERSINE;
+
+        // Each segment of the track is 1º of arc apart, but not the gap between them.
+        let degree = 6371. * std::f64::consts::PI / 180.;
+        let track = &gpx.tracks[0];
+
+        assert!((track.segments[1].segment_distances(&method, None) - degree).iter().all(| d | d.abs() < 1e-9));
+        assert!((track.length(&method, None) - 3. * degree).abs() < 1e-9);
+        assert!((gpx.routes[0].length(&method, None) - degree).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kml_read() {
+        let placemarks = kml::read(KML).unwrap();
+
+        assert_eq!(placemarks.len(), 4);
+
+        assert_eq!(placemarks[0].name.as_deref(), Some("Pin"));
+        assert_eq!(placemarks[0].description.as_deref(), Some("<b>Big</b> Ben"));
+        assert_eq!(placemarks[0].geometry, Geometry::Point(arr2(&[[51.5007, -0.1246]])));
+        assert_eq!(placemarks[0].elevations[0], 96.);
+
+        // MultiGeometry is flattened, and the ring is closed.
+        assert_eq!(placemarks[1].geometry, Geometry::LineString(arr2(&[[0., 0.], [0., 1.], [1., 1.]])));
+        assert_eq!(placemarks[2].name.as_deref(), Some("Multi"));
+        assert_eq!(placemarks[2].geometry, Geometry::Polygon(vec![arr2(&[[0., 0.], [0., 1.], [1., 1.], [0., 0.]])]));
+        assert_eq!(placemarks[2].elevations.len(), 4);
+
+        let track = &placemarks[3];
+        assert_eq!(track.geometry, Geometry::LineString(arr2(&[[0., 0.], [0.001, 0.]])));
+        assert_eq!(track.elevations.to_vec(), vec![5., 6.]);
+        assert_eq!(track.times[1] - track.times[0], 60.);
+    }
+
+    #[test]
+    fn test_gpx_kml_errors() {
+        assert!(matches!(gpx::read("<kml/>"), Err(FormatError::Parse(_))));
+        assert!(matches!(gpx::read("<gpx><wpt lon=\"0\"/></gpx>"), Err(FormatError::InvalidGeometry(_))));
+        assert!(matches!(gpx::read("<gpx><wpt lat=\"0\" lon=\"0\">"), Err(FormatError::Parse(_))));
+        assert!(matches!(
+            kml::read("<kml><Placemark><LineString><coordinates>0,0</coordinates></LineString></Placemark></kml>"),
+            Err(FormatError::InvalidGeometry(_))
+        ));
+        assert!(matches!(
+            kml::read("<kml><Placemark><Point><coordinates>0;0</coordinates></Point></Placemark></kml>"),
+            Err(FormatError::Parse(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_csv {
+    use std::fs;
+
+    use crate::compatibility::{CalculationInterfaceInternal, CalculationMethod};
+    use crate::formats::csv::{self, Chunks, CsvColumns};
+    use crate::formats::FormatError;
+
+    /// Write a grid of points as CSV, returning its path.
+    fn write_grid(name:&str, header:&str, offset:f64) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+
+        let mut text = format!("{}\n", header);
+        for k in 0..100 {
+            text += &format!("p{},{},{}\n", k, (k / 10) as f64 * 0.1 + offset, (k % 10) as f64 * 0.1);
+        }
+        fs::write(&path, text).unwrap();
+
+        return path;
+    }
+
+    #[test]
+    fn test_csv_chunks() {
+        let path = write_grid("test_csv_chunks.csv", "id,lat,lng", 0.);
+
+        let chunks: Vec<_> = Chunks::open(&path, &CsvColumns::default(), 30).unwrap()
+                                                                            .map(Result::unwrap)
+                                                                            .collect();
+        assert_eq!(chunks.iter().map(csv::Chunk::len).collect::<Vec<_>>(), vec![30, 30, 30, 10]);
+        assert_eq!(chunks[1].ids[0], "p30");
+        assert_eq!(chunks[1].points.row(0).to_vec(), vec![0.30000000000000004, 0.]);
+
+        assert!(matches!(
+            Chunks::open(&path, &CsvColumns::new("id", "latitude", "lng"), 30),
+            Err(FormatError::Schema(_))
+        ));
+
+        fs::write(&path, "id,lat,lng\na,1,2\nb,north,2\n").unwrap();
+        let result: Result<Vec<_>, _> = Chunks::open(&path, &CsvColumns::default(), 30).unwrap().collect();
+        assert!(matches!(result, Err(FormatError::Parse(_))));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_csv_within_distance_join() {
+        let s_path = write_grid("test_csv_join_s.csv", "id,lat,lng", 0.);
+        let e_path = write_grid("test_csv_join_e.csv", "name,y,x", 0.05);
+        let output = std::env::temp_dir().join("test_csv_join_output.csv");
+
+        let method = CalculationMethod::HAVERSINE;
+        let e_columns = CsvColumns::new("name", "y", "x");
+
+        let expected = {
+            let s = Chunks::open(&s_path, &CsvColumns::default(), 1000).unwrap().next().unwrap().unwrap();
+            let e = Chunks::open(&e_path, &e_columns, 1000).unwrap().next().unwrap().unwrap();
+            CalculationInterfaceInternal::<f64>::_within_distance(&method, &s.points, &e.points, 8., None)
+                .iter()
+                .filter(| within | **within)
+                .count()
+        };
+        assert!(expected > 0);
+
+        // Chunks that do not divide the files evenly.
+        for chunk_size in [7, 1000] {
+            let count = csv::within_distance_join(
+                &method, &s_path, &e_path, &output,
+                &CsvColumns::default(), &e_columns,
+                8., chunk_size, None,
+            ).unwrap();
+            assert_eq!(count, expected);
+
+            let text = fs::read_to_string(&output).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines[0], "id_s,id_e,distance");
+            assert_eq!(lines.len(), expected + 1);
+            assert!(lines[1..].iter().all(| line | line.split(',').nth(2).unwrap().parse::<f64>().unwrap() <= 8.));
+        }
+
+        for path in [s_path, e_path, output] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_coordinates {
+    use ndarray::arr2;
+
+    use crate::coordinates::{
+        format_coordinates,
+        format_latlng,
+        parse_angle,
+        parse_coordinates,
+        parse_latlng,
+        parse_pair,
+        CoordinateError,
+        Hemisphere,
+        Style,
+    };
+
+    fn assert_close(result:[f64; 2], expected:[f64; 2]) {
+        assert!(
+            (result[0] - expected[0]).abs() < 1e-9 && (result[1] - expected[1]).abs() < 1e-9,
+            "{:?} != {:?}", result, expected
+        );
+    }
+
+    #[test]
+    fn test_parse_angle() {
+        assert_eq!(parse_angle("-0.1276").unwrap(), (-0.1276, None));
+        assert_eq!(parse_angle("51 30.433 N").unwrap().1, Some(Hemisphere::N));
+        assert!((parse_angle("51 30.433 N").unwrap().0 - (51. + 30.433 / 60.)).abs() < 1e-12);
+        assert!((parse_angle("W 0°7′39″").unwrap().0 + 0.1275).abs() < 1e-12);
+        assert!((parse_angle("0°7'39''W").unwrap().0 + 0.1275).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_latlng() {
+        let expected = [51. + 30. / 60. + 26. / 3600., -(7. / 60. + 39. / 3600.)];
+
+        for text in [
+            "51°30'26\"N 0°7'39\"W",
+            "51°30'26\"N, 0°7'39\"W",
+            "0°7'39\"W 51°30'26\"N",
+            "N51°30'26\" W0°7'39\"",
+            "51°30'26\" -0°7'39\"",
+            "51 30 26 -0 7 39",
+        ] {
+            assert_close(parse_latlng(text).unwrap(), expected);
+        }
+
+        assert_close(parse_latlng("N51.5072 W0.1276").unwrap(), [51.5072, -0.1276]);
+        assert_close(parse_latlng("51.5072, -0.1276").unwrap(), [51.5072, -0.1276]);
+        assert_close(parse_latlng("  51.5072 -0.1276 ").unwrap(), [51.5072, -0.1276]);
+        assert_close(parse_latlng("51 30.433 N; 0 7.656 W").unwrap(), [51. + 30.433 / 60., -7.656 / 60.]);
+        assert_close(parse_pair("0.1276 W", "51.5072 N").unwrap(), [51.5072, -0.1276]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse_latlng("51.5072 x 0.1276"), Err(CoordinateError::UnexpectedCharacter('x'))));
+        assert!(matches!(parse_latlng("91, 0"), Err(CoordinateError::OutOfRange(_))));
+        assert!(matches!(parse_latlng("51°61'0\"N 0W"), Err(CoordinateError::OutOfRange(_))));
+        assert!(matches!(parse_latlng("51N 0N"), Err(CoordinateError::Malformed(_))));
+        assert!(matches!(parse_latlng("-51N 0W"), Err(CoordinateError::Malformed(_))));
+        assert!(matches!(parse_latlng("51.5 30 0 7"), Err(CoordinateError::Malformed(_))));
+        assert!(matches!(parse_latlng("51 0 7"), Err(CoordinateError::Malformed(_))));
+        assert!(matches!(parse_latlng("51'30° 0"), Err(CoordinateError::Malformed(_))));
+
+        let results = parse_coordinates(&["51.5, 0", "nowhere", "0, 181"]);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(CoordinateError::UnexpectedCharacter('o'))));
+        assert!(matches!(results[2], Err(CoordinateError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_format_coordinates() {
+        assert_eq!(format_latlng(51.5072, -0.1276, Style::DMS, None), "51°30'25.9\"N 0°07'39.4\"W");
+        assert_eq!(format_latlng(51.5072, -0.1276, Style::DDM, None), "51°30.432'N 0°07.656'W");
+        assert_eq!(format_latlng(51.5072, -0.1276, Style::Decimal, Some(4)), "51.5072, -0.1276");
+
+        // Rounding carries over to minutes and degrees.
+        assert_eq!(format_latlng(-33.99999, 151., Style::DMS, Some(0)), "34°00'00\"S 151°00'00\"E");
+
+        let s = arr2(&[[51.5072, -0.1276], [-33.8688, 151.2093], [0., 0.]]);
+        for style in [Style::DMS, Style::DDM, Style::Decimal] {
+            for (text, row) in format_coordinates(&s, style, Some(10)).iter().zip(s.rows()) {
+                assert_close(parse_latlng(text).unwrap(), [row[0], row[1]]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_transforms {
+    use ndarray::{
+        arr1,
+        arr2,
+    };
+
+    use ndarray_numeric::F64Array2;
+
+    use crate::calc_models::config::{
+        ELLIPSE_WGS84_A,
+        ELLIPSE_WGS84_B,
+    };
+    use crate::compatibility::{
+        CalculationInterfaceInternal,
+        CalculationMethod,
+    };
+    use crate::transforms::{
+        chord_distance,
+        ecef_to_geodetic,
+        enu_to_geodetic,
+        geodetic_to_ecef,
+        geodetic_to_enu,
+        slant_distance,
+    };
+
+    fn assert_close(result:&F64Array2, expected:&F64Array2) {
+        assert_eq!(result.shape(), expected.shape());
+        assert!(
+            result.iter().zip(expected.iter()).all(| (r, e) | (r - e).abs() < 1e-9),
+            "{:?} != {:?}", result, expected
+        );
+    }
+
+    #[test]
+    fn test_geodetic_to_ecef() {
+        let xyz = geodetic_to_ecef(&arr2(&[[0., 0.], [0., 90.], [90., 0.], [-90., 0., 1.]]), None);
+
+        let expected = arr2(&[
+            [ELLIPSE_WGS84_A, 0., 0.],
+            [0., ELLIPSE_WGS84_A, 0.],
+            [0., 0., ELLIPSE_WGS84_B],
+            [0., 0., -ELLIPSE_WGS84_B - 1.],
+        ]);
+        assert_close(&xyz, &expected);
+    }
+
+    #[test]
+    fn test_ecef_round_trip() {
+        let s = arr2(&[
+            [51.5072, -0.1276, 0.035],
+            [-33.8688, 151.2093, 0.],
+            [89.9999, 45., 10.],
+            [-12.5, -170.25, -0.4],
+        ]);
+
+        let result = ecef_to_geodetic(&geodetic_to_ecef(&s, None), None);
+        assert_close(&result, &s);
+    }
+
+    #[test]
+    fn test_enu() {
+        let origin = arr1(&[51.5072, -0.1276, 0.1]);
+        let s = arr2(&[[51.5072, -0.1276, 0.1], [51.6, -0.1276, 0.1], [51.5072, 0., 0.5]]);
+
+        let enu = geodetic_to_enu(&s, &origin, None);
+
+        // The origin itself, then due north, then east and up.
+        assert!(enu.row(0).iter().all(| v | v.abs() < 1e-9));
+        assert!(enu[[1, 0]].abs() < 1e-9 && enu[[1, 1]] > 10.);
+        assert!(enu[[2, 0]] > 8. && enu[[2, 1]].abs() < 0.1);
+
+        let result = enu_to_geodetic(&enu, &origin, None);
+        assert_close(&result, &s);
+    }
+
+    #[test]
+    fn test_chord_and_slant_distance() {
+        let s = arr2(&[[51.5072, -0.1276], [40.7128, -74.0060]]);
+        let e = arr2(&[[48.8566, 2.3522], [-33.8688, 151.2093], [51.5072, -0.1276]]);
+
+        let surface = CalculationInterfaceInternal::<f64>::_distance(&CalculationMethod::VINCENTY, &s, &e, None);
+        let chord = chord_distance(&s, &e, None);
+
+        assert_eq!(chord.shape(), &[2, 3]);
+        assert!(chord[[0, 2]].abs() < 1e-9);
+        assert!(chord.iter().zip(surface.iter()).all(| (c, d) | *c <= *d + 1e-9));
+
+        // Heights are ignored by chords, but not by slant distances.
+        let s_high = arr2(&[[51.5072, -0.1276, 10.]]);
+        let e_ground = arr2(&[[51.5072, -0.1276, 0.]]);
+
+        assert!(chord_distance(&s_high, &e_ground, None)[[0, 0]].abs() < 1e-9);
+        assert!((slant_distance(&s_high, &e_ground, None)[[0, 0]] - 10.).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod test_altitude {
+    use ndarray::{
+        arr1,
+        arr2,
+    };
+
+    use crate::compatibility::{
+        AltitudeMode,
+        CalculationInterfaceAltitude,
+        CalculationInterfaceInternal,
+        CalculationMethod,
+    };
+
+    #[test]
+    fn test_altitude_mode() {
+        assert_eq!("slant".parse::<AltitudeMode>().unwrap(), AltitudeMode::Slant);
+        assert_eq!("SURFACE_CLIMB".parse::<AltitudeMode>().unwrap(), AltitudeMode::SurfaceClimb);
+        assert!("climb".parse::<AltitudeMode>().is_err());
+
+        assert_eq!(AltitudeMode::Surface.combine(3., 4.), 3.);
+        assert_eq!(AltitudeMode::Slant.combine(3., -4.), 5.);
+        assert_eq!(AltitudeMode::SurfaceClimb.combine(3., -4.), 7.);
+    }
+
+    #[test]
+    fn test_distance_with_altitude() {
+        let method = CalculationMethod::HAVERSINE;
+
+        let s = arr2(&[[51.5072, -0.1276, 0.1], [48.8566, 2.3522, 0.5]]);
+        let e = arr2(&[[51.5072, -0.1276, 1.1], [40.7128, -74.0060, 0.]]);
+        let surface = CalculationInterfaceInternal::<f64>::_distance(&method, &s, &e, None);
+
+        // Heights do not change the surface distance.
+        let flat = arr2(&[[51.5072, -0.1276], [48.8566, 2.3522]]);
+        assert_eq!(CalculationInterfaceInternal::<f64>::_distance(&method, &flat, &e, None), surface);
+        assert_eq!(method._distance_with_altitude(&s, Some(&e), AltitudeMode::Surface, None), surface);
+
+        let slant = method._distance_with_altitude(&s, Some(&e), AltitudeMode::Slant, None);
+        let climb = method._distance_with_altitude(&s, Some(&e), AltitudeMode::SurfaceClimb, None);
+
+        assert!((slant[[0, 0]] - 1.).abs() < 1e-9);
+        assert!((climb[[0, 0]] - 1.).abs() < 1e-9);
+        assert!((climb[[1, 1]] - (surface[[1, 1]] + 0.5)).abs() < 1e-9);
+        assert!((slant[[1, 0]] - surface[[1, 0]].hypot(0.6)).abs() < 1e-9);
+
+        // Pairing an array with itself mirrors the same results.
+        let within = method._distance_with_altitude(&s, None, AltitudeMode::SurfaceClimb, None);
+        let expected = method._distance_with_altitude(&s, Some(&s), AltitudeMode::SurfaceClimb, None);
+        assert!(within.iter().zip(expected.iter()).all(| (a, b) | (a - b).abs() < 1e-9));
+
+        let from_point = method._distance_from_point_with_altitude(
+            &arr1(&[51.5072, -0.1276, 0.1]), &e, AltitudeMode::SurfaceClimb, None
+        );
+        assert!((from_point[0] - 1.).abs() < 1e-9);
+
+        let pairwise = method._distance_pairwise_with_altitude(&s, &e, AltitudeMode::Slant, None);
+        assert!((pairwise[0] - 1.).abs() < 1e-9);
+        assert!((pairwise[1] - slant[[1, 1]]).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod test_projections {
+    use ndarray::arr2;
+
+    use crate::transforms::{
+        from_utm,
+        from_web_mercator,
+        to_utm,
+        to_web_mercator,
+        TransformError,
+        Zone,
+    };
+    use crate::transforms::mercator::MAX_LATITUDE;
+
+    #[test]
+    fn test_utm_zone() {
+        assert_eq!(Zone::from_latlng(51.5072, -0.1276).unwrap(), Zone { number: 30, north: true });
+        assert_eq!(Zone::from_latlng(-33.8688, 151.2093).unwrap(), Zone { number: 56, north: false });
+        assert_eq!(Zone::from_latlng(0., 180.).unwrap().number, 1);
+
+        // Norway and Svalbard.
+        assert_eq!(Zone::from_latlng(60.39, 5.32).unwrap().number, 32);
+        assert_eq!(Zone::from_latlng(78.22, 15.65).unwrap().number, 33);
+
+        assert!(matches!(Zone::from_latlng(85., 0.), Err(TransformError::OutOfRange(_))));
+        assert_eq!(Zone::new(61, true), Err(TransformError::InvalidZone(61)));
+    }
+
+    #[test]
+    fn test_utm() {
+        let s = arr2(&[[51.5072, -0.1276], [-33.8688, 151.2093], [0., 3.]]);
+
+        let (xy, zones) = to_utm(&s, None, None).unwrap();
+
+        // On the central meridian at the equator.
+        assert!((xy[[2, 0]] - 500.).abs() < 1e-9 && xy[[2, 1]].abs() < 1e-9);
+        assert!((xy[[0, 0]] - 699.331).abs() < 1e-3 && (xy[[0, 1]] - 5710.142).abs() < 1e-3);
+        assert!((xy[[1, 1]] - 6250.948).abs() < 1e-3);
+
+        let result = from_utm(&xy, &zones, None).unwrap();
+        assert!(result.iter().zip(s.iter()).all(| (r, e) | (r - e).abs() < 1e-9), "{:?}", result);
+
+        // All points into a single zone.
+        let zone = Zone::new(31, true).unwrap();
+        let (xy, zones) = to_utm(&s.slice(ndarray::s![..1, ..]).to_owned(), Some(zone), None).unwrap();
+        assert_eq!(zones, vec![zone]);
+        assert!(from_utm(&xy, &[zone], None).unwrap().iter().zip(s.iter()).all(| (r, e) | (r - e).abs() < 1e-9));
+
+        assert!(matches!(from_utm(&xy, &[zone, zone], None), Err(TransformError::Shape(_))));
+    }
+
+    #[test]
+    fn test_web_mercator() {
+        let s = arr2(&[[0., 0.], [51.5072, -0.1276], [-60., 179.]]);
+
+        let xy = to_web_mercator(&s, None);
+        assert!(xy[[0, 0]].abs() < 1e-12 && xy[[0, 1]].abs() < 1e-12);
+
+        let result = from_web_mercator(&xy, None);
+        assert!(result.iter().zip(s.iter()).all(| (r, e) | (r - e).abs() < 1e-9), "{:?}", result);
+
+        // Clamped to a square.
+        let xy = to_web_mercator(&arr2(&[[90., 180.]]), None);
+        assert!((xy[[0, 0]] - xy[[0, 1]]).abs() < 1e-6);
+        assert!((from_web_mercator(&xy, None)[[0, 0]] - MAX_LATITUDE).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod test_tiles {
+    use ndarray::{
+        arr1,
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::{
+        CalculationInterfaceInternal,
+        CalculationMethod,
+    };
+    use crate::tiles::{
+        tile_bounds,
+        tiles_within_distance,
+        to_tiles,
+        Tile,
+        TileError,
+    };
+
+    #[test]
+    fn test_to_tiles() {
+        let s = arr2(&[[51.5072, -0.1276], [0., 0.], [89.9, 180.]]);
+
+        let (tiles, pixels) = to_tiles(&s, 10).unwrap();
+
+        assert_eq!(tiles, arr2(&[[511, 340], [512, 512], [0, 0]]));
+        assert!((pixels[[0, 0]] - 0.6370488888889 * 256.).abs() < 1e-6);
+        assert!(pixels[[1, 0]].abs() < 1e-9 && pixels[[1, 1]].abs() < 1e-9);
+
+        assert_eq!(to_tiles(&s, 31), Err(TileError::InvalidZoom(31)));
+    }
+
+    #[test]
+    fn test_tile_bounds() {
+        let bounds = tile_bounds(&Array2::from_shape_vec((2, 2), vec![0, 0, 1, 1]).unwrap(), 1).unwrap();
+
+        assert!((bounds[[0, 0]] - 0.).abs() < 1e-9);
+        assert!((bounds[[0, 1]] + 180.).abs() < 1e-9);
+        assert!((bounds[[0, 2]] - 85.0511287798).abs() < 1e-9);
+        assert!((bounds[[1, 3]] - 180.).abs() < 1e-9);
+
+        assert_eq!(Tile::new(2, 0, 1), Err(TileError::InvalidTile(2, 0, 1)));
+
+        // Every point lies within the bounds of its tile.
+        let s = arr2(&[[51.5072, -0.1276], [-33.8688, 151.2093]]);
+        let (tiles, _) = to_tiles(&s, 14).unwrap();
+        let bounds = tile_bounds(&tiles, 14).unwrap();
+        for i in 0..2 {
+            assert!(bounds[[i, 0]] <= s[[i, 0]] && s[[i, 0]] <= bounds[[i, 2]]);
+            assert!(bounds[[i, 1]] <= s[[i, 1]] && s[[i, 1]] <= bounds[[i, 3]]);
+        }
+    }
+
+    #[test]
+    fn test_tiles_within_distance() {
+        let method = CalculationMethod::HAVERSINE;
+        let centre = arr1(&[51.5072, -0.1276]);
+
+        let tiles = tiles_within_distance(&method, &centre, 5., 12, None).unwrap();
+        let (centre_tile, _) = Tile::from_latlng(51.5072, -0.1276, 12).unwrap();
+        assert!(tiles.contains(&centre_tile));
+        assert!(tiles.len() > 1);
+
+        // All points within the radius on a fine grid lie in one of the tiles.
+        let grid = arr2(
+            &(0..41).flat_map(
+                | i | (0..41).map(move | j | [51.45 + i as f64 * 0.0025, -0.2276 + j as f64 * 0.005])
+            )
+            .collect::<Vec<_>>()
+        );
+        let distances = CalculationInterfaceInternal::<f64>::_distance_from_point(&method, &centre, &grid, None);
+        let (grid_tiles, _) = to_tiles(&grid, 12).unwrap();
+
+        for (i, d) in distances.iter().enumerate() {
+            if *d <= 5. {
+                let tile = Tile::new(grid_tiles[[i, 0]], grid_tiles[[i, 1]], 12).unwrap();
+                assert!(tiles.contains(&tile), "{:?} is missing.", tile);
+            }
+        }
+
+        // Every tile at zoom 0.
+        assert_eq!(tiles_within_distance(&method, &centre, 1., 0, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_tiles_within_distance_polar() {
+        let method = CalculationMethod::HAVERSINE;
+
+        // Beyond the Web Mercator square, only the clamped centre tile is listed
+        // instead of every column of the top row.
+        let centre = arr1(&[89.99, 10.]);
+        let (centre_tile, _) = Tile::from_latlng(89.99, 10., 30).unwrap();
+        assert_eq!(tiles_within_distance(&method, &centre, 1., 30, None).unwrap(), vec![centre_tile]);
+
+        // A circle around the pole reaching past the opposite side of the square
+        // covers whole rows.
+        let centre = arr1(&[89., 10.]);
+        let tiles = tiles_within_distance(&method, &centre, 700., 3, None).unwrap();
+        assert_eq!(tiles.iter().filter(| tile | tile.y == 0).count(), 8);
+        assert!(tiles.iter().all(| tile | tile.y == 0));
+    }
+}
+
+#[cfg(test)]
+mod test_trajectory {
+    use ndarray::{
+        arr1,
+        arr2,
+    };
+
+    use crate::compatibility::{
+        CalculationInterfacePairwise,
+        CalculationMethod,
+    };
+    use crate::trajectory::{
+        accelerations,
+        cumulative_lengths,
+        speeds,
+        Trajectory,
+        TrajectoryError,
+    };
+
+    #[test]
+    fn test_cumulative_lengths() {
+        assert_eq!(cumulative_lengths(&arr1(&[1., 2., 3.])), arr1(&[0., 1., 3., 6.]));
+        assert_eq!(cumulative_lengths(&arr1(&[])), arr1(&[0.]));
+    }
+
+    #[test]
+    fn test_speeds_and_accelerations() {
+        let times = arr1(&[0., 10., 20., 20., 40.]);
+        let v = speeds(&arr1(&[10., 30., 5., 20.]), &times).unwrap();
+
+        assert_eq!(v.slice(ndarray::s![..2]), arr1(&[1., 3.]));
+        assert!(v[2].is_nan());
+        assert_eq!(v[3], 1.);
+
+        // Middles of the first two segments are 10 apart.
+        let a = accelerations(&arr1(&[1., 3., 2.]), &arr1(&[0., 10., 20., 30.])).unwrap();
+        assert_eq!(a, arr1(&[0.2, -0.1]));
+
+        assert!(matches!(speeds(&arr1(&[1.]), &times), Err(TrajectoryError::Shape(_))));
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let method = CalculationMethod::VINCENTY;
+        let s = arr2(&[[51.5072, -0.1276], [51.5072, -0.1276], [51.6, -0.1276], [51.6, 0.]]);
+        let times = arr1(&[0., 60., 120., 180.]);
+
+        let trajectory = Trajectory::new(&method, &s, Some(&times), None).unwrap();
+
+        let expected = method._distance_pairwise(
+            &s.slice(ndarray::s![..-1, ..]).to_owned(),
+            &s.slice(ndarray::s![1.., ..]).to_owned(),
+            None,
+        );
+        assert_eq!(trajectory.segment_lengths, expected);
+        assert_eq!(trajectory.cumulative_lengths.len(), 4);
+        assert!((trajectory.cumulative_lengths[3] - expected.sum()).abs() < 1e-9);
+
+        // Stationary, due north, then roughly due east.
+        assert_eq!(trajectory.headings[0], 0.);
+        assert!(trajectory.headings[1].abs() < 1e-9);
+        assert!((trajectory.headings[2] - 90.).abs() < 0.1);
+
+        let speeds = trajectory.speeds.unwrap();
+        assert_eq!(speeds[0], 0.);
+        assert!((speeds[1] - expected[1] / 60.).abs() < 1e-12);
+        assert_eq!(trajectory.accelerations.unwrap().len(), 2);
+
+        let untimed = Trajectory::new(&method, &s, None, None).unwrap();
+        assert!(untimed.speeds.is_none() && untimed.accelerations.is_none());
+
+        assert!(Trajectory::new(&method, &s, Some(&arr1(&[0.])), None).is_err());
+
+        let empty = Trajectory::new(&method, &arr2(&[[0.; 2]; 0]), Some(&arr1(&[])), None).unwrap();
+        assert_eq!(empty.cumulative_lengths.len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod test_stops {
+    use ndarray::{
+        arr1,
+        Array1,
+        Array2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::trajectory::detect_stops;
+
+    #[test]
+    fn test_detect_stops() {
+        let method = CalculationMethod::HAVERSINE;
+
+        // Moving north at ~1.1 km per minute, dwelling around two places on the way,
+        // the second only briefly.
+        let mut rows: Vec<[f64; 2]> = (0..5).map(| i | [51. + i as f64 * 0.01, 0.]).collect();
+        rows.extend((0..40).map(| i | [51.05 + (i % 3) as f64 * 0.0002, (i % 2) as f64 * 0.0002]));
+        rows.extend((0..5).map(| i | [51.06 + i as f64 * 0.01, 0.]));
+        rows.extend((0..3).map(| _ | [51.2, 0.]));
+        rows.extend((0..5).map(| i | [51.21 + i as f64 * 0.01, 0.]));
+
+        let n = rows.len();
+        let s = Array2::from_shape_vec((n, 2), rows.into_iter().flatten().collect()).unwrap();
+        let times = Array1::range(0., n as f64 * 60., 60.);
+
+        let stops = detect_stops(&method, &s, &times, 0.1, 600., None).unwrap();
+
+        assert_eq!(stops.len(), 1);
+        assert_eq!((stops[0].start, stops[0].end), (5, 44));
+        assert_eq!(stops[0].duration, 39. * 60.);
+        assert!((stops[0].centroid.0 - 51.0502).abs() < 1e-3 && stops[0].centroid.1.abs() < 1e-3);
+
+        // Lowering the minimum duration also finds the brief stop.
+        let stops = detect_stops(&method, &s, &times, 0.1, 60., None).unwrap();
+        assert_eq!(stops.len(), 2);
+        assert_eq!((stops[1].start, stops[1].end), (50, 52));
+
+        assert!(detect_stops(&method, &s, &arr1(&[0.]), 0.1, 60., None).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_simplify {
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::trajectory::{
+        distance_to_segment,
+        simplify,
+        Algorithm,
+    };
+
+    #[test]
+    fn test_distance_to_segment() {
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            // 1 arc-minute north of the middle of a segment along the equator.
+            let (d, along) = distance_to_segment(&method, (0., 0.), (0., 2.), (1. / 60., 1.), None);
+            assert!((d - 1.8427).abs() < 0.02, "{:?}", d);
+            assert!((along - 111.2).abs() < 0.5, "{:?}", along);
+
+            // Beyond either end.
+            let (d, along) = distance_to_segment(&method, (0., 0.), (0., 2.), (0., -1.), None);
+            assert!((d - 111.2).abs() < 0.5 && along == 0.);
+            let (_, along) = distance_to_segment(&method, (0., 0.), (0., 2.), (0., 3.), None);
+            assert!((along - 222.4).abs() < 1.);
+        }
+    }
+
+    #[test]
+    fn test_simplify() {
+        let method = CalculationMethod::HAVERSINE;
+
+        // A zigzag of ~10 m amplitude along a meridian at high latitude, with one
+        // large detour in the middle.
+        let n = 101;
+        let s = Array2::from_shape_fn(
+            (n, 2),
+            | (i, j) | match j {
+                0 => 70. + i as f64 * 0.001,
+                _ => if i == 50 { 0.05 } else { (i % 2) as f64 * 0.00026 },
+            },
+        );
+
+        for algorithm in [Algorithm::DouglasPeucker, Algorithm::Visvalingam] {
+            let (points, indices) = simplify(&method, &s, 0.05, algorithm, None);
+
+            assert_eq!(indices, vec![0, 49, 50, 51, 100], "{:?}", algorithm);
+            assert_eq!(points.shape(), &[5, 2]);
+            assert_eq!(points.row(2), s.row(50));
+
+            // Nothing is removed below the amplitude.
+            let (_, indices) = simplify(&method, &s, 0.001, algorithm, None);
+            assert_eq!(indices.len(), n);
+        }
+
+        // The tolerance is a distance for both: a spike of about 1 km on a base of
+        // 10 m is kept, and a bump of about 1 m on a base of 111 km is removed.
+        for algorithm in [Algorithm::DouglasPeucker, Algorithm::Visvalingam] {
+            let spike = arr2(&[[0., 0.], [0.009, 0.], [0., 0.00009]]);
+            let (_, indices) = simplify(&method, &spike, 0.1, algorithm, None);
+            assert_eq!(indices, vec![0, 1, 2], "{:?}", algorithm);
+
+            let bump = arr2(&[[0., 0.], [0.00001, 0.5], [0., 1.]]);
+            let (_, indices) = simplify(&method, &bump, 0.1, algorithm, None);
+            assert_eq!(indices, vec![0, 2], "{:?}", algorithm);
+        }
+
+        let (_, indices) = simplify(&method, &arr2(&[[0., 0.], [1., 1.]]), 1., Algorithm::DouglasPeucker, None);
+        assert_eq!(indices, vec![0, 1]);
+
+        assert_eq!("vw".parse::<Algorithm>().unwrap(), Algorithm::Visvalingam);
+        assert!("bezier".parse::<Algorithm>().is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_resample {
+    use ndarray::{
+        arr1,
+        arr2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::trajectory::{
+        resample_by_distance,
+        resample_by_time,
+        segment_lengths,
+        TrajectoryError,
+    };
+
+    #[test]
+    fn test_resample_by_distance() {
+        let s = arr2(&[[0., 0.], [0., 1.], [0., 2.]]);
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let (points, positions) = resample_by_distance(&method, &s, 10., None).unwrap();
+            let total = segment_lengths(&method, &s, None).sum();
+
+            assert_eq!(points.shape(), &[(total / 10.).floor() as usize + 1, 2]);
+            assert_eq!(points.row(0), s.row(0));
+            assert!(points.column(0).iter().all(| lat | lat.abs() < 1e-9));
+            assert!(segment_lengths(&method, &points, None).iter().all(| d | (d - 10.).abs() < 1e-6));
+
+            // Positions follow the points along each segment.
+            assert!(positions.iter().zip(points.column(1).iter()).all(| (p, lng) | (p - lng).abs() < 1e-6));
+        }
+
+        let single = arr2(&[[51.5, -0.1]]);
+        let (points, positions) = resample_by_distance(&CalculationMethod::HAVERSINE, &single, 10., None).unwrap();
+        assert_eq!(points, single);
+        assert_eq!(positions, arr1(&[0.]));
+
+        assert!(matches!(
+            resample_by_distance(&CalculationMethod::HAVERSINE, &s, 0., None),
+            Err(TrajectoryError::Parameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_resample_by_time() {
+        let method = CalculationMethod::HAVERSINE;
+        let s = arr2(&[[0., 0.], [0., 1.], [0., 2.]]);
+
+        let (points, times) = resample_by_time(&method, &s, &arr1(&[0., 100., 300.]), 50., None).unwrap();
+
+        assert_eq!(times, arr1(&[0., 50., 100., 150., 200., 250., 300.]));
+        for (lng, expected) in points.column(1).iter().zip([0., 0.5, 1., 1.25, 1.5, 1.75, 2.]) {
+            assert!((lng - expected).abs() < 1e-9, "{:?} != {:?}", lng, expected);
+        }
+
+        assert!(matches!(
+            resample_by_time(&method, &s, &arr1(&[0., 200., 100.]), 50., None),
+            Err(TrajectoryError::Times(_))
+        ));
+        assert!(matches!(
+            resample_by_time(&method, &s, &arr1(&[0., 100.]), 50., None),
+            Err(TrajectoryError::Shape(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_similarity {
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::trajectory::{
+        dtw,
+        frechet,
+        hausdorff,
+        segment_lengths,
+        track_distance,
+        track_distances,
+        Metric,
+        TrajectoryError,
+    };
+
+    fn assert_close(a:f64, b:f64) {
+        assert!((a - b).abs() < 1e-6, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let method = CalculationMethod::HAVERSINE;
+        let a = arr2(&[[0., 0.], [0., 1.], [0., 2.]]);
+        let reversed = arr2(&[[0., 2.], [0., 1.], [0., 0.]]);
+        let d = segment_lengths(&method, &a, None)[0];
+
+        // Identical tracks.
+        assert_close(hausdorff(&method, &a, &a, None).unwrap(), 0.);
+        assert_close(frechet(&method, &a, &a, None).unwrap(), 0.);
+        assert_close(dtw(&method, &a, &a, None).unwrap(), 0.);
+
+        // The same points in reverse order; only Hausdorff ignores the order.
+        assert_close(hausdorff(&method, &a, &reversed, None).unwrap(), 0.);
+        assert_close(frechet(&method, &a, &reversed, None).unwrap(), 2. * d);
+        assert_close(dtw(&method, &a, &reversed, None).unwrap(), 4. * d);
+
+        // A track of only the ends.
+        let ends = arr2(&[[0., 0.], [0., 2.]]);
+        assert_close(hausdorff(&method, &a, &ends, None).unwrap(), d);
+        assert_close(frechet(&method, &ends, &a, None).unwrap(), d);
+        assert_close(dtw(&method, &a, &ends, None).unwrap(), d);
+
+        assert!(matches!(
+            hausdorff(&method, &a, &Array2::zeros((0, 2)), None),
+            Err(TrajectoryError::Shape(_))
+        ));
+
+        assert_eq!("DTW".parse::<Metric>().unwrap(), Metric::Dtw);
+        assert!("euclidean".parse::<Metric>().is_err());
+    }
+
+    #[test]
+    fn test_track_distances() {
+        let tracks = vec![
+            arr2(&[[0., 0.], [0., 1.], [0., 2.]]),
+            arr2(&[[1., 0.], [1., 1.5], [1.2, 2.]]),
+            arr2(&[[0., 2.], [0.5, 1.], [0., 0.], [0., -1.]]),
+        ];
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            for metric in [Metric::Hausdorff, Metric::Frechet, Metric::Dtw] {
+                let result = track_distances(&method, &tracks, metric, None).unwrap();
+                assert_eq!(result.shape(), &[3, 3]);
+
+                for i in 0..3 {
+                    assert_eq!(result[[i, i]], 0.);
+                    for j in 0..3 {
+                        assert_eq!(result[[i, j]], result[[j, i]]);
+                        if i != j {
+                            assert_close(result[[i, j]], track_distance(&method, &tracks[i], &tracks[j], metric, None).unwrap());
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(track_distances(&CalculationMethod::HAVERSINE, &[], Metric::Dtw, None).unwrap().shape(), &[0, 0]);
+    }
+}
+
+#[cfg(test)]
+mod test_matching {
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::matching::{
+        match_track,
+        MatchingError,
+        Network,
+    };
+
+    /// A T-junction at the equator, plus a separate road further away:
+    ///
+    /// - edge 0 from node 0 to the junction at node 1,
+    /// - edge 1 from the junction east to node 2,
+    /// - edge 2 from the junction north to node 3 via a bend, and
+    /// - edge 3 from node 4 to node 5, not connected to the rest.
+    fn network(method:&CalculationMethod) -> Network {
+        let nodes = arr2(&[
+            [0., 0.], [0., 0.01], [0., 0.02], [0.01, 0.01],
+            [1., 1.], [1., 1.01],
+        ]);
+        let geometries = vec![
+            arr2(&[[0., 0.005]]),
+            Array2::zeros((0, 2)),
+            arr2(&[[0.005, 0.0101]]),
+            arr2(&[[1., 1.005]]),
+        ];
+
+        return Network::new(method, &nodes, &[(0, 1), (1, 2), (1, 3), (4, 5)], Some(&geometries), None).unwrap();
+    }
+
+    #[test]
+    fn test_network() {
+        let method = CalculationMethod::HAVERSINE;
+        let network = network(&method);
+
+        assert_eq!(network.edges()[0].geometry.shape(), &[3, 2]);
+        assert_eq!(network.edges()[1].geometry.shape(), &[2, 2]);
+
+        let lengths: Vec<f64> = network.edges().iter().map(| edge | edge.length()).collect();
+        assert!((lengths[0] - 1.112).abs() < 0.001, "{:?}", lengths);
+
+        // Near the junction, all three of its edges are candidates.
+        let candidates = network.candidates(&method, (0.0001, 0.0099), 0.05, None);
+        let mut edges: Vec<usize> = candidates.iter().map(| c | c.edge).collect();
+        assert_eq!(edges[0], 0);
+        edges.sort();
+        assert_eq!(edges, vec![0, 1, 2]);
+        assert!(candidates.windows(2).all(| pair | pair[0].distance <= pair[1].distance));
+
+        let distances = network.graph().distances_from(0, f64::INFINITY);
+        assert!((distances[&3] - lengths[0] - lengths[2]).abs() < 1e-9);
+        assert!(!distances.contains_key(&4));
+
+        assert_eq!(network.graph().distances_from(0, 1.).len(), 1);
+
+        assert!(matches!(
+            Network::new(&method, &arr2(&[[0., 0.]]), &[(0, 1)], None, None),
+            Err(MatchingError::InvalidNode(1))
+        ));
+    }
+
+    #[test]
+    fn test_match_track() {
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let network = network(&method);
+
+            let track = arr2(&[
+                [0.00005, 0.002],
+                [-0.00004, 0.006],
+                [0.00008, 0.0098],      // Closer to edge 0 than to the others.
+                [0.005, 0.005],         // Far from any edge.
+                [0.003, 0.01008],
+                [0.008, 0.00995],
+                [1.00003, 1.004],       // On the road that is not connected.
+            ]);
+
+            let (edges, positions) = match_track(&method, &network, &track, 0.05, 0.01, 0.02, None).unwrap();
+
+            assert_eq!(edges, vec![Some(0), Some(0), Some(0), None, Some(2), Some(2), Some(3)]);
+
+            assert!(positions.row(3).iter().all(| v | v.is_nan()));
+            for (t, expected) in [(0, (0., 0.002)), (1, (0., 0.006)), (6, (1., 1.004))] {
+                assert!((positions[[t, 0]] - expected.0).abs() < 1e-6, "{:?}", positions.row(t));
+                assert!((positions[[t, 1]] - expected.1).abs() < 1e-6, "{:?}", positions.row(t));
+            }
+        }
+
+        let network = network(&CalculationMethod::HAVERSINE);
+        assert!(matches!(
+            match_track(&CalculationMethod::HAVERSINE, &network, &arr2(&[[0., 0.]]), 0.05, 0., 0.02, None),
+            Err(MatchingError::Parameter(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_graph {
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::{
+        CalculationInterfacePairwise,
+        CalculationMethod,
+    };
+    use crate::graph::{
+        Algorithm,
+        Graph,
+        GraphError,
+    };
+
+    /// A square of 1 degree with a diagonal, and a spur off one corner.
+    fn square() -> (Array2<f64>, Vec<(usize, usize)>) {
+        return (
+            arr2(&[[0., 0.], [0., 1.], [1., 1.], [1., 0.], [0., 2.]]),
+            vec![(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (1, 4)],
+        );
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let method = CalculationMethod::HAVERSINE;
+        let (nodes, edges) = square();
+
+        // By default, the diagonal is shorter than going around.
+        let graph = Graph::new(&method, &nodes, &edges, None, false, None).unwrap();
+        let diagonal = method._inverse(0., 0., 1., 1., None).0;
+        for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+            let path = graph.shortest_path(&method, 0, 2, algorithm, None).unwrap().unwrap();
+            assert_eq!(path.nodes, vec![0, 2]);
+            assert_eq!(path.edges, vec![4]);
+            assert!((path.distance - diagonal).abs() < 1e-9);
+        }
+
+        let path = graph.dijkstra(4, 4).unwrap().unwrap();
+        assert_eq!((path.distance, path.nodes, path.edges), (0., vec![4], vec![]));
+
+        // With explicit weights, the diagonal is longer.
+        let weights = [1., 1., 1., 1.5, 5., 1.];
+        let graph = Graph::new(&method, &nodes, &edges, Some(&weights[..]), false, None).unwrap();
+        let path = graph.dijkstra(0, 2).unwrap().unwrap();
+        assert_eq!((path.distance, path.nodes, path.edges), (2., vec![0, 1, 2], vec![0, 1]));
+
+        // Directed edges only go one way.
+        let graph = Graph::new(&method, &nodes, &edges, Some(&weights[..]), true, None).unwrap();
+        let path = graph.dijkstra(2, 0).unwrap().unwrap();
+        assert_eq!((path.distance, path.nodes), (2.5, vec![2, 3, 0]));
+        assert_eq!(graph.dijkstra(4, 0).unwrap(), None);
+
+        assert_eq!(graph.dijkstra(0, 99), Err(GraphError::InvalidNode(99)));
+        assert_eq!(
+            Graph::new(&method, &nodes, &edges, Some(&[1., -1., 1., 1., 1., 1.][..]), false, None).err(),
+            Some(GraphError::InvalidWeight(-1.)),
+        );
+        assert!(matches!(
+            Graph::new(&method, &nodes, &edges, Some(&[1.][..]), false, None),
+            Err(GraphError::Shape(_))
+        ));
+
+        assert_eq!("A*".parse::<Algorithm>().unwrap(), Algorithm::AStar);
+        assert!("bfs".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_astar() {
+        // A jittered grid of 8 x 8 nodes, joined to their right and upper
+        // neighbours.
+        let size = 8;
+        let nodes = Array2::from_shape_fn(
+            (size * size, 2),
+            | (i, j) | match j {
+                0 => 50. + (i / size) as f64 * 0.1 + ((i * 7) % 5) as f64 * 0.01,
+                _ => (i % size) as f64 * 0.1 + ((i * 3) % 7) as f64 * 0.01,
+            },
+        );
+        let edges: Vec<(usize, usize)> = (0..size * size).flat_map(
+            | i | {
+                let mut edges = Vec::new();
+                if i % size < size - 1 { edges.push((i, i + 1)); }
+                if i / size < size - 1 { edges.push((i, i + size)); }
+                edges
+            }
+        )
+        .collect();
+
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let graph = Graph::new(&method, &nodes, &edges, None, false, None).unwrap();
+
+            for (source, target) in [(0, 63), (7, 56), (10, 45), (63, 8)] {
+                let dijkstra = graph.dijkstra(source, target).unwrap().unwrap();
+                let astar = graph.astar(&method, source, target, None).unwrap().unwrap();
+
+                assert!((dijkstra.distance - astar.distance).abs() < 1e-9, "{:?} {:?}", dijkstra, astar);
+                assert_eq!(astar.nodes.first(), Some(&source));
+                assert_eq!(astar.nodes.last(), Some(&target));
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance_matrix() {
+        let method = CalculationMethod::HAVERSINE;
+        let (nodes, edges) = square();
+
+        let graph = Graph::new(&method, &nodes, &edges, None, false, None).unwrap();
+        let (sources, targets) = ([0, 4], [2, 4, 0]);
+        let result = graph.distance_matrix(&sources, &targets, None).unwrap();
+
+        assert_eq!(result.shape(), &[2, 3]);
+        for (i, source) in sources.iter().enumerate() {
+            for (j, target) in targets.iter().enumerate() {
+                assert_eq!(result[[i, j]], graph.dijkstra(*source, *target).unwrap().unwrap().distance);
+            }
+        }
+        assert_eq!(result[[0, 2]], 0.);
+
+        let directed = Graph::new(&method, &nodes, &edges, None, true, None).unwrap();
+        let result = directed.distance_matrix(&[4], &targets, None).unwrap();
+        assert_eq!(result.row(0).to_vec(), vec![f64::INFINITY, 0., f64::INFINITY]);
+
+        assert_eq!(graph.distance_matrix(&[5], &targets, None), Err(GraphError::InvalidNode(5)));
+    }
+}
+
+#[cfg(test)]
+mod test_proximity {
+    use ndarray::{
+        arr2,
+        Array2,
+    };
+
+    use crate::compatibility::{
+        CalculationInterfaceInternal,
+        CalculationInterfaceProximity,
+        CalculationMethod,
+    };
+    use crate::compatibility::proximity::to_csr;
+
+    #[test]
+    fn test_proximity_graph() {
+        let method = CalculationMethod::HAVERSINE;
+
+        // Points along the equator about 1.112 km apart, with a gap at the end.
+        let s = arr2(&[[0., 0.], [0., 0.01], [0., 0.02], [0., 0.05]]);
+        let pairs = | edges:Vec<(usize, usize, f64)> | edges.iter().map(| e | (e.0, e.1)).collect::<Vec<_>>();
+
+        assert_eq!(pairs(method._proximity_graph(&s, Some(1.2), None, None)), vec![(0, 1), (1, 2)]);
+        assert_eq!(pairs(method._proximity_graph(&s, None, Some(1), None)), vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(pairs(method._proximity_graph(&s, Some(1.2), Some(1), None)), vec![(0, 1), (1, 2)]);
+        assert!(method._proximity_graph(&s, None, None, None).is_empty());
+
+        let edges = method._proximity_graph(&s, None, Some(1), None);
+        assert!((edges[0].2 - 1.112).abs() < 0.001, "{:?}", edges);
+
+        // Radius graphs agree with the full distance array.
+        let s = Array2::from_shape_fn((60, 2), | (i, j) | ((i * (7 + j * 6)) % 19) as f64 * 0.01 + j as f64 * 10.);
+        for method in [CalculationMethod::HAVERSINE, CalculationMethod::VINCENTY] {
+            let d = CalculationInterfaceInternal::<f64>::_distance_within_array(&method, &s, None);
+            let expected: Vec<(usize, usize)> = (0..60).flat_map(
+                | i | (i + 1..60).map(move | j | (i, j))
+            )
+            .filter(| (i, j) | d[[*i, *j]] <= 5.)
+            .collect();
+
+            let edges = method._proximity_graph(&s, Some(5.), None, None);
+            assert_eq!(pairs(edges.clone()), expected);
+            assert!(edges.iter().all(| (i, j, distance) | (distance - d[[*i, *j]]).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_to_csr() {
+        let (data, indices, indptr) = to_csr(4, &[(0, 2, 1.5), (1, 2, 0.5), (0, 1, 2.)]);
+
+        assert_eq!(indptr.to_vec(), vec![0, 2, 4, 6, 6]);
+        assert_eq!(indices.to_vec(), vec![1, 2, 0, 2, 0, 1]);
+        assert_eq!(data.to_vec(), vec![2., 1.5, 2., 0.5, 1.5, 0.5]);
+    }
+}
+
+#[cfg(test)]
+mod test_geofence {
+    use ndarray::{
+        arr1,
+        arr2,
+    };
+
+    use crate::compatibility::CalculationMethod;
+    use crate::geofence::{
+        EventKind,
+        Geofence,
+        GeofenceError,
+    };
+
+    /// A circle of 1.2 km around ``(0, 0.01)``, and a square from ``0.025`` to
+    /// ``0.035`` degrees either way.
+    fn geofence() -> Geofence {
+        let mut geofence = Geofence::new();
+
+        assert_eq!(geofence.add_circle((0., 0.01), 1.2), Ok(0));
+        assert_eq!(
+            geofence.add_polygon(&arr2(&[[-0.005, 0.025], [-0.005, 0.035], [0.005, 0.035], [0.005, 0.025]])),
+            Ok(1),
+        );
+
+        return geofence;
+    }
+
+    #[test]
+    fn test_check() {
+        let method = CalculationMethod::HAVERSINE;
+        let geofence = geofence();
+
+        // Along the equator about 1.112 km apart: through the circle, then into
+        // the square and staying there.
+        let track = arr2(&[
+            [0., -0.01], [0., 0.], [0., 0.01], [0., 0.02], [0., 0.03], [0., 0.031], [0., 0.032],
+        ]);
+        let times = arr1(&[0., 10., 20., 30., 40., 50., 60.]);
+
+        let events = geofence.check(&method, &track, &times, Some(15.), None).unwrap();
+        let summary: Vec<(usize, EventKind, usize)> = events.iter().map(| e | (e.fence, e.kind, e.index)).collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (0, EventKind::Enter, 1),
+                (0, EventKind::Exit, 4),
+                (1, EventKind::Enter, 4),
+                (1, EventKind::Dwell, 6),
+            ]
+        );
+        assert_eq!(events[3].time, 60.);
+
+        // Without dwell, only the crossings remain.
+        assert_eq!(geofence.check(&method, &track, &times, None, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_check_errors() {
+        let method = CalculationMethod::HAVERSINE;
+        let mut geofence = geofence();
+        let track = arr2(&[[0., 0.], [0., 0.01]]);
+
+        assert!(matches!(
+            geofence.check(&method, &track, &arr1(&[0.]), None, None),
+            Err(GeofenceError::Shape(_))
+        ));
+        assert!(matches!(
+            geofence.check(&method, &track, &arr1(&[1., 0.]), None, None),
+            Err(GeofenceError::Times(_))
+        ));
+        assert!(matches!(geofence.add_circle((0., 0.), -1.), Err(GeofenceError::Parameter(_))));
+        assert!(matches!(geofence.add_polygon(&arr2(&[[0., 0.], [0., 1.]])), Err(GeofenceError::Shape(_))));
+    }
+}
+
+#[cfg(test)]
+mod test_facilities {
+    use ndarray::{
+        arr2,
+        Array2,
+        Axis,
+    };
+
+    use crate::compatibility::{
+        CalculationInterfaceInternal,
+        CalculationMethod,
+    };
+    use crate::facilities::{
+        FacilityError,
+        FacilityIndex,
+        MAX_UNINDEXED,
+    };
+
+    #[test]
+    fn test_insert_remove() {
+        let method = CalculationMethod::HAVERSINE;
+        let mut index = FacilityIndex::new();
+        let queries = arr2(&[[0., 0.009], [0., 0.031]]);
+
+        assert_eq!(index.nearest(&method, &queries, None), vec![None, None]);
+
+        // Along the equator about 1.112 km apart.
+        assert_eq!(index.insert(&arr2(&[[0., 0.], [0., 0.01], [0., 0.03]])), Ok(vec![0, 1, 2]));
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(1), Some((0., 0.01)));
+
+        let nearest = index.nearest(&method, &queries, None);
+        assert_eq!(nearest.iter().map(| r | r.unwrap().0).collect::<Vec<u64>>(), vec![1, 2]);
+        assert!((nearest[0].unwrap().1 - 0.1112).abs() < 0.001, "{:?}", nearest);
+
+        assert_eq!(index.remove(&[1, 1, 7]), 1);
+        assert!(!index.contains(1));
+        assert_eq!(index.nearest(&method, &queries, None)[0].unwrap().0, 0);
+
+        // Ids are not reused.
+        assert_eq!(index.insert(&arr2(&[[0., 0.009]])), Ok(vec![3]));
+        assert_eq!(index.nearest(&method, &queries, None)[0], Some((3, 0.)));
+
+        assert!(matches!(index.insert(&Array2::zeros((1, 3))), Err(FacilityError::Shape(_))));
+    }
+
+    #[test]
+    fn test_nearest_brute_force() {
+        let method = CalculationMethod::VINCENTY;
+        let mut index = FacilityIndex::new();
+
+        // Enough facilities to build the tree, then a few more left out of it.
+        let facilities = Array2::from_shape_fn((MAX_UNINDEXED * 2 + 10, 2), | (i, j) | {
+            let x = i as f64;
+            if j == 0 { (x * 7.3) % 170. - 85. } else { (x * 13.7) % 360. - 180. }
+        });
+        let (first, second) = facilities.view().split_at(Axis(0), facilities.shape()[0] - 10);
+        index.insert(&first.to_owned()).unwrap();
+        index.insert(&second.to_owned()).unwrap();
+
+        let removed: Vec<u64> = (0..facilities.shape()[0] as u64).step_by(3).collect();
+        index.remove(&removed);
+
+        let queries = arr2(&[[51.5, -0.1], [-33.9, 151.2], [0., 0.], [89., 45.]]);
+        let nearest = index.nearest(&method, &queries, None);
+
+        for (i, query) in queries.rows().into_iter().enumerate() {
+            let distances = CalculationInterfaceInternal::<f64>::_distance_from_point(
+                &method, &query, &facilities, None,
+            );
+            let expected = (0..facilities.shape()[0])
+                           .filter(| j | j % 3 != 0)
+                           .map(| j | distances[j])
+                           .fold(f64::INFINITY, f64::min);
+
+            assert_eq!(nearest[i].unwrap().1, expected, "query {:?}", query);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+/// Slippy map tiles, as used by OpenStreetMap and most web maps.
+///
+/// At zoom ``z``, the Web Mercator square between latitudes ±85.0511º is divided
+/// into ``2^z`` by ``2^z`` tiles, each of 256 by 256 pixels. Tile ``(x, y)``
+/// counts eastwards from longitude -180º and southwards from the northern edge.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use ndarray::{
+    Array2,
+    Axis,
+};
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::{
+    LatLng,
+    LatLngArray,
+};
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationInterfaceNeighbours,
+    CalculationMethod,
+    CalculationSettings,
+};
+use crate::geohash::closest_point;
+use crate::transforms::mercator::MAX_LATITUDE;
+
+// Import this if you want the tiles module to be available in Python.
+pub mod python;
+
+/// Highest zoom supported.
+pub const MAX_ZOOM:u8 = 30;
+
+/// Width and height of a tile in pixels.
+pub const TILE_SIZE:f64 = 256.;
+
+/// Errors from tile calculations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TileError {
+    /// The zoom is above ``MAX_ZOOM``.
+    InvalidZoom(u8),
+
+    /// The tile indices are outside of the grid of the zoom.
+    InvalidTile(u32, u32, u8),
+}
+impl fmt::Display for TileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::InvalidZoom(z) => write!(
+                f, "Tile zoom must be between 0 and {:?}, yet {:?} provided.", MAX_ZOOM, z
+            ),
+            Self::InvalidTile(x, y, z) => write!(
+                f, "Tile ({:?}, {:?}) does not exist at zoom {:?}.", x, y, z
+            ),
+        };
+    }
+}
+impl std::error::Error for TileError {}
+
+/// Check that a zoom is supported.
+fn check_zoom(zoom:u8) -> Result<(), TileError> {
+    return if zoom <= MAX_ZOOM { Ok(()) } else { Err(TileError::InvalidZoom(zoom)) };
+}
+
+/// Number of tiles along each axis at a zoom.
+fn tiles_per_axis(zoom:u8) -> u32 {
+    return 1_u32 << zoom;
+}
+
+/// A tile at a zoom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub z: u8,
+}
+impl Tile {
+    pub fn new(x:u32, y:u32, z:u8) -> Result<Self, TileError> {
+        check_zoom(z)?;
+
+        if x >= tiles_per_axis(z) || y >= tiles_per_axis(z) {
+            return Err(TileError::InvalidTile(x, y, z));
+        }
+
+        return Ok(Self { x, y, z });
+    }
+
+    /// Position of a point in fractional tiles at a zoom; latitudes are clamped to
+    /// the Web Mercator square.
+    fn position(lat:f64, lng:f64, zoom:u8) -> (f64, f64) {
+        let n = tiles_per_axis(zoom) as f64;
+        let lat_r = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+        let lng = (lng + 180.).rem_euclid(360.) - 180.;
+
+        return (
+            (lng + 180.) / 360. * n,
+            (1. - (lat_r.tan() + 1. / lat_r.cos()).ln() / PI) / 2. * n,
+        );
+    }
+
+    /// The tile containing a point, and the pixel offset of the point from the
+    /// north-west corner of the tile.
+    pub fn from_latlng(lat:f64, lng:f64, zoom:u8) -> Result<(Self, [f64; 2]), TileError> {
+        check_zoom(zoom)?;
+
+        let max_idx = (tiles_per_axis(zoom) - 1) as f64;
+        let (x, y) = Self::position(lat, lng, zoom);
+        let (x_idx, y_idx) = (x.floor().clamp(0., max_idx), y.floor().clamp(0., max_idx));
+
+        return Ok((
+            Self { x: x_idx as u32, y: y_idx as u32, z: zoom },
+            [(x - x_idx) * TILE_SIZE, (y - y_idx) * TILE_SIZE],
+        ));
+    }
+
+    /// Latitude of the northern edge of tile row ``y``.
+    fn lat_of_row(y:f64, zoom:u8) -> f64 {
+        return (PI * (1. - 2. * y / tiles_per_axis(zoom) as f64)).sinh().atan().to_degrees();
+    }
+
+    /// Longitude of the western edge of tile column ``x``.
+    fn lng_of_column(x:f64, zoom:u8) -> f64 {
+        return x / tiles_per_axis(zoom) as f64 * 360. - 180.;
+    }
+
+    /// ``(lat_min, lat_max, lng_min, lng_max)`` of this tile, in degrees.
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (x, y) = (self.x as f64, self.y as f64);
+
+        return (
+            Self::lat_of_row(y + 1., self.z),
+            Self::lat_of_row(y, self.z),
+            Self::lng_of_column(x, self.z),
+            Self::lng_of_column(x + 1., self.z),
+        );
+    }
+}
+
+/// Tiles containing each row of an array of lat-long coordinates at ``zoom``.
+///
+/// Returns a tuple of arrays of dimension ``(n, 2)``: the ``(x, y)`` of each tile,
+/// and the ``(x, y)`` pixel offset of each point within its tile.
+pub fn to_tiles(
+    s:&dyn LatLngArray,
+    zoom:u8,
+) -> Result<(Array2<u32>, F64Array2), TileError> {
+    check_zoom(zoom)?;
+
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+
+    let rows: Vec<(Tile, [f64; 2])> = {
+        s_owned.axis_iter(Axis(0))
+               .into_par_iter()
+               .map(| row | Tile::from_latlng(row[0], row[1], zoom).unwrap())
+               .collect()
+    };
+
+    let tiles = rows.iter().flat_map(| (tile, _) | [tile.x, tile.y]).collect();
+    let pixels = rows.iter().flat_map(| (_, pixel) | *pixel).collect();
+
+    return Ok((
+        Array2::from_shape_vec((n, 2), tiles).unwrap(),
+        F64Array2::from_shape_vec((n, 2), pixels).unwrap(),
+    ));
+}
+
+/// Bounds of each tile ``(x, y)`` of an array of dimension ``(n, 2)`` at ``zoom``.
+///
+/// Returns an array of dimension ``(n, 4)`` in order
+/// ``(lat_min, lng_min, lat_max, lng_max)``, in degrees.
+pub fn tile_bounds(
+    tiles:&Array2<u32>,
+    zoom:u8,
+) -> Result<F64Array2, TileError> {
+    let rows = {
+        tiles.axis_iter(Axis(0))
+             .into_par_iter()
+             .map(
+                 | row | -> Result<[f64; 4], TileError> {
+                     let (lat_min, lat_max, lng_min, lng_max) = Tile::new(row[0], row[1], zoom)?.bounds();
+                     Ok([lat_min, lng_min, lat_max, lng_max])
+                 }
+             )
+             .collect::<Result<Vec<_>, _>>()?
+    };
+
+    return Ok(F64Array2::from_shape_vec((rows.len(), 4), rows.into_iter().flatten().collect()).unwrap());
+}
+
+/// Largest longitude difference, in degrees, from ``lat`` of the points of a
+/// spherical cap of angular radius ``ang_dist`` around it, over the latitudes of
+/// ``band``; ``None`` if the cap covers all longitudes within ``band``.
+///
+/// The width of the cap rises to its widest latitude and falls after it, or keeps
+/// rising towards a pole inside the cap, so only the ends of ``band`` and the
+/// widest latitude need checking.
+fn lng_half_width(lat:f64, ang_dist:f64, band:(f64, f64)) -> Option<f64> {
+    if ang_dist >= 180. || lat.abs() >= 90. {
+        return None;
+    }
+
+    let (lat_r, ang_r) = (lat.to_radians(), ang_dist.to_radians());
+
+    let mut candidates = vec![band.0, band.1];
+    if ang_r.cos() > lat_r.sin().abs() {
+        let widest = (lat_r.sin() / ang_r.cos()).asin().to_degrees();
+        candidates.push(widest.clamp(band.0, band.1));
+    }
+
+    let mut d_lng:f64 = 0.;
+    for candidate in candidates {
+        if candidate.abs() >= 90. {
+            return None;
+        }
+
+        let candidate_r = candidate.to_radians();
+        let cos_d_lng = {
+            (ang_r.cos() - candidate_r.sin() * lat_r.sin())
+            / (candidate_r.cos() * lat_r.cos())
+        };
+
+        if cos_d_lng <= -1. {
+            return None;
+        }
+
+        d_lng = d_lng.max(cos_d_lng.min(1.).acos().to_degrees());
+    }
+
+    return Some(d_lng);
+}
+
+/// All tiles at ``zoom`` intersecting a geodesic circle.
+///
+/// A tile is included if any part of it is within ``radius`` of ``centre``, using
+/// the same distance semantics as :meth:`_within_distance_of_point` of ``method``.
+pub fn tiles_within_distance(
+    method:&CalculationMethod,
+    centre:&dyn LatLng,
+    radius:f64,
+    zoom:u8,
+    settings: Option<&CalculationSettings>,
+) -> Result<Vec<Tile>, TileError> {
+    let (lat, lng) = (centre[0], centre[1]);
+    let (centre_tile, _) = Tile::from_latlng(lat, lng, zoom)?;
+
+    let (radius_min, _) = method._radius_bounds(settings);
+    let ang_dist = (radius / radius_min).to_degrees();
+
+    let n = tiles_per_axis(zoom) as i64;
+
+    // Bounding box of the circle in tile indices; rows count southwards.
+    let (_, row_max) = Tile::position(lat - ang_dist, lng, zoom);
+    let (_, row_min) = Tile::position(lat + ang_dist, lng, zoom);
+    let (row_min, row_max) = ((row_min.floor() as i64).max(0), (row_max.floor() as i64).min(n - 1));
+
+    // Columns of each row within the longitudes the circle reaches at the latitudes
+    // of the row. Rows beyond the latitudes of the circle are skipped, and only
+    // rows around a pole inside the circle span all longitudes.
+    let mut tiles: Vec<Tile> = Vec::new();
+    for y in row_min..=row_max {
+        let (band_min, band_max, _, _) = Tile { x: 0, y: y as u32, z: zoom }.bounds();
+        let band = (band_min.max(lat - ang_dist), band_max.min(lat + ang_dist));
+
+        if band.0 > band.1 {
+            continue;
+        }
+
+        let span = lng_half_width(lat, ang_dist, band).map(
+            | d_lng | (d_lng / 360. * n as f64).ceil() as i64 + 1
+        );
+        let columns: Vec<u32> = match span {
+            Some(span) if 2 * span + 1 < n => {
+                let mut columns: Vec<u32> = {
+                    (-span..=span).map(| d | (centre_tile.x as i64 + d).rem_euclid(n) as u32)
+                                  .collect()
+                };
+                columns.sort_unstable();
+                columns
+            },
+            _ => (0..n as u32).collect(),
+        };
+
+        tiles.extend(columns.into_iter().map(| x | Tile { x, y: y as u32, z: zoom }));
+    }
+
+    if !tiles.contains(&centre_tile) {
+        tiles.push(centre_tile);
+    }
+
+    // Distance from the centre to the closest point of each tile.
+    let mut closest = F64LatLngArray::zeros((tiles.len(), 2));
+    for (i, tile) in tiles.iter().enumerate() {
+        let (closest_lat, closest_lng) = closest_point(tile.bounds(), lat, lng);
+
+        closest[[i, 0]] = closest_lat;
+        closest[[i, 1]] = closest_lng;
+    }
+
+    let distances: F64Array1 = CalculationInterfaceInternal::<f64>::_distance_from_point(
+        method,
+        centre, &closest,
+        settings,
+    );
+
+    return Ok(
+        tiles.into_iter()
+             .zip(distances.iter())
+             .filter(| (tile, d) | **d <= radius || *tile == centre_tile)
+             .map(| (tile, _) | tile)
+             .collect()
+    );
+}
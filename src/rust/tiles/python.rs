@@ -0,0 +1,174 @@
+/// Python compatibility layer for the `tiles` module.
+///
+/// The functions here are registered into a `tiles` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.tiles`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array2,
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::TileError;
+
+impl From<TileError> for PyErr {
+    fn from(err: TileError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, zoom)")]
+/// Tiles containing each row of an array of lat-long coordinates.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///     Latitudes beyond ±85.0511º are clamped.
+///
+/// zoom: int
+///     Zoom level, between ``0`` and ``30``.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(tiles, pixels)``, both of dimension ``(n, 2)``: ``tiles`` contains the
+///     ``(x, y)`` indices of each tile with ``dtype=numpy.uint32``, and ``pixels``
+///     the ``(x, y)`` offset in pixels of each point from the north-west corner of
+///     its tile, between ``0`` and ``256``.
+///
+/// Example
+/// -------
+/// ::
+///
+///     >>> import numpy as np
+///     >>> from rust_geodistances import tiles
+///     >>> tiles.to_tiles(np.array([[51.5072, -0.1276]]), 10)[0]
+///     array([[511, 340]], dtype=uint32)
+fn to_tiles(
+    s: &PyArray<f64, Ix2>,
+    zoom: u8,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let (tiles, pixels) = super::to_tiles(&s.to_owned_array(), zoom)?;
+
+    return Ok((tiles.to_pyarray(py), pixels.to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(tiles, zoom)")]
+/// Bounds of tiles in lat-long coordinates.
+///
+/// Parameters
+/// ----------
+/// tiles: numpy.ndarray
+///     Of dimension ``(n, 2)`` and ``dtype=numpy.uint32``, the ``(x, y)`` indices of
+///     each tile, as returned by :func:`to_tiles`.
+///
+/// zoom: int
+///     Zoom level, between ``0`` and ``30``.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 4)``, in degrees. In order
+///     ``(lat_min, lng_min, lat_max, lng_max)``.
+fn bounds(
+    tiles: &PyArray<u32, Ix2>,
+    zoom: u8,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    if tiles.shape()[1] != 2 {
+        return Err(PyValueError::new_err(
+            format!("`tiles` must be of dimension (n, 2), yet {:?} provided.", tiles.shape())
+        ));
+    }
+
+    return Ok(super::tile_bounds(&tiles.to_owned_array(), zoom)?.to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(centre, radius, zoom, method, *, settings)")]
+/// Tiles intersecting a geodesic circle, e.g. to pre-render tiles around a point.
+///
+/// A tile is included if any part of it is within ``radius`` of ``centre``, using
+/// the same distance semantics as
+/// :meth:`~rust_geodistances.CalculationMethod.within_distance_of_point`.
+///
+/// Parameters
+/// ----------
+/// centre: numpy.ndarray
+///     Of dimension ``(2)``, e.g. ``numpy.array([51.5072, -0.1276])``.
+///
+/// radius: numpy.float64
+///     Radius of the circle.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// zoom: int
+///     Zoom level, between ``0`` and ``30``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(k, 2)`` and ``dtype=numpy.uint32``, the ``(x, y)`` indices of
+///     each tile intersecting the circle.
+fn tiles_within_distance(
+    centre: &PyArray<f64, Ix1>,
+    radius: f64,
+    zoom: u8,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let tiles = super::tiles_within_distance(
+        method.unwrap_or(method_default),
+        &centre.to_owned_array(),
+        radius,
+        zoom,
+        settings,
+    )?;
+
+    let flat: Vec<u32> = tiles.iter().flat_map(| tile | [tile.x, tile.y]).collect();
+
+    return Ok(Array2::from_shape_vec((tiles.len(), 2), flat).unwrap().to_pyarray(py).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(to_tiles, m)?)?;
+    m.add_function(wrap_pyfunction!(bounds, m)?)?;
+    m.add_function(wrap_pyfunction!(tiles_within_distance, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,387 @@
+/// Analytics of ordered tracks.
+///
+/// A track is an array of dimension ``(n, 2)`` of consecutive positions, with
+/// optional timestamps of dimension ``(n)``. Segment ``i`` runs from point ``i`` to
+/// point ``i+1``; all calculations over segments use the pairwise methods of
+/// :class:`CalculationInterfacePairwise`, so every segment is solved once, in
+/// parallel.
+///
+/// Lengths are in the unit of the calculation method, and time in the unit of the
+/// timestamps, e.g. seconds since the Unix epoch as read by :mod:`formats`; speeds
+/// and accelerations are in the units derived from those.
+///
+/// Stay points, where a track dwells within a small area, are found by
+/// :func:`detect_stops`; polylines are simplified by :mod:`simplify` and
+/// resampled at regular intervals by :mod:`resample`. Whole tracks are compared by
+/// the metrics in :mod:`similarity`.
+
+use std::fmt;
+
+use ndarray::{
+    s,
+    Zip,
+};
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+use crate::cells::to_latlng;
+use crate::spatial::kdtree::to_unit_vector_rad;
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationInterfacePairwise,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+pub mod resample;
+pub mod similarity;
+pub mod simplify;
+
+// Import this if you want the trajectory module to be available in Python.
+pub mod python;
+
+pub use resample::{
+    resample_by_distance,
+    resample_by_time,
+};
+pub use similarity::{
+    dtw,
+    frechet,
+    hausdorff,
+    track_distance,
+    track_distances,
+    Metric,
+};
+pub use simplify::{
+    simplify,
+    Algorithm,
+};
+
+/// Errors from trajectory calculations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrajectoryError {
+    /// Timestamps do not match the number of points.
+    Shape(String),
+
+    /// Timestamps are missing or decreasing where they must increase.
+    Times(String),
+
+    /// A parameter is out of its valid range.
+    Parameter(String),
+}
+impl fmt::Display for TrajectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+            Self::Times(msg) => write!(f, "Invalid timestamps: {}", msg),
+            Self::Parameter(msg) => write!(f, "Invalid parameter: {}", msg),
+        };
+    }
+}
+impl std::error::Error for TrajectoryError {}
+
+/// Radius used to convert the distances of ``method`` into angles for spherical
+/// trigonometry; the mean radius ``(2a + b) / 3`` of the ellipsoid for Vincenty.
+pub fn mean_radius(
+    method:&CalculationMethod,
+    settings: Option<&CalculationSettings>,
+) -> f64 {
+    let settings_default = &CalculationSettings::default();
+    let settings = settings.unwrap_or(settings_default);
+
+    return match method {
+        CalculationMethod::HAVERSINE => settings.spherical_radius,
+        CalculationMethod::VINCENTY => (2. * settings.ellipse_a + settings.ellipse_b) / 3.,
+    };
+}
+
+/// Distance from point ``p`` to the segment from ``a`` to ``b``, all ``(lat, lng)``
+/// in degrees.
+///
+/// Returns a tuple of the distance, and the distance along the segment from ``a``
+/// to the closest point, between ``0`` and the length of the segment.
+///
+/// Distances and bearings from ``a`` are those of ``method``; the cross-track and
+/// along-track distances are then found by spherical trigonometry on a sphere of
+/// :func:`mean_radius`. Beyond either end of the segment, the distance is that to
+/// the nearer end.
+pub fn distance_to_segment(
+    method:&CalculationMethod,
+    a:(f64, f64),
+    b:(f64, f64),
+    p:(f64, f64),
+    settings: Option<&CalculationSettings>,
+) -> (f64, f64) {
+    let (d_ab, bearing_ab) = method._inverse(a.0, a.1, b.0, b.1, settings);
+    let (d_ap, bearing_ap) = method._inverse(a.0, a.1, p.0, p.1, settings);
+
+    let d_theta = (bearing_ap - bearing_ab).to_radians();
+    if d_ab == 0. || d_theta.cos() <= 0. {
+        return (d_ap, 0.);
+    }
+
+    let radius = mean_radius(method, settings);
+    let delta_ap = d_ap / radius;
+
+    let cross_track = (delta_ap.sin() * d_theta.sin()).asin();
+    let along_track = radius * (delta_ap.cos() / cross_track.cos()).clamp(-1., 1.).acos();
+
+    if along_track >= d_ab {
+        return (method._inverse(b.0, b.1, p.0, p.1, settings).0, d_ab);
+    }
+
+    return ((cross_track * radius).abs(), along_track);
+}
+
+/// Lengths and initial headings in degrees of each segment of a track, both of
+/// dimension ``(n-1)``.
+pub fn segments(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> (F64Array1, F64Array1) {
+    let s_owned = s.to_owned();
+    if s_owned.shape()[0] < 2 { return (F64Array1::zeros(0), F64Array1::zeros(0)) }
+
+    return method._inverse_pairwise(
+        &s_owned.slice(s![..-1, ..]).to_owned(),
+        &s_owned.slice(s![1.., ..]).to_owned(),
+        settings,
+    );
+}
+
+/// Lengths of each segment of a track, of dimension ``(n-1)``.
+pub fn segment_lengths(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> F64Array1 {
+    let s_owned = s.to_owned();
+    if s_owned.shape()[0] < 2 { return F64Array1::zeros(0) }
+
+    return method._distance_pairwise(
+        &s_owned.slice(s![..-1, ..]).to_owned(),
+        &s_owned.slice(s![1.., ..]).to_owned(),
+        settings,
+    );
+}
+
+/// Distance travelled up to each point, of dimension ``(n)``; starts at ``0``.
+pub fn cumulative_lengths(segment_lengths:&F64Array1) -> F64Array1 {
+    let mut total = 0.;
+
+    return std::iter::once(0.)
+                      .chain(segment_lengths.iter().map(| d | { total += d; total }))
+                      .collect();
+}
+
+/// Check that there is a timestamp for each point.
+fn check_times(points:usize, times:&F64Array1) -> Result<(), TrajectoryError> {
+    if times.len() != points {
+        return Err(TrajectoryError::Shape(
+            format!("expected {:?} timestamps, yet {:?} provided.", points, times.len())
+        ));
+    }
+
+    return Ok(());
+}
+
+/// Average speed over each segment, of dimension ``(n-1)``.
+///
+/// Segments with a missing or non-increasing time are ``NaN``.
+pub fn speeds(
+    segment_lengths:&F64Array1,
+    times:&F64Array1,
+) -> Result<F64Array1, TrajectoryError> {
+    check_times(segment_lengths.len() + 1, times)?;
+
+    let dt = &times.slice(s![1..]) - &times.slice(s![..-1]);
+
+    return Ok(
+        Zip::from(segment_lengths)
+            .and(&dt)
+            .map_collect(| d, dt | if *dt > 0. { d / dt } else { f64::NAN })
+    );
+}
+
+/// Acceleration between consecutive segments, of dimension ``(n-2)``.
+///
+/// The speed of each segment is taken to be at the middle of its time span; the
+/// acceleration is the change in speed over the time between the middles.
+pub fn accelerations(
+    speeds:&F64Array1,
+    times:&F64Array1,
+) -> Result<F64Array1, TrajectoryError> {
+    check_times(speeds.len() + 1, times)?;
+    if speeds.len() < 2 { return Ok(F64Array1::zeros(0)) }
+
+    let midpoints = (&times.slice(s![1..]) + &times.slice(s![..-1])) / 2.;
+    let dv = &speeds.slice(s![1..]) - &speeds.slice(s![..-1]);
+    let dt = &midpoints.slice(s![1..]) - &midpoints.slice(s![..-1]);
+
+    return Ok(
+        Zip::from(&dv)
+            .and(&dt)
+            .map_collect(| dv, dt | if *dt > 0. { dv / dt } else { f64::NAN })
+    );
+}
+
+/// All series of a track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    /// Dimension ``(n-1)``.
+    pub segment_lengths: F64Array1,
+
+    /// Dimension ``(n)``.
+    pub cumulative_lengths: F64Array1,
+
+    /// Initial heading in degrees of each segment, of dimension ``(n-1)``.
+    pub headings: F64Array1,
+
+    /// Dimension ``(n-1)``; only if timestamps are provided.
+    pub speeds: Option<F64Array1>,
+
+    /// Dimension ``(n-2)``; only if timestamps are provided.
+    pub accelerations: Option<F64Array1>,
+}
+impl Trajectory {
+    pub fn new(
+        method:&CalculationMethod,
+        s:&dyn LatLngArray,
+        times:Option<&F64Array1>,
+        settings: Option<&CalculationSettings>,
+    ) -> Result<Self, TrajectoryError> {
+        if let Some(times) = times {
+            check_times(s.shape()[0], times)?;
+        }
+
+        let n = s.shape()[0];
+        let (segment_lengths, headings) = segments(method, s, settings);
+
+        let (speeds, accelerations) = match times {
+            Some(times) if n > 0 => {
+                let speeds = speeds(&segment_lengths, times)?;
+                let accelerations = accelerations(&speeds, times)?;
+
+                (Some(speeds), Some(accelerations))
+            },
+            Some(_) => (Some(F64Array1::zeros(0)), Some(F64Array1::zeros(0))),
+            None => (None, None),
+        };
+
+        return Ok(Self {
+            cumulative_lengths: if n > 0 { cumulative_lengths(&segment_lengths) } else { F64Array1::zeros(0) },
+            segment_lengths,
+            headings,
+            speeds,
+            accelerations,
+        });
+    }
+}
+
+/// Number of points initially checked at once against the anchor of a stop; this
+/// doubles while all of them remain within the radius.
+const STOP_BATCH_SIZE:usize = 16;
+
+/// A stay point: consecutive points of a track that remain within a radius of the
+/// first for at least a minimum duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stop {
+    /// Index of the first point.
+    pub start: usize,
+
+    /// Index of the last point, inclusive.
+    pub end: usize,
+
+    /// ``(lat, lng)`` of the centroid of the points, in degrees.
+    pub centroid: (f64, f64),
+
+    /// Time from the first to the last point.
+    pub duration: f64,
+}
+
+/// Centroid of points on the unit sphere, in degrees.
+fn centroid(s:&F64LatLngArray) -> (f64, f64) {
+    let mut total = [0.; 3];
+
+    for row in s.rows() {
+        let p = to_unit_vector_rad(row[0].to_radians(), row[1].to_radians());
+        for k in 0..3 { total[k] += p[k]; }
+    }
+
+    return to_latlng(&total);
+}
+
+/// Index after the last point from ``start`` onwards that is within ``radius`` of
+/// point ``start``.
+fn stay_end(
+    method:&CalculationMethod,
+    s:&F64LatLngArray,
+    start:usize,
+    radius:f64,
+    settings: Option<&CalculationSettings>,
+) -> usize {
+    let (n, anchor) = (s.shape()[0], s.row(start).to_owned());
+
+    let (mut end, mut batch) = (start + 1, STOP_BATCH_SIZE);
+    while end < n {
+        let upper = (end + batch).min(n);
+        let distances = CalculationInterfaceInternal::<f64>::_distance_from_point(
+            method,
+            &anchor, &s.slice(s![end..upper, ..]).to_owned(),
+            settings,
+        );
+
+        match distances.iter().position(| d | !(*d <= radius)) {
+            Some(offset) => return end + offset,
+            None => { end = upper; batch *= 2; },
+        }
+    }
+
+    return n;
+}
+
+/// Detect stay points along a track.
+///
+/// From each point in turn, the track is followed for as long as it stays within
+/// ``radius`` of that point; if this lasts for at least ``min_duration``, the
+/// points form a :class:`Stop` and the search resumes after it.
+pub fn detect_stops(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    times:&F64Array1,
+    radius:f64,
+    min_duration:f64,
+    settings: Option<&CalculationSettings>,
+) -> Result<Vec<Stop>, TrajectoryError> {
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+    check_times(n, times)?;
+
+    let mut stops: Vec<Stop> = Vec::new();
+
+    let mut start = 0;
+    while start < n {
+        let end = stay_end(method, &s_owned, start, radius, settings) - 1;
+        let duration = times[end] - times[start];
+
+        if end > start && duration >= min_duration {
+            stops.push(Stop {
+                start,
+                end,
+                centroid: centroid(&s_owned.slice(s![start..=end, ..]).to_owned()),
+                duration,
+            });
+            start = end + 1;
+        } else {
+            start += 1;
+        }
+    }
+
+    return Ok(stops);
+}
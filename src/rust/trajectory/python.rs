@@ -0,0 +1,666 @@
+/// Python compatibility layer for the `trajectory` module.
+///
+/// The functions here are registered into a `trajectory` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.trajectory`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+
+use numpy::ndarray::{
+    Array1,
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use ndarray_numeric::F64LatLngArray;
+
+use crate::calc_models::config;
+use crate::compatibility::enums;
+
+use super::{
+    Algorithm,
+    Metric,
+    Trajectory,
+    TrajectoryError,
+};
+
+impl From<TrajectoryError> for PyErr {
+    fn from(err: TrajectoryError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, times, method, *, settings)")]
+/// Length, heading, speed and acceleration series of an ordered track.
+///
+/// Segment ``i`` runs from ``s[i]`` to ``s[i+1]``; all segments are calculated at
+/// once in parallel, like
+/// :meth:`~rust_geodistances.CalculationMethod.distance_pairwise`.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// times: Optional[numpy.ndarray]
+///     Of dimension ``(n)``, the timestamp of each point, e.g. seconds since the
+///     Unix epoch as returned by :func:`~rust_geodistances.formats.gpx.read`. A
+///     ``numpy.datetime64`` array can be converted by
+///     ``times.astype("datetime64[ms]").astype(float) / 1000``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// dict
+///     Containing:
+///
+///     - ``segment_lengths``: of dimension ``(n-1)``,
+///     - ``cumulative_lengths``: of dimension ``(n)``, the distance travelled up to
+///       each point, starting at ``0``,
+///     - ``headings``: of dimension ``(n-1)``, the initial bearing of each segment
+///       in degrees; ``0`` for segments of zero length,
+///     - ``speeds``: of dimension ``(n-1)``, the average speed over each segment,
+///       in the unit of distance per unit of ``times``, and
+///     - ``accelerations``: of dimension ``(n-2)``, the change in speed between
+///       the middles of consecutive segments, per unit of ``times``.
+///
+///     ``speeds`` and ``accelerations`` are ``None`` if ``times`` is not
+///     provided, and ``nan`` wherever time does not increase.
+///
+/// Example
+/// -------
+/// ::
+///
+///     >>> from rust_geodistances import trajectory, vincenty
+///     >>> result = trajectory.analyse(track, times, vincenty)
+///     >>> result["speeds"] * 3600   # km/h from km and seconds.
+fn analyse(
+    s: &PyArray<f64, Ix2>,
+    times: Option<&PyArray<f64, Ix1>>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let times = times.map(| times | times.to_owned_array());
+    let trajectory = Trajectory::new(
+        method.unwrap_or(method_default),
+        &s.to_owned_array(),
+        times.as_ref(),
+        settings,
+    )?;
+
+    let dict = PyDict::new(py);
+
+    dict.set_item("segment_lengths", trajectory.segment_lengths.to_pyarray(py))?;
+    dict.set_item("cumulative_lengths", trajectory.cumulative_lengths.to_pyarray(py))?;
+    dict.set_item("headings", trajectory.headings.to_pyarray(py))?;
+    dict.set_item("speeds", trajectory.speeds.map(| speeds | speeds.to_pyarray(py)))?;
+    dict.set_item("accelerations", trajectory.accelerations.map(| accelerations | accelerations.to_pyarray(py)))?;
+
+    return Ok(dict.into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, times, radius, min_duration, method, *, settings)")]
+/// Detect stay points, where a track dwells within a small area.
+///
+/// From each point in turn, the track is followed for as long as it stays within
+/// ``radius`` of that point; if this lasts for at least ``min_duration``, the
+/// points form a stop and the search resumes after it.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// times: numpy.ndarray
+///     Of dimension ``(n)``, the timestamp of each point.
+///
+/// radius: numpy.float64
+///     Maximum distance from the first point of a stop.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// min_duration: numpy.float64
+///     Minimum time from the first to the last point of a stop, in the unit of
+///     ``times``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// dict
+///     Containing, for ``k`` stops:
+///
+///     - ``start`` and ``end``: of dimension ``(k)``, the indices of the first and
+///       last points of each stop, inclusive,
+///     - ``centroids``: of dimension ``(k, 2)``, in order
+///       ``(latitude, longitude)``, and
+///     - ``durations``: of dimension ``(k)``, in the unit of ``times``.
+///
+/// Example
+/// -------
+/// Stops of at least 5 minutes within 200 metres::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> stops = trajectory.detect_stops(track, times, 0.2, 300.)
+fn detect_stops(
+    s: &PyArray<f64, Ix2>,
+    times: &PyArray<f64, Ix1>,
+    radius: f64,
+    min_duration: f64,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let stops = super::detect_stops(
+        method.unwrap_or(method_default),
+        &s.to_owned_array(),
+        &times.to_owned_array(),
+        radius,
+        min_duration,
+        settings,
+    )?;
+
+    let centroids = F64LatLngArray::from_shape_vec(
+        (stops.len(), 2),
+        stops.iter().flat_map(| stop | [stop.centroid.0, stop.centroid.1]).collect(),
+    ).unwrap();
+
+    let dict = PyDict::new(py);
+
+    dict.set_item("start", Array1::from_iter(stops.iter().map(| stop | stop.start)).to_pyarray(py))?;
+    dict.set_item("end", Array1::from_iter(stops.iter().map(| stop | stop.end)).to_pyarray(py))?;
+    dict.set_item("centroids", centroids.to_pyarray(py))?;
+    dict.set_item("durations", Array1::from_iter(stops.iter().map(| stop | stop.duration)).to_pyarray(py))?;
+
+    return Ok(dict.into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(a, b, method, *, settings)")]
+/// Dynamic time warping distance between two tracks.
+///
+/// The smallest sum of point-to-point distances over any alignment of ``a`` and
+/// ``b`` that keeps both in order, with each point matched at least once.
+///
+/// Parameters
+/// ----------
+/// a: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// b: numpy.ndarray
+///     Of dimension ``(m, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.float64
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// Example
+/// -------
+/// Compare two tracks::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> trajectory.dtw(track_a, track_b)
+fn dtw(
+    a: &PyArray<f64, Ix2>,
+    b: &PyArray<f64, Ix2>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<f64> {
+    let method_default = &enums::CalculationMethod::default();
+
+    return Ok(super::dtw(
+        method.unwrap_or(method_default),
+        &a.to_owned_array(),
+        &b.to_owned_array(),
+        settings,
+    )?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(a, b, method, *, settings)")]
+/// Discrete Fréchet distance between two tracks.
+///
+/// The shortest leash with which ``a`` and ``b`` can both be walked from start to
+/// end, only ever stepping forwards from point to point.
+///
+/// Parameters
+/// ----------
+/// a: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// b: numpy.ndarray
+///     Of dimension ``(m, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.float64
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// Example
+/// -------
+/// Compare two tracks::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> trajectory.frechet(track_a, track_b)
+fn frechet(
+    a: &PyArray<f64, Ix2>,
+    b: &PyArray<f64, Ix2>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<f64> {
+    let method_default = &enums::CalculationMethod::default();
+
+    return Ok(super::frechet(
+        method.unwrap_or(method_default),
+        &a.to_owned_array(),
+        &b.to_owned_array(),
+        settings,
+    )?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(a, b, method, *, settings)")]
+/// Hausdorff distance between two tracks.
+///
+/// The furthest any point of either ``a`` or ``b`` is from its nearest point in the
+/// other; the order of the points is ignored.
+///
+/// Parameters
+/// ----------
+/// a: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// b: numpy.ndarray
+///     Of dimension ``(m, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.float64
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// Example
+/// -------
+/// Compare two tracks::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> trajectory.hausdorff(track_a, track_b)
+fn hausdorff(
+    a: &PyArray<f64, Ix2>,
+    b: &PyArray<f64, Ix2>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+) -> PyResult<f64> {
+    let method_default = &enums::CalculationMethod::default();
+
+    return Ok(super::hausdorff(
+        method.unwrap_or(method_default),
+        &a.to_owned_array(),
+        &b.to_owned_array(),
+        settings,
+    )?);
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, step, method, *, settings)")]
+/// Resample a track at regular intervals of distance along it.
+///
+/// New points are placed along the geodesic of each segment rather than
+/// interpolated in degrees. The first point is kept; any remainder at the end of
+/// the track shorter than ``step`` is dropped.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// step: numpy.float64
+///     Distance between consecutive resampled points.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, positions)``: ``points`` is of dimension ``(k, 2)``, and
+///     ``positions`` of dimension ``(k)`` the position of each in ``s`` as a
+///     fractional index, so that any per-point metadata can be interpolated.
+///
+/// Example
+/// -------
+/// Resample a track every 50 metres, with its timestamps::
+///
+///     >>> import numpy as np
+///     >>> from rust_geodistances import trajectory
+///     >>> points, positions = trajectory.resample_by_distance(track, 0.05)
+///     >>> times = np.interp(positions, np.arange(len(track)), times)
+fn resample_by_distance(
+    s: &PyArray<f64, Ix2>,
+    step: f64,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let (points, positions) = super::resample_by_distance(
+        method.unwrap_or(method_default),
+        &s.to_owned_array(),
+        step,
+        settings,
+    )?;
+
+    return Ok((points.to_pyarray(py), positions.to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, times, period, method, *, settings)")]
+/// Resample a track at regular intervals of time.
+///
+/// Speed is assumed constant along each segment, and new points are placed along
+/// its geodesic. Intervals start from ``times[0]``; any remainder at the end of
+/// the track shorter than ``period`` is dropped.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// times: numpy.ndarray
+///     Of dimension ``(n)``, the timestamp of each point; must be non-decreasing.
+///
+/// period: numpy.float64
+///     Time between consecutive resampled points, in the unit of ``times``.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, times)``: ``points`` is of dimension ``(k, 2)``, and ``times``
+///     of dimension ``(k)`` the timestamp of each.
+///
+/// Example
+/// -------
+/// Resample a track to one point per second::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> points, times = trajectory.resample_by_time(track, times, 1.)
+fn resample_by_time(
+    s: &PyArray<f64, Ix2>,
+    times: &PyArray<f64, Ix1>,
+    period: f64,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+
+    let (points, times) = super::resample_by_time(
+        method.unwrap_or(method_default),
+        &s.to_owned_array(),
+        &times.to_owned_array(),
+        period,
+        settings,
+    )?;
+
+    return Ok((points.to_pyarray(py), times.to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, tolerance, algorithm, method, *, settings)")]
+/// Simplify a polyline, with a tolerance in true distance rather than degrees.
+///
+/// The deviation of each vertex is its cross-track distance from the simplified
+/// line, so the same tolerance applies at all latitudes. The first and last
+/// vertices are always kept.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///
+/// tolerance: numpy.float64
+///     Maximum deviation of the removed vertices.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// algorithm: Optional[str]
+///     Either:
+///
+///     - ``"douglas_peucker"`` (default): keep the vertex furthest from the line
+///       between the ends of each span while it is further than ``tolerance``,
+///       or
+///     - ``"visvalingam"``: remove the vertex forming the smallest triangle with
+///       its neighbours, of those within ``tolerance`` of the line between their
+///       neighbours at the time.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray]
+///     ``(points, indices)``: ``points`` is the simplified polyline of dimension
+///     ``(k, 2)``, and ``indices`` of dimension ``(k)`` the index of each of its
+///     vertices in ``s``, so that any per-vertex metadata can follow.
+///
+/// Example
+/// -------
+/// Simplify a track to within 10 metres::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> points, indices = trajectory.simplify(track, 0.01)
+///     >>> times = times[indices]
+fn simplify(
+    s: &PyArray<f64, Ix2>,
+    tolerance: f64,
+    algorithm: Option<&str>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let algorithm: Algorithm = match algorithm {
+        Some(algorithm) => algorithm.parse().map_err(PyValueError::new_err)?,
+        None => Algorithm::default(),
+    };
+
+    let (points, indices) = super::simplify(
+        method.unwrap_or(method_default),
+        &s.to_owned_array(),
+        tolerance,
+        algorithm,
+        settings,
+    );
+
+    return Ok((points.to_pyarray(py), Array1::from_vec(indices).to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(tracks, metric, method, *, settings)")]
+/// Distances between all pairs of a list of tracks.
+///
+/// Each pair is calculated once, with the pairs spread over
+/// :attr:`CalculationSettings.workers` threads.
+///
+/// Parameters
+/// ----------
+/// tracks: List[numpy.ndarray]
+///     Each of dimension ``(n, 2)``, in degrees. In order
+///     ``(latitude, longitude)``.
+///
+/// metric: Optional[str]
+///     One of ``"hausdorff"`` (default), ``"frechet"`` or ``"dtw"``; see
+///     :func:`hausdorff`, :func:`frechet` and :func:`dtw`.
+///
+/// method: Optional[CalculationMethod]
+///     Calculation method for the distances. Defaults to
+///     :attr:`CalculationMethod.HAVERSINE`.
+///
+/// settings: CalculationSettings
+///     Settings to be passed on to the calculation method.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(t, t)``, symmetric with a zero diagonal.
+///     The unit of this must be the same as that of:
+///
+///     - :attr:`CalculationSettings.spherical_radius` or
+///     - :attr:`CalculationSettings.ellipse_a` and
+///     - :attr:`CalculationSettings.ellipse_b` and
+///     - :attr:`CalculationSettings.ellipse_f`
+///
+///     whichever used by in ``method``.
+///
+/// Example
+/// -------
+/// Fréchet distances among routes, for clustering::
+///
+///     >>> from rust_geodistances import trajectory
+///     >>> d = trajectory.track_distances(routes, "frechet")
+fn track_distances(
+    tracks: Vec<&PyArray<f64, Ix2>>,
+    metric: Option<&str>,
+    method: Option<&enums::CalculationMethod>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let method_default = &enums::CalculationMethod::default();
+    let metric: Metric = match metric {
+        Some(metric) => metric.parse().map_err(PyValueError::new_err)?,
+        None => Metric::default(),
+    };
+
+    let tracks: Vec<F64LatLngArray> = tracks.iter().map(| track | track.to_owned_array()).collect();
+
+    return Ok(
+        super::track_distances(
+            method.unwrap_or(method_default),
+            &tracks,
+            metric,
+            settings,
+        )?
+        .to_pyarray(py)
+        .into_py(py)
+    );
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(analyse, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_stops, m)?)?;
+    m.add_function(wrap_pyfunction!(dtw, m)?)?;
+    m.add_function(wrap_pyfunction!(frechet, m)?)?;
+    m.add_function(wrap_pyfunction!(hausdorff, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_by_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_by_time, m)?)?;
+    m.add_function(wrap_pyfunction!(simplify, m)?)?;
+    m.add_function(wrap_pyfunction!(track_distances, m)?)?;
+
+    Ok(())
+}
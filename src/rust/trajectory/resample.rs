@@ -0,0 +1,157 @@
+/// Resampling of tracks at regular intervals of distance or time.
+///
+/// New points are placed along the geodesic of each segment by solving the direct
+/// problem from its start with :meth:`CalculationInterfaceInternal::_displace`,
+/// rather than interpolating in degrees. Intervals are regular from the first
+/// point; any remainder at the end of the track shorter than one interval is
+/// dropped.
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+use super::{
+    check_times,
+    cumulative_lengths,
+    segments,
+    TrajectoryError,
+};
+
+/// Check that an interval is positive and finite.
+fn check_interval(name:&str, interval:f64) -> Result<(), TrajectoryError> {
+    if !(interval > 0. && interval.is_finite()) {
+        return Err(TrajectoryError::Parameter(
+            format!("`{}` must be positive, yet {:?} provided.", name, interval)
+        ));
+    }
+
+    return Ok(());
+}
+
+/// Points at ``offsets`` along the segments ``indices`` of a track.
+fn interpolate(
+    method:&CalculationMethod,
+    s:&F64LatLngArray,
+    headings:&F64Array1,
+    indices:&[usize],
+    offsets:F64Array1,
+    settings: Option<&CalculationSettings>,
+) -> F64LatLngArray {
+    let origins = s.select(Axis(0), indices);
+    let bearings = headings.select(Axis(0), indices);
+
+    return CalculationInterfaceInternal::<&F64Array1>::_displace(method, &origins, &offsets, &bearings, settings);
+}
+
+/// Resample a track at every ``step`` of distance along it.
+///
+/// Returns a tuple of the new points, and the position of each in the original
+/// track as a fractional index, i.e. ``i + f`` for the fraction ``f`` of the
+/// length of segment ``i``; per-point values such as timestamps can be
+/// interpolated at these positions.
+pub fn resample_by_distance(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    step:f64,
+    settings: Option<&CalculationSettings>,
+) -> Result<(F64LatLngArray, F64Array1), TrajectoryError> {
+    check_interval("step", step)?;
+
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+    if n < 2 {
+        return Ok((s_owned, F64Array1::zeros(n)));
+    }
+
+    let (lengths, headings) = segments(method, &s_owned, settings);
+    let cumulative = cumulative_lengths(&lengths).to_vec();
+
+    let count = (cumulative[n - 1] / step).floor() as usize + 1;
+    let (indices, offsets): (Vec<usize>, Vec<f64>) = {
+        (0..count).map(
+                      | k | {
+                          let target = k as f64 * step;
+                          let i = (cumulative.partition_point(| c | *c <= target) - 1).min(n - 2);
+
+                          (i, target - cumulative[i])
+                      }
+                  )
+                  .unzip()
+    };
+
+    let positions = indices.iter()
+                           .zip(offsets.iter())
+                           .map(
+                               | (i, offset) | *i as f64 + if lengths[*i] > 0. { offset / lengths[*i] } else { 0. }
+                           )
+                           .collect();
+
+    return Ok((
+        interpolate(method, &s_owned, &headings, &indices, F64Array1::from_vec(offsets), settings),
+        positions,
+    ));
+}
+
+/// Resample a track at every ``period`` of time, assuming constant speed along
+/// each segment.
+///
+/// ``times`` must be non-decreasing. Returns a tuple of the new points and their
+/// timestamps.
+pub fn resample_by_time(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    times:&F64Array1,
+    period:f64,
+    settings: Option<&CalculationSettings>,
+) -> Result<(F64LatLngArray, F64Array1), TrajectoryError> {
+    check_interval("period", period)?;
+
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+    check_times(n, times)?;
+
+    if let Some(i) = (1..n).find(| i | !(times[*i] >= times[*i - 1])) {
+        return Err(TrajectoryError::Times(
+            format!("timestamps must be non-decreasing, yet {:?} follows {:?}.", times[i], times[i - 1])
+        ));
+    }
+    if n < 2 {
+        return Ok((s_owned, times.clone()));
+    }
+
+    let (lengths, headings) = segments(method, &s_owned, settings);
+    let times_vec = times.to_vec();
+
+    let count = ((times[n - 1] - times[0]) / period).floor() as usize + 1;
+    let targets: Vec<f64> = (0..count).map(| k | times[0] + k as f64 * period).collect();
+
+    let (indices, offsets): (Vec<usize>, Vec<f64>) = {
+        targets.iter()
+               .map(
+                   | target | {
+                       let i = (times_vec.partition_point(| t | t <= target) - 1).min(n - 2);
+                       let duration = times[i + 1] - times[i];
+                       let fraction = if duration > 0. { ((target - times[i]) / duration).min(1.) } else { 0. };
+
+                       (i, fraction * lengths[i])
+                   }
+               )
+               .unzip()
+    };
+
+    return Ok((
+        interpolate(method, &s_owned, &headings, &indices, F64Array1::from_vec(offsets), settings),
+        F64Array1::from_vec(targets),
+    ));
+}
@@ -0,0 +1,226 @@
+/// Distances between whole tracks.
+///
+/// Each metric is derived from the distances between the points of two tracks,
+/// calculated from one point of the first track at a time by
+/// :meth:`CalculationInterfaceInternal::_distance_from_point`; the full matrix of
+/// distances is never held in memory:
+///
+/// - Hausdorff: the furthest any point of either track is from the other track,
+///   ignoring order,
+/// - discrete Fréchet: the shortest leash with which both tracks can be walked in
+///   order, and
+/// - dynamic time warping: the smallest sum of distances over any monotonic
+///   alignment of the points of both tracks.
+///
+/// All three are symmetric, so :func:`track_distances` calculates each pair once.
+
+use std::cmp;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+
+use crate::compatibility::{
+    CalculationInterfaceInternal,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+use super::TrajectoryError;
+
+/// Metric of the distance between two tracks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Hausdorff,
+    Frechet,
+    Dtw,
+}
+impl Default for Metric {
+    fn default() -> Self { Self::Hausdorff }
+}
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "hausdorff" => Ok(Self::Hausdorff),
+            "frechet" => Ok(Self::Frechet),
+            "dtw" => Ok(Self::Dtw),
+            other => Err(
+                format!("Unknown metric {:?}; expected \"hausdorff\", \"frechet\" or \"dtw\".", other)
+            ),
+        };
+    }
+}
+impl Metric {
+    /// Apply this metric between two tracks.
+    fn between(
+        &self,
+        method:&CalculationMethod,
+        a:&F64LatLngArray,
+        b:&F64LatLngArray,
+        settings: Option<&CalculationSettings>,
+    ) -> f64 {
+        let m = b.shape()[0];
+        let rows = a.axis_iter(Axis(0)).map(
+            | p | CalculationInterfaceInternal::<f64>::_distance_from_point(method, &p, b, settings)
+        );
+
+        return match self {
+            Self::Hausdorff => hausdorff_from_rows(rows, m),
+            Self::Frechet => coupling_from_rows(rows, m, f64::max),
+            Self::Dtw => coupling_from_rows(rows, m, | cost, step | cost + step),
+        };
+    }
+}
+
+/// Larger of the directed Hausdorff distances from each side, given the rows of
+/// distances from each point of one track to the ``m`` points of the other.
+///
+/// Only the closest distance to each of the ``m`` points is kept, in ``O(m)``
+/// memory.
+fn hausdorff_from_rows<I>(rows:I, m:usize) -> f64
+where I: Iterator<Item=F64Array1>
+{
+    let mut a_to_b: f64 = 0.;
+    let mut b_to_a: Vec<f64> = vec![f64::INFINITY; m];
+
+    for row in rows {
+        a_to_b = a_to_b.max(row.fold(f64::INFINITY, | a, b | a.min(*b)));
+
+        for (closest, d) in b_to_a.iter_mut().zip(row.iter()) {
+            *closest = closest.min(*d);
+        }
+    }
+
+    return b_to_a.into_iter().fold(a_to_b, f64::max);
+}
+
+/// Cost of the cheapest monotonic coupling of both tracks, given the rows of
+/// distances from each point of one track to the ``m`` points of the other, where
+/// ``combine`` accumulates the cost of a step onto the cost of the path so far.
+///
+/// Only the previous row of the table is kept, in ``O(m)`` memory.
+fn coupling_from_rows<I, F>(rows:I, m:usize, combine:F) -> f64
+where I: Iterator<Item=F64Array1>, F: Fn(f64, f64) -> f64
+{
+    let mut prev: Vec<f64> = vec![f64::INFINITY; m];
+    let mut curr: Vec<f64> = vec![f64::INFINITY; m];
+
+    for (i, row) in rows.enumerate() {
+        for j in 0..m {
+            let cheapest = match (i, j) {
+                (0, 0) => { curr[0] = row[0]; continue },
+                (0, _) => curr[j - 1],
+                (_, 0) => prev[0],
+                _ => prev[j].min(prev[j - 1]).min(curr[j - 1]),
+            };
+            curr[j] = combine(cheapest, row[j]);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    return prev[m - 1];
+}
+
+/// Check that neither track is empty.
+fn check_tracks(a:&dyn LatLngArray, b:&dyn LatLngArray) -> Result<(), TrajectoryError> {
+    if a.shape()[0] == 0 || b.shape()[0] == 0 {
+        return Err(TrajectoryError::Shape("tracks must have at least 1 point.".to_string()));
+    }
+
+    return Ok(());
+}
+
+/// Distance between two tracks by ``metric``.
+pub fn track_distance(
+    method:&CalculationMethod,
+    a:&dyn LatLngArray,
+    b:&dyn LatLngArray,
+    metric:Metric,
+    settings: Option<&CalculationSettings>,
+) -> Result<f64, TrajectoryError> {
+    check_tracks(a, b)?;
+
+    return Ok(metric.between(method, &a.to_owned(), &b.to_owned(), settings));
+}
+
+/// Hausdorff distance between two tracks.
+pub fn hausdorff(
+    method:&CalculationMethod,
+    a:&dyn LatLngArray,
+    b:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> Result<f64, TrajectoryError> {
+    return track_distance(method, a, b, Metric::Hausdorff, settings);
+}
+
+/// Discrete Fréchet distance between two tracks.
+pub fn frechet(
+    method:&CalculationMethod,
+    a:&dyn LatLngArray,
+    b:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> Result<f64, TrajectoryError> {
+    return track_distance(method, a, b, Metric::Frechet, settings);
+}
+
+/// Dynamic time warping distance between two tracks.
+pub fn dtw(
+    method:&CalculationMethod,
+    a:&dyn LatLngArray,
+    b:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> Result<f64, TrajectoryError> {
+    return track_distance(method, a, b, Metric::Dtw, settings);
+}
+
+/// Distances by ``metric`` between all pairs of ``tracks``, of dimension
+/// ``(t, t)``.
+///
+/// Each pair is calculated once, with the pairs spread over
+/// :attr:`CalculationSettings.workers` threads.
+pub fn track_distances(
+    method:&CalculationMethod,
+    tracks:&[F64LatLngArray],
+    metric:Metric,
+    settings: Option<&CalculationSettings>,
+) -> Result<F64Array2, TrajectoryError> {
+    let t = tracks.len();
+    if let Some(i) = tracks.iter().position(| track | track.shape()[0] == 0) {
+        return Err(TrajectoryError::Shape(format!("track {:?} must have at least 1 point.", i)));
+    }
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+
+    let pairs: Vec<(usize, usize)> = (0..t).flat_map(| i | (i + 1..t).map(move | j | (i, j))).collect();
+    let chunk_size: usize = cmp::max((pairs.len() as f32 / workers as f32).ceil() as usize, 1);
+
+    let values: Vec<f64> = {
+        pairs.par_iter()
+             .with_min_len(chunk_size)
+             .map(
+                 | (i, j) | metric.between(method, &tracks[*i], &tracks[*j], settings)
+             )
+             .collect()
+    };
+
+    let mut result = F64Array2::zeros((t, t));
+    for ((i, j), value) in pairs.into_iter().zip(values) {
+        result[[i, j]] = value;
+        result[[j, i]] = value;
+    }
+
+    return Ok(result);
+}
@@ -0,0 +1,210 @@
+/// Polyline simplification with a geodesic tolerance.
+///
+/// Both algorithms measure how far each vertex deviates from the simplified line
+/// by :func:`distance_to_segment`, i.e. as a true distance in the unit of the
+/// calculation method rather than in degrees; this keeps the same tolerance at all
+/// latitudes. The first and last vertices are always kept.
+
+use std::cmp::{
+    self,
+    Ordering,
+    Reverse,
+};
+use std::collections::BinaryHeap;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::F64LatLngArray;
+
+use crate::calc_models::traits::LatLngArray;
+
+use crate::compatibility::{
+    CalculationInterfacePairwise,
+    CalculationMethod,
+    CalculationSettings,
+};
+
+use super::distance_to_segment;
+
+/// Simplification algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Ramer-Douglas-Peucker: recursively keep the vertex furthest from the line
+    /// between the ends of each span, while it is further than the tolerance.
+    DouglasPeucker,
+
+    /// Visvalingam-Whyatt: repeatedly remove the vertex forming the smallest
+    /// triangle with its neighbours, of those within the tolerance of the line
+    /// between their neighbours.
+    ///
+    /// Unlike Douglas-Peucker, each vertex is only checked against the line at
+    /// the time it is removed; later removals may move the line further away.
+    Visvalingam,
+}
+impl Default for Algorithm {
+    fn default() -> Self { Self::DouglasPeucker }
+}
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s:&str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "douglas_peucker" | "dp" => Ok(Self::DouglasPeucker),
+            "visvalingam" | "vw" => Ok(Self::Visvalingam),
+            other => Err(
+                format!("Unknown algorithm {:?}; expected \"douglas_peucker\" or \"visvalingam\".", other)
+            ),
+        };
+    }
+}
+
+/// Ramer-Douglas-Peucker; returns the sorted indices of the vertices kept.
+fn douglas_peucker(
+    method:&CalculationMethod,
+    s:&F64LatLngArray,
+    tolerance:f64,
+    settings: Option<&CalculationSettings>,
+) -> Vec<usize> {
+    let n = s.shape()[0];
+    let point = | i:usize | (s[[i, 0]], s[[i, 1]]);
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+
+    let mut keep = vec![false; n];
+    keep[0] = true;
+    keep[n - 1] = true;
+
+    let mut spans = vec![(0, n - 1)];
+    while let Some((first, last)) = spans.pop() {
+        if last <= first + 1 { continue }
+
+        let chunk_size: usize = cmp::max(((last - first - 1) as f32 / workers as f32).ceil() as usize, 1);
+        let (furthest, distance) = {
+            (first + 1..last)
+            .into_par_iter()
+            .with_min_len(chunk_size)
+            .map(| i | (i, distance_to_segment(method, point(first), point(last), point(i), settings).0))
+            .reduce(
+                || (first, f64::NEG_INFINITY),
+                | a, b | if b.1 > a.1 || (b.1 == a.1 && b.0 < a.0) { b } else { a },
+            )
+        };
+
+        if distance > tolerance {
+            keep[furthest] = true;
+            spans.push((first, furthest));
+            spans.push((furthest, last));
+        }
+    }
+
+    return (0..n).filter(| i | keep[*i]).collect();
+}
+
+/// Area of a vertex in a heap; ordered by area, then by index for determinism.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Significance {
+    area: f64,
+    index: usize,
+}
+impl Eq for Significance {}
+impl PartialOrd for Significance {
+    fn partial_cmp(&self, other:&Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+impl Ord for Significance {
+    fn cmp(&self, other:&Self) -> Ordering {
+        return self.area.total_cmp(&other.area).then(self.index.cmp(&other.index));
+    }
+}
+
+/// Visvalingam-Whyatt; returns the sorted indices of the vertices kept.
+fn visvalingam(
+    method:&CalculationMethod,
+    s:&F64LatLngArray,
+    tolerance:f64,
+    settings: Option<&CalculationSettings>,
+) -> Vec<usize> {
+    let n = s.shape()[0];
+    let point = | i:usize | (s[[i, 0]], s[[i, 1]]);
+
+    // Area of the triangle of vertex `i` and its neighbours, as half its base times
+    // its height; infinite if the height is over the tolerance, so that the vertex
+    // is not removed.
+    let area = | prev:usize, i:usize, next:usize | {
+        let height = distance_to_segment(method, point(prev), point(next), point(i), settings).0;
+        if height > tolerance { return f64::INFINITY }
+
+        let base = method._inverse(s[[prev, 0]], s[[prev, 1]], s[[next, 0]], s[[next, 1]], settings).0;
+
+        base * height / 2.
+    };
+
+    let mut prev: Vec<usize> = (0..n).map(| i | i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(| i | i + 1).collect();
+    let mut areas: Vec<f64> = {
+        (0..n).into_par_iter()
+              .map(| i | if i == 0 || i == n - 1 { f64::INFINITY } else { area(i - 1, i, i + 1) })
+              .collect()
+    };
+
+    let mut heap: BinaryHeap<Reverse<Significance>> = {
+        (1..n - 1).map(| index | Reverse(Significance { area: areas[index], index }))
+                  .collect()
+    };
+
+    let mut keep = vec![true; n];
+
+    while let Some(Reverse(Significance { area: smallest, index })) = heap.pop() {
+        // Stale entry of a vertex that has since been recalculated or removed.
+        if !keep[index] || smallest != areas[index] { continue }
+        if smallest.is_infinite() { break }
+
+        keep[index] = false;
+        let (p, q) = (prev[index], next[index]);
+        next[p] = q;
+        prev[q] = p;
+
+        // The removed area carries over, so that a neighbour is never less
+        // significant than the vertices already removed around it.
+        for neighbour in [p, q] {
+            if neighbour == 0 || neighbour == n - 1 { continue }
+
+            areas[neighbour] = area(prev[neighbour], neighbour, next[neighbour]).max(smallest);
+            heap.push(Reverse(Significance { area: areas[neighbour], index: neighbour }));
+        }
+    }
+
+    return (0..n).filter(| i | keep[*i]).collect();
+}
+
+/// Simplify a polyline of dimension ``(n, 2)``.
+///
+/// Returns a tuple of the simplified polyline, and the indices of its vertices in
+/// ``s``, in order.
+pub fn simplify(
+    method:&CalculationMethod,
+    s:&dyn LatLngArray,
+    tolerance:f64,
+    algorithm:Algorithm,
+    settings: Option<&CalculationSettings>,
+) -> (F64LatLngArray, Vec<usize>) {
+    let s_owned = s.to_owned();
+    let n = s_owned.shape()[0];
+
+    let indices = if n < 3 {
+        (0..n).collect()
+    } else {
+        match algorithm {
+            Algorithm::DouglasPeucker => douglas_peucker(method, &s_owned, tolerance, settings),
+            Algorithm::Visvalingam => visvalingam(method, &s_owned, tolerance, settings),
+        }
+    };
+
+    return (s_owned.select(Axis(0), &indices), indices);
+}
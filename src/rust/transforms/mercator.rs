@@ -0,0 +1,77 @@
+/// Web Mercator projection, as used by web map tiles (EPSG:3857).
+///
+/// Latitudes and longitudes on the ellipsoid are projected as if they were on a
+/// sphere of radius :attr:`CalculationSettings.ellipse_a`; projected coordinates
+/// ``(x, y)`` are in the same unit.
+
+use std::f64::consts::PI;
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+use crate::compatibility::CalculationSettings;
+
+/// Latitude at which the projection becomes square, beyond which latitudes are
+/// clamped.
+pub const MAX_LATITUDE:f64 = 85.05112877980659;
+
+fn radius(settings: Option<&CalculationSettings>) -> f64 {
+    return settings.unwrap_or(&CalculationSettings::default()).ellipse_a;
+}
+
+/// Project a single point.
+pub fn forward(lat:f64, lng:f64, radius:f64) -> [f64; 2] {
+    let lat_r = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+
+    return [radius * lng.to_radians(), radius * (PI / 4. + lat_r / 2.).tan().ln()];
+}
+
+/// Unproject a single point.
+pub fn inverse(x:f64, y:f64, radius:f64) -> [f64; 2] {
+    return [(2. * (y / radius).exp().atan() - PI / 2.).to_degrees(), (x / radius).to_degrees()];
+}
+
+/// Project lat-long coordinates into Web Mercator ``(x, y)``.
+///
+/// Latitudes beyond ±85.0511º are clamped.
+pub fn to_web_mercator(
+    s:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> F64Array2 {
+    let radius = radius(settings);
+    let s_owned = s.to_owned();
+
+    let flat: Vec<f64> = {
+        s_owned.axis_iter(Axis(0))
+               .into_par_iter()
+               .flat_map_iter(| row | forward(row[0], row[1], radius))
+               .collect()
+    };
+
+    return F64Array2::from_shape_vec((s_owned.shape()[0], 2), flat).unwrap();
+}
+
+/// Unproject Web Mercator ``(x, y)`` of dimension ``(n, 2)`` into lat-long
+/// coordinates.
+pub fn from_web_mercator(
+    xy:&F64Array2,
+    settings: Option<&CalculationSettings>,
+) -> F64LatLngArray {
+    let radius = radius(settings);
+
+    let flat: Vec<f64> = {
+        xy.axis_iter(Axis(0))
+          .into_par_iter()
+          .flat_map_iter(| row | inverse(row[0], row[1], radius))
+          .collect()
+    };
+
+    return F64LatLngArray::from_shape_vec((xy.shape()[0], 2), flat).unwrap();
+}
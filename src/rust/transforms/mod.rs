@@ -0,0 +1,310 @@
+/// Conversions between geodetic, Earth-Centred Earth-Fixed (ECEF) and local
+/// East-North-Up (ENU) coordinates, straight line distances, and the UTM and Web
+/// Mercator projections in :mod:`utm` and :mod:`mercator`.
+///
+/// Geodetic coordinates are arrays of dimension ``(n, 2)`` or ``(n, 3)``, in order
+/// ``(latitude, longitude[, height])``, with latitude and longitude in degrees.
+/// Heights are above the ellipsoid, and default to ``0`` if absent.
+///
+/// All heights, ECEF and ENU coordinates and distances are in the same unit as
+/// :attr:`CalculationSettings.ellipse_a`, i.e. kilometres by default; the
+/// ellipsoid is defined by :attr:`CalculationSettings.ellipse_a` and
+/// :attr:`CalculationSettings.ellipse_b`.
+
+use std::cmp;
+use std::fmt;
+
+use ndarray::{
+    Array2,
+    ArrayView1,
+    Axis,
+};
+use rayon::prelude::*;
+
+use ndarray_numeric::{
+    F64Array1,
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::traits::LatLngArray;
+use crate::compatibility::CalculationSettings;
+
+pub mod mercator;
+pub mod utm;
+
+// Import this if you want the transforms module to be available in Python.
+pub mod python;
+
+pub use mercator::{
+    from_web_mercator,
+    to_web_mercator,
+};
+pub use utm::{
+    from_utm,
+    to_utm,
+    Zone,
+};
+
+/// Errors raised by projections.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// A coordinate is outside of the area covered by the projection.
+    OutOfRange(String),
+
+    /// A UTM zone number is not between 1 and 60.
+    InvalidZone(u8),
+
+    /// Arrays are of mismatched dimensions.
+    Shape(String),
+}
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Self::OutOfRange(msg) => write!(f, "Out of range: {}", msg),
+            Self::InvalidZone(zone) => write!(f, "UTM zone must be between 1 and 60, yet {:?} provided.", zone),
+            Self::Shape(msg) => write!(f, "Invalid shape: {}", msg),
+        };
+    }
+}
+impl std::error::Error for TransformError {}
+
+/// ECEF or ENU vector.
+pub type Vector3 = [f64; 3];
+
+/// Parameters of the ellipsoid in ``settings``.
+struct Ellipsoid {
+    a: f64,
+    b: f64,
+
+    /// First eccentricity squared.
+    e2: f64,
+}
+impl Ellipsoid {
+    fn new(settings: Option<&CalculationSettings>) -> Self {
+        let settings_default = &CalculationSettings::default();
+        let settings = settings.unwrap_or(settings_default);
+
+        let (a, b) = (settings.ellipse_a, settings.ellipse_b);
+
+        return Self { a, b, e2: 1. - (b / a).powi(2) };
+    }
+
+    /// Prime vertical radius of curvature at a latitude.
+    fn prime_vertical_radius(&self, sin_lat:f64) -> f64 {
+        return self.a / (1. - self.e2 * sin_lat.powi(2)).sqrt();
+    }
+}
+
+/// Latitude, longitude and height of a row, with latitude and longitude in
+/// degrees.
+fn row_to_geodetic(row:ArrayView1<f64>) -> Vector3 {
+    return [row[0], row[1], if row.len() > 2 { row[2] } else { 0. }];
+}
+
+/// Convert a single geodetic coordinate into ECEF.
+fn to_ecef(geodetic:Vector3, ellipsoid:&Ellipsoid) -> Vector3 {
+    let [lat, lng, height] = geodetic;
+    let (lat_r, lng_r) = (lat.to_radians(), lng.to_radians());
+
+    let n = ellipsoid.prime_vertical_radius(lat_r.sin());
+
+    return [
+        (n + height) * lat_r.cos() * lng_r.cos(),
+        (n + height) * lat_r.cos() * lng_r.sin(),
+        (n * (1. - ellipsoid.e2) + height) * lat_r.sin(),
+    ];
+}
+
+/// Convert a single ECEF coordinate into geodetic, iterating until the latitude
+/// converges to within ``tolerance`` radians.
+fn to_geodetic(xyz:Vector3, ellipsoid:&Ellipsoid, tolerance:f64, max_iterations:usize) -> Vector3 {
+    let [x, y, z] = xyz;
+    let p = x.hypot(y);
+
+    let lng_r = y.atan2(x);
+    let mut lat_r = z.atan2(p * (1. - ellipsoid.e2));
+
+    for _ in 0..max_iterations {
+        let n = ellipsoid.prime_vertical_radius(lat_r.sin());
+        let lat_r_next = (z + ellipsoid.e2 * n * lat_r.sin()).atan2(p);
+
+        let converged = (lat_r_next - lat_r).abs() <= tolerance;
+        lat_r = lat_r_next;
+
+        if converged { break }
+    }
+
+    // This form of height is stable near the poles, where `p / cos(lat)` is not.
+    let height = {
+        p * lat_r.cos()
+        + z * lat_r.sin()
+        - ellipsoid.a * (1. - ellipsoid.e2 * lat_r.sin().powi(2)).sqrt()
+    };
+
+    return [lat_r.to_degrees(), lng_r.to_degrees(), height];
+}
+
+/// Rotation from ECEF into the ENU frame at a geodetic origin.
+///
+/// Rows are the East, North and Up unit vectors in ECEF.
+fn enu_rotation(origin:Vector3) -> [Vector3; 3] {
+    let (lat_r, lng_r) = (origin[0].to_radians(), origin[1].to_radians());
+    let (sin_lat, cos_lat, sin_lng, cos_lng) = (lat_r.sin(), lat_r.cos(), lng_r.sin(), lng_r.cos());
+
+    return [
+        [-sin_lng, cos_lng, 0.],
+        [-sin_lat * cos_lng, -sin_lat * sin_lng, cos_lat],
+        [cos_lat * cos_lng, cos_lat * sin_lng, sin_lat],
+    ];
+}
+
+/// Apply a function to each row of an array of dimension ``(n, k)``, returning an
+/// array of dimension ``(n, 3)``.
+fn map_rows<F>(s:&F64Array2, f:F) -> F64Array2
+where F: Fn(ArrayView1<f64>) -> Vector3 + Sync
+{
+    let rows: Vec<Vector3> = {
+        s.axis_iter(Axis(0))
+         .into_par_iter()
+         .map(f)
+         .collect()
+    };
+
+    return Array2::from_shape_vec((rows.len(), 3), rows.into_iter().flatten().collect()).unwrap();
+}
+
+/// Convert geodetic coordinates into ECEF.
+///
+/// Returns an array of dimension ``(n, 3)`` of ``(x, y, z)``.
+pub fn geodetic_to_ecef(
+    s:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> F64Array2 {
+    let ellipsoid = Ellipsoid::new(settings);
+
+    return map_rows(&s.to_owned(), | row | to_ecef(row_to_geodetic(row), &ellipsoid));
+}
+
+/// Convert ECEF coordinates of dimension ``(n, 3)`` into geodetic.
+///
+/// Returns an array of dimension ``(n, 3)`` of ``(latitude, longitude, height)``.
+pub fn ecef_to_geodetic(
+    xyz:&F64Array2,
+    settings: Option<&CalculationSettings>,
+) -> F64LatLngArray {
+    let settings_default = &CalculationSettings::default();
+    let (tolerance, max_iterations) = {
+        let settings = settings.unwrap_or(settings_default);
+        (settings.tolerance, settings.max_iterations)
+    };
+    let ellipsoid = Ellipsoid::new(settings);
+
+    return map_rows(xyz, | row | to_geodetic([row[0], row[1], row[2]], &ellipsoid, tolerance, max_iterations));
+}
+
+/// Convert geodetic coordinates into the local ENU frame at ``origin``.
+///
+/// ``origin`` is of dimension ``(2)`` or ``(3)``. Returns an array of dimension
+/// ``(n, 3)`` of ``(east, north, up)``.
+pub fn geodetic_to_enu(
+    s:&dyn LatLngArray,
+    origin:&F64Array1,
+    settings: Option<&CalculationSettings>,
+) -> F64Array2 {
+    let ellipsoid = Ellipsoid::new(settings);
+
+    let origin = row_to_geodetic(origin.view());
+    let origin_xyz = to_ecef(origin, &ellipsoid);
+    let rotation = enu_rotation(origin);
+
+    return map_rows(
+        &s.to_owned(),
+        | row | {
+            let xyz = to_ecef(row_to_geodetic(row), &ellipsoid);
+            let d = [xyz[0] - origin_xyz[0], xyz[1] - origin_xyz[1], xyz[2] - origin_xyz[2]];
+
+            rotation.map(| axis | axis[0] * d[0] + axis[1] * d[1] + axis[2] * d[2])
+        }
+    );
+}
+
+/// Convert coordinates of dimension ``(n, 3)`` in the local ENU frame at
+/// ``origin`` into geodetic.
+///
+/// Returns an array of dimension ``(n, 3)`` of ``(latitude, longitude, height)``.
+pub fn enu_to_geodetic(
+    enu:&F64Array2,
+    origin:&F64Array1,
+    settings: Option<&CalculationSettings>,
+) -> F64LatLngArray {
+    let ellipsoid = Ellipsoid::new(settings);
+
+    let origin = row_to_geodetic(origin.view());
+    let origin_xyz = to_ecef(origin, &ellipsoid);
+    let rotation = enu_rotation(origin);
+
+    // The rotation is orthonormal; its inverse is its transpose.
+    let xyz = map_rows(
+        enu,
+        | row | [0, 1, 2].map(
+            | k | origin_xyz[k] + rotation[0][k] * row[0] + rotation[1][k] * row[1] + rotation[2][k] * row[2]
+        )
+    );
+
+    return ecef_to_geodetic(&xyz, settings);
+}
+
+/// Straight line distances in 3 dimensions from every point of ``s`` to every point
+/// of ``e``, taking heights into account if present.
+///
+/// Returns an array of dimension ``(n, m)``.
+pub fn slant_distance(
+    s:&dyn LatLngArray,
+    e:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> F64Array2 {
+    let (s_xyz, e_xyz) = (geodetic_to_ecef(s, settings), geodetic_to_ecef(e, settings));
+    let (n, m) = (s_xyz.shape()[0], e_xyz.shape()[0]);
+
+    let workers: usize = settings.unwrap_or(
+        &CalculationSettings::default()
+    ).workers;
+    let chunk_size: usize = cmp::max((n as f32 / workers as f32).ceil() as usize, 1);
+
+    let flat: Vec<f64> = {
+        (0..n)
+        .into_par_iter()
+        .with_min_len(chunk_size)
+        .flat_map_iter(
+            | i | {
+                let (s_xyz, e_xyz) = (&s_xyz, &e_xyz);
+
+                (0..m).map(
+                    move | j | {
+                        (0..3).map(| k | (s_xyz[[i, k]] - e_xyz[[j, k]]).powi(2))
+                              .sum::<f64>()
+                              .sqrt()
+                    }
+                )
+            }
+        )
+        .collect()
+    };
+
+    return F64Array2::from_shape_vec((n, m), flat).unwrap();
+}
+
+/// Straight line distances through the ellipsoid from every point of ``s`` to every
+/// point of ``e`` on its surface, ignoring any heights.
+///
+/// Returns an array of dimension ``(n, m)``.
+pub fn chord_distance(
+    s:&dyn LatLngArray,
+    e:&dyn LatLngArray,
+    settings: Option<&CalculationSettings>,
+) -> F64Array2 {
+    let surface = | s:&dyn LatLngArray | -> F64LatLngArray { s.to_owned().slice(ndarray::s![.., ..2]).to_owned() };
+
+    return slant_distance(&surface(s), &surface(e), settings);
+}
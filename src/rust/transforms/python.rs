@@ -0,0 +1,506 @@
+/// Python compatibility layer for the `transforms` module.
+///
+/// The functions here are registered into a `transforms` submodule of
+/// `lib_rust_geodistances`, accessible in Python as
+/// :mod:`rust_geodistances.transforms`.
+///
+/// All docstrings in this module are also parsed by PyO3 to become the `__doc__`
+/// of each function; thus they need to be sphinx+numpydoc compliant.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+use numpy::ndarray::{
+    Array1,
+    Ix1,
+    Ix2,
+};
+use numpy::{
+    ToPyArray,
+    PyArray,
+};
+
+use crate::calc_models::config;
+
+use super::{
+    TransformError,
+    Zone,
+};
+
+impl From<TransformError> for PyErr {
+    fn from(err: TransformError) -> PyErr {
+        return PyValueError::new_err(err.to_string());
+    }
+}
+
+/// Check that an array is of dimension ``(n, 2)`` or ``(n, 3)``.
+fn check_geodetic(name:&str, s:&PyArray<f64, Ix2>) -> PyResult<()> {
+    return match s.shape()[1] {
+        2 | 3 => Ok(()),
+        k => Err(PyValueError::new_err(
+            format!("`{}` must be of dimension (n, 2) or (n, 3), yet (n, {:?}) provided.", name, k)
+        )),
+    };
+}
+
+/// Check that an array is of dimension ``(n, 3)``.
+fn check_cartesian(name:&str, s:&PyArray<f64, Ix2>) -> PyResult<()> {
+    return match s.shape()[1] {
+        3 => Ok(()),
+        k => Err(PyValueError::new_err(
+            format!("`{}` must be of dimension (n, 3), yet (n, {:?}) provided.", name, k)
+        )),
+    };
+}
+
+/// Check that projected coordinates are of dimension ``(n, 2)``.
+fn check_projected(name:&str, s:&PyArray<f64, Ix2>) -> PyResult<()> {
+    return match s.shape()[1] {
+        2 => Ok(()),
+        k => Err(PyValueError::new_err(
+            format!("`{}` must be of dimension (n, 2), yet (n, {:?}) provided.", name, k)
+        )),
+    };
+}
+
+/// Check that an origin is of dimension ``(2)`` or ``(3)``.
+fn check_origin(origin:&PyArray<f64, Ix1>) -> PyResult<()> {
+    return match origin.len() {
+        2 | 3 => Ok(()),
+        k => Err(PyValueError::new_err(
+            format!("`origin` must be of dimension (2) or (3), yet ({:?}) provided.", k)
+        )),
+    };
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, *, settings)")]
+/// Convert geodetic coordinates into Earth-Centred Earth-Fixed (ECEF) coordinates.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)`` or ``(n, 3)``. In order
+///     ``(latitude, longitude[, height])``, with latitude and longitude in degrees
+///     and height above the ellipsoid. Heights default to ``0``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid, by
+///     :attr:`CalculationSettings.ellipse_a` and
+///     :attr:`CalculationSettings.ellipse_b`.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(x, y, z)``. The unit of heights and
+///     of the returned coordinates is that of
+///     :attr:`CalculationSettings.ellipse_a`.
+///
+/// Example
+/// -------
+/// ::
+///
+///     >>> import numpy as np
+///     >>> from rust_geodistances import transforms
+///     >>> transforms.geodetic_to_ecef(np.array([[0., 0.], [90., 0.]]))
+///     array([[6378.137     ,    0.        ,    0.        ],
+///            [   0.        ,    0.        , 6356.75231425]])
+fn geodetic_to_ecef(
+    s: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_geodetic("s", s)?;
+
+    return Ok(super::geodetic_to_ecef(&s.to_owned_array(), settings).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(xyz, *, settings)")]
+/// Convert Earth-Centred Earth-Fixed (ECEF) coordinates into geodetic coordinates.
+///
+/// Latitude is found iteratively, until it converges to within
+/// :attr:`CalculationSettings.tolerance` radians or after
+/// :attr:`CalculationSettings.max_iterations` iterations.
+///
+/// Parameters
+/// ----------
+/// xyz: numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(x, y, z)``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid, by
+///     :attr:`CalculationSettings.ellipse_a` and
+///     :attr:`CalculationSettings.ellipse_b`.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(latitude, longitude, height)``, with
+///     latitude and longitude in degrees.
+fn ecef_to_geodetic(
+    xyz: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_cartesian("xyz", xyz)?;
+
+    return Ok(super::ecef_to_geodetic(&xyz.to_owned_array(), settings).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, origin, *, settings)")]
+/// Convert geodetic coordinates into a local East-North-Up (ENU) frame.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)`` or ``(n, 3)``. In order
+///     ``(latitude, longitude[, height])``.
+///
+/// origin: numpy.ndarray
+///     Of dimension ``(2)`` or ``(3)``; the origin of the local frame, in order
+///     ``(latitude, longitude[, height])``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(east, north, up)``, in the unit of
+///     :attr:`CalculationSettings.ellipse_a`.
+fn geodetic_to_enu(
+    s: &PyArray<f64, Ix2>,
+    origin: &PyArray<f64, Ix1>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_geodetic("s", s)?;
+    check_origin(origin)?;
+
+    return Ok(
+        super::geodetic_to_enu(&s.to_owned_array(), &origin.to_owned_array(), settings)
+        .to_pyarray(py)
+        .into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(enu, origin, *, settings)")]
+/// Convert coordinates in a local East-North-Up (ENU) frame into geodetic
+/// coordinates.
+///
+/// Parameters
+/// ----------
+/// enu: numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(east, north, up)``.
+///
+/// origin: numpy.ndarray
+///     Of dimension ``(2)`` or ``(3)``; the origin of the local frame, in order
+///     ``(latitude, longitude[, height])``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 3)``, in order ``(latitude, longitude, height)``.
+fn enu_to_geodetic(
+    enu: &PyArray<f64, Ix2>,
+    origin: &PyArray<f64, Ix1>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_cartesian("enu", enu)?;
+    check_origin(origin)?;
+
+    return Ok(
+        super::enu_to_geodetic(&enu.to_owned_array(), &origin.to_owned_array(), settings)
+        .to_pyarray(py)
+        .into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, e, *, settings)")]
+/// Straight line distances in 3 dimensions, e.g. for line-of-sight between points
+/// at altitude.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)`` or ``(n, 3)``. In order
+///     ``(latitude, longitude[, height])``.
+///
+/// e: numpy.ndarray
+///     Of dimension ``(m, 2)`` or ``(m, 3)``. In order
+///     ``(latitude, longitude[, height])``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid. Rows of ``s`` are split across
+///     :attr:`CalculationSettings.workers` threads.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, m)``, in the unit of
+///     :attr:`CalculationSettings.ellipse_a`.
+fn slant_distance(
+    s: &PyArray<f64, Ix2>,
+    e: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_geodetic("s", s)?;
+    check_geodetic("e", e)?;
+
+    return Ok(
+        super::slant_distance(&s.to_owned_array(), &e.to_owned_array(), settings)
+        .to_pyarray(py)
+        .into_py(py)
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, e, *, settings)")]
+/// Straight line distances through the ellipsoid between points on its surface.
+///
+/// Any heights are ignored; see :func:`slant_distance` to include them.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)`` or ``(n, 3)``. In order
+///     ``(latitude, longitude[, height])``.
+///
+/// e: numpy.ndarray
+///     Of dimension ``(m, 2)`` or ``(m, 3)``. In order
+///     ``(latitude, longitude[, height])``.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, m)``, in the unit of
+///     :attr:`CalculationSettings.ellipse_a`.
+fn chord_distance(
+    s: &PyArray<f64, Ix2>,
+    e: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_geodetic("s", s)?;
+    check_geodetic("e", e)?;
+
+    return Ok(
+        super::chord_distance(&s.to_owned_array(), &e.to_owned_array(), settings)
+        .to_pyarray(py)
+        .into_py(py)
+    );
+}
+
+/// Zones from a scalar or a sequence of zone numbers, and hemispheres.
+fn zones_from_py(zone: &PyAny, north: &PyAny) -> PyResult<Vec<Zone>> {
+    let numbers: Vec<u8> = match zone.extract::<u8>() {
+        Ok(number) => vec![number],
+        Err(_) => zone.extract()?,
+    };
+    let north: Vec<bool> = match north.extract::<bool>() {
+        Ok(north) => vec![north; numbers.len()],
+        Err(_) => north.extract()?,
+    };
+
+    let numbers = match (numbers.len(), north.len()) {
+        (1, k) => vec![numbers[0]; k],
+        (k, l) if k == l => numbers,
+        (k, l) => return Err(PyValueError::new_err(
+            format!("`zone` and `north` must be of the same length, yet {:?} and {:?} provided.", k, l)
+        )),
+    };
+
+    return Ok(
+        numbers.into_iter()
+               .zip(north)
+               .map(| (number, north) | Zone::new(number, north))
+               .collect::<Result<_, _>>()?
+    );
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, zone, north, *, settings)")]
+/// Project lat-long coordinates into Universal Transverse Mercator (UTM).
+///
+/// Uses the Krüger series on the ellipsoid defined by
+/// :attr:`CalculationSettings.ellipse_a` and :attr:`CalculationSettings.ellipse_b`.
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///     Latitudes must be between -80º and 84º.
+///
+/// zone: Optional[int]
+///     If provided, all points are projected into this zone, between ``1`` and
+///     ``60``; otherwise each point is projected into its own zone, including the
+///     exceptions around Norway and Svalbard.
+///
+/// north: Optional[bool]
+///     Hemisphere of ``zone``; defaults to that of the first point. Ignored if
+///     ``zone`` is not provided.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid. :attr:`CalculationSettings.ellipse_a`
+///     must be in kilometres or metres.
+///
+/// Returns
+/// -------
+/// Tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]
+///     ``(xy, zone, north)``: ``xy`` is of dimension ``(n, 2)`` in order
+///     ``(easting, northing)``, in the unit of
+///     :attr:`CalculationSettings.ellipse_a`; ``zone`` and ``north`` are of
+///     dimension ``(n)``, the zone number and hemisphere of each point.
+///
+/// Example
+/// -------
+/// ::
+///
+///     >>> import numpy as np
+///     >>> from rust_geodistances import transforms
+///     >>> xy, zone, north = transforms.to_utm(np.array([[51.5072, -0.1276]]))
+///     >>> xy
+///     array([[ 699.33098394, 5710.1420666 ]])
+///     >>> zone, north
+///     (array([30], dtype=uint8), array([ True]))
+fn to_utm(
+    s: &PyArray<f64, Ix2>,
+    zone: Option<u8>,
+    north: Option<bool>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    let s_native = s.to_owned_array();
+
+    let zone = match zone {
+        Some(number) => Some(
+            Zone::new(number, north.unwrap_or(s_native.nrows() == 0 || s_native[[0, 0]] >= 0.))?
+        ),
+        None => None,
+    };
+
+    let (xy, zones) = super::to_utm(&s_native, zone, settings)?;
+
+    let numbers = Array1::from_iter(zones.iter().map(| zone | zone.number));
+    let north = Array1::from_iter(zones.iter().map(| zone | zone.north));
+
+    return Ok((xy.to_pyarray(py), numbers.to_pyarray(py), north.to_pyarray(py)).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(xy, zone, north, *, settings)")]
+/// Unproject Universal Transverse Mercator (UTM) coordinates into lat-long
+/// coordinates.
+///
+/// Parameters
+/// ----------
+/// xy: numpy.ndarray
+///     Of dimension ``(n, 2)``, in order ``(easting, northing)``.
+///
+/// zone: Union[int, numpy.ndarray]
+///     Zone number of all points, or of each point.
+///
+/// north: Union[bool, numpy.ndarray]
+///     Hemisphere of all points, or of each point.
+///
+/// settings: CalculationSettings
+///     Settings defining the ellipsoid.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+fn from_utm(
+    xy: &PyArray<f64, Ix2>,
+    zone: &PyAny,
+    north: &PyAny,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_projected("xy", xy)?;
+    let zones = zones_from_py(zone, north)?;
+
+    return Ok(super::from_utm(&xy.to_owned_array(), &zones, settings)?.to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(s, *, settings)")]
+/// Project lat-long coordinates into Web Mercator (EPSG:3857).
+///
+/// Parameters
+/// ----------
+/// s: numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+///     Latitudes beyond ±85.0511º are clamped.
+///
+/// settings: CalculationSettings
+///     Settings defining the radius of the projection, by
+///     :attr:`CalculationSettings.ellipse_a`.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 2)``, in order ``(x, y)``, in the unit of
+///     :attr:`CalculationSettings.ellipse_a`.
+fn to_web_mercator(
+    s: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    return Ok(super::to_web_mercator(&s.to_owned_array(), settings).to_pyarray(py).into_py(py));
+}
+
+#[pyfunction]
+#[pyo3(text_signature = "(xy, *, settings)")]
+/// Unproject Web Mercator (EPSG:3857) coordinates into lat-long coordinates.
+///
+/// Parameters
+/// ----------
+/// xy: numpy.ndarray
+///     Of dimension ``(n, 2)``, in order ``(x, y)``.
+///
+/// settings: CalculationSettings
+///     Settings defining the radius of the projection, by
+///     :attr:`CalculationSettings.ellipse_a`.
+///
+/// Returns
+/// -------
+/// numpy.ndarray
+///     Of dimension ``(n, 2)``, in degrees. In order ``(latitude, longitude)``.
+fn from_web_mercator(
+    xy: &PyArray<f64, Ix2>,
+    settings: Option<&config::CalculationSettings>,
+    py: Python<'_>,
+) -> PyResult<PyObject> {
+    check_projected("xy", xy)?;
+
+    return Ok(super::from_web_mercator(&xy.to_owned_array(), settings).to_pyarray(py).into_py(py));
+}
+
+/// Add all the functions of this module to a Python module.
+pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(geodetic_to_ecef, m)?)?;
+    m.add_function(wrap_pyfunction!(ecef_to_geodetic, m)?)?;
+    m.add_function(wrap_pyfunction!(geodetic_to_enu, m)?)?;
+    m.add_function(wrap_pyfunction!(enu_to_geodetic, m)?)?;
+    m.add_function(wrap_pyfunction!(slant_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(chord_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(to_utm, m)?)?;
+    m.add_function(wrap_pyfunction!(from_utm, m)?)?;
+    m.add_function(wrap_pyfunction!(to_web_mercator, m)?)?;
+    m.add_function(wrap_pyfunction!(from_web_mercator, m)?)?;
+
+    Ok(())
+}
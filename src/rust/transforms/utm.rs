@@ -0,0 +1,267 @@
+/// Universal Transverse Mercator projection.
+///
+/// Uses the Krüger series to 4th order in the third flattening ``n``, which is
+/// accurate to well within a millimetre across each zone. Projected coordinates
+/// are ``(easting, northing)``, in the same unit as
+/// :attr:`CalculationSettings.ellipse_a`.
+///
+/// The false easting of 500 km and the false northing of 10,000 km south of the
+/// equator are converted into the unit of ``ellipse_a``, which must therefore be
+/// in either kilometres or metres.
+
+use rayon::prelude::*;
+
+use ndarray::Axis;
+
+use ndarray_numeric::{
+    F64Array2,
+    F64LatLngArray,
+};
+
+use crate::calc_models::config::ELLIPSE_WGS84_A;
+use crate::calc_models::traits::LatLngArray;
+use crate::compatibility::CalculationSettings;
+
+use super::TransformError;
+
+/// Scale factor on the central meridian.
+pub const SCALE_FACTOR:f64 = 0.9996;
+
+/// False easting, in kilometres.
+pub const FALSE_EASTING_KM:f64 = 500.;
+
+/// False northing in the southern hemisphere, in kilometres.
+pub const FALSE_NORTHING_KM:f64 = 10000.;
+
+/// Southernmost and northernmost latitudes covered by UTM.
+pub const MIN_LATITUDE:f64 = -80.;
+pub const MAX_LATITUDE:f64 = 84.;
+
+/// A UTM zone and hemisphere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Zone {
+    /// Zone number between 1 and 60.
+    pub number: u8,
+
+    /// ``true`` for the northern hemisphere.
+    pub north: bool,
+}
+impl Zone {
+    /// Check that the zone number is between 1 and 60.
+    pub fn new(number:u8, north:bool) -> Result<Self, TransformError> {
+        return match number {
+            1..=60 => Ok(Self { number, north }),
+            _ => Err(TransformError::InvalidZone(number)),
+        };
+    }
+
+    /// The zone containing a point, including the exceptions around Norway and
+    /// Svalbard.
+    pub fn from_latlng(lat:f64, lng:f64) -> Result<Self, TransformError> {
+        check_latitude(lat)?;
+
+        let lng = (lng + 180.).rem_euclid(360.) - 180.;
+        let mut number = (((lng + 180.) / 6.).floor() as u8 + 1).min(60);
+
+        if (56. ..64.).contains(&lat) && (3. ..12.).contains(&lng) {
+            number = 32;
+        } else if (72. ..=MAX_LATITUDE).contains(&lat) && (0. ..42.).contains(&lng) {
+            number = match lng {
+                lng if lng < 9. => 31,
+                lng if lng < 21. => 33,
+                lng if lng < 33. => 35,
+                _ => 37,
+            };
+        }
+
+        return Ok(Self { number, north: lat >= 0. });
+    }
+
+    /// Longitude of the central meridian in degrees.
+    pub fn central_meridian(&self) -> f64 {
+        return (self.number as f64 - 1.) * 6. - 180. + 3.;
+    }
+}
+
+/// Check that a latitude is within the coverage of UTM.
+fn check_latitude(lat:f64) -> Result<(), TransformError> {
+    if !(MIN_LATITUDE..=MAX_LATITUDE).contains(&lat) {
+        return Err(TransformError::OutOfRange(
+            format!("latitude {:?} is outside of UTM coverage of {:?} to {:?}.", lat, MIN_LATITUDE, MAX_LATITUDE)
+        ));
+    }
+
+    return Ok(());
+}
+
+/// Krüger series coefficients of an ellipsoid.
+struct Kruger {
+    /// First eccentricity.
+    e: f64,
+
+    /// Rectifying radius multiplied by the scale factor.
+    k0_a: f64,
+
+    alpha: [f64; 4],
+    beta: [f64; 4],
+    delta: [f64; 4],
+
+    /// False easting and northing, in the unit of ``ellipse_a``.
+    false_easting: f64,
+    false_northing: f64,
+}
+impl Kruger {
+    fn new(settings: Option<&CalculationSettings>) -> Self {
+        let settings_default = &CalculationSettings::default();
+        let settings = settings.unwrap_or(settings_default);
+
+        let (a, b) = (settings.ellipse_a, settings.ellipse_b);
+        let f = (a - b) / a;
+        let n = f / (2. - f);
+        let (n2, n3, n4) = (n.powi(2), n.powi(3), n.powi(4));
+
+        // Kilometres or metres, to the nearest power of 10.
+        let units_per_km = 10_f64.powf((a / ELLIPSE_WGS84_A).log10().round());
+
+        return Self {
+            e: (f * (2. - f)).sqrt(),
+            k0_a: SCALE_FACTOR * a / (1. + n) * (1. + n2 / 4. + n4 / 64.),
+            alpha: [
+                n / 2. - 2. * n2 / 3. + 5. * n3 / 16. + 41. * n4 / 180.,
+                13. * n2 / 48. - 3. * n3 / 5. + 557. * n4 / 1440.,
+                61. * n3 / 240. - 103. * n4 / 140.,
+                49561. * n4 / 161280.,
+            ],
+            beta: [
+                n / 2. - 2. * n2 / 3. + 37. * n3 / 96. - n4 / 360.,
+                n2 / 48. + n3 / 15. - 437. * n4 / 1440.,
+                17. * n3 / 480. - 37. * n4 / 840.,
+                4397. * n4 / 161280.,
+            ],
+            delta: [
+                2. * n - 2. * n2 / 3. - 2. * n3 + 116. * n4 / 45.,
+                7. * n2 / 3. - 8. * n3 / 5. - 227. * n4 / 45.,
+                56. * n3 / 15. - 136. * n4 / 35.,
+                4279. * n4 / 630.,
+            ],
+            false_easting: FALSE_EASTING_KM * units_per_km,
+            false_northing: FALSE_NORTHING_KM * units_per_km,
+        };
+    }
+
+    /// Project a point into a zone.
+    fn forward(&self, lat:f64, lng:f64, zone:Zone) -> [f64; 2] {
+        let (lat_r, dlng_r) = (lat.to_radians(), (lng - zone.central_meridian()).to_radians());
+
+        // Conformal latitude.
+        let sin_lat = lat_r.sin();
+        let t = (sin_lat.atanh() - self.e * (self.e * sin_lat).atanh()).sinh();
+
+        let xi_p = t.atan2(dlng_r.cos());
+        let eta_p = (dlng_r.sin() / (1. + t.powi(2)).sqrt()).atanh();
+
+        let (mut xi, mut eta) = (xi_p, eta_p);
+        for (j, alpha) in self.alpha.iter().enumerate() {
+            let k = 2. * (j + 1) as f64;
+            xi += alpha * (k * xi_p).sin() * (k * eta_p).cosh();
+            eta += alpha * (k * xi_p).cos() * (k * eta_p).sinh();
+        }
+
+        let false_northing = if zone.north { 0. } else { self.false_northing };
+
+        return [self.false_easting + self.k0_a * eta, false_northing + self.k0_a * xi];
+    }
+
+    /// Unproject a point from a zone.
+    fn inverse(&self, easting:f64, northing:f64, zone:Zone) -> [f64; 2] {
+        let false_northing = if zone.north { 0. } else { self.false_northing };
+
+        let xi = (northing - false_northing) / self.k0_a;
+        let eta = (easting - self.false_easting) / self.k0_a;
+
+        let (mut xi_p, mut eta_p) = (xi, eta);
+        for (j, beta) in self.beta.iter().enumerate() {
+            let k = 2. * (j + 1) as f64;
+            xi_p -= beta * (k * xi).sin() * (k * eta).cosh();
+            eta_p -= beta * (k * xi).cos() * (k * eta).sinh();
+        }
+
+        let chi = (xi_p.sin() / eta_p.cosh()).asin();
+
+        let mut lat_r = chi;
+        for (j, delta) in self.delta.iter().enumerate() {
+            lat_r += delta * (2. * (j + 1) as f64 * chi).sin();
+        }
+
+        let dlng_r = eta_p.sinh().atan2(xi_p.cos());
+
+        return [lat_r.to_degrees(), zone.central_meridian() + dlng_r.to_degrees()];
+    }
+}
+
+/// Project lat-long coordinates into UTM.
+///
+/// If ``zone`` is ``None``, each point is projected into its own zone; otherwise
+/// all points are projected into ``zone``, which allows points straddling zone
+/// boundaries to be handed off together.
+///
+/// Returns a tuple of an array of dimension ``(n, 2)`` of ``(easting, northing)``,
+/// and the zone of each point.
+pub fn to_utm(
+    s:&dyn LatLngArray,
+    zone:Option<Zone>,
+    settings: Option<&CalculationSettings>,
+) -> Result<(F64Array2, Vec<Zone>), TransformError> {
+    let kruger = Kruger::new(settings);
+    let s_owned = s.to_owned();
+
+    let rows: Vec<([f64; 2], Zone)> = {
+        s_owned.axis_iter(Axis(0))
+               .into_par_iter()
+               .map(
+                   | row | -> Result<([f64; 2], Zone), TransformError> {
+                       let (lat, lng) = (row[0], row[1]);
+                       let zone = match zone {
+                           Some(zone) => { check_latitude(lat)?; zone },
+                           None => Zone::from_latlng(lat, lng)?,
+                       };
+
+                       Ok((kruger.forward(lat, lng, zone), zone))
+                   }
+               )
+               .collect::<Result<_, _>>()?
+    };
+
+    let zones = rows.iter().map(| (_, zone) | *zone).collect();
+    let flat = rows.into_iter().flat_map(| (xy, _) | xy).collect::<Vec<f64>>();
+
+    return Ok((F64Array2::from_shape_vec((flat.len() / 2, 2), flat).unwrap(), zones));
+}
+
+/// Unproject UTM ``(easting, northing)`` of dimension ``(n, 2)`` into lat-long
+/// coordinates; ``zones`` is either of length ``1``, shared by all points, or of
+/// length ``n``.
+pub fn from_utm(
+    xy:&F64Array2,
+    zones:&[Zone],
+    settings: Option<&CalculationSettings>,
+) -> Result<F64LatLngArray, TransformError> {
+    let n = xy.shape()[0];
+    if zones.len() != 1 && zones.len() != n {
+        return Err(TransformError::Shape(
+            format!("expected 1 or {:?} zones, yet {:?} provided.", n, zones.len())
+        ));
+    }
+
+    let kruger = Kruger::new(settings);
+
+    let flat: Vec<f64> = {
+        xy.axis_iter(Axis(0))
+          .into_par_iter()
+          .enumerate()
+          .flat_map_iter(| (i, row) | kruger.inverse(row[0], row[1], zones[if zones.len() == 1 { 0 } else { i }]))
+          .collect()
+    };
+
+    return Ok(F64LatLngArray::from_shape_vec((n, 2), flat).unwrap());
+}
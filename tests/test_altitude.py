@@ -0,0 +1,83 @@
+# -*- coding: utf-8 -*-
+import numpy as np
+import pytest
+
+from rust_geodistances import CalculationMethod, CalculationSettings
+
+SETTINGS = CalculationSettings(workers=2)
+
+S = np.array([[51.5072, -0.1276], [40.7128, -74.0060], [-33.8688, 151.2093]])
+E = np.array([[48.8566, 2.3522], [35.6762, 139.6503], [-36.8485, 174.7633]])
+
+S_HEIGHTS = np.array([0.0, 1.5, 0.2])
+E_HEIGHTS = np.array([3.0, 0.5, 0.2])
+
+
+@pytest.mark.parametrize(
+    ["method"],
+    [
+        (CalculationMethod.HAVERSINE,),
+        (CalculationMethod.VINCENTY,),
+    ],
+)
+def test_settings_positional(method: CalculationMethod):
+    """
+    ``settings`` is still the third positional argument of the distance methods.
+    """
+    np.testing.assert_array_equal(
+        method.distance_from_point(S[0], E, SETTINGS),
+        method.distance_from_point(S[0], E, settings=SETTINGS),
+    )
+    np.testing.assert_array_equal(
+        method.distance(S, E, SETTINGS),
+        method.distance(S, E, settings=SETTINGS),
+    )
+    np.testing.assert_array_equal(
+        method.distance_pairwise(S, E, SETTINGS),
+        method.distance_pairwise(S, E, settings=SETTINGS),
+    )
+
+
+@pytest.mark.parametrize(
+    ["method"],
+    [
+        (CalculationMethod.HAVERSINE,),
+        (CalculationMethod.VINCENTY,),
+    ],
+)
+def test_mode(method: CalculationMethod):
+    s = np.column_stack([S, S_HEIGHTS])
+    e = np.column_stack([E, E_HEIGHTS])
+
+    surface = method.distance_pairwise(S, E)
+    climb = np.abs(E_HEIGHTS - S_HEIGHTS)
+
+    np.testing.assert_almost_equal(method.distance_pairwise(s, e), surface)
+    np.testing.assert_almost_equal(
+        method.distance_pairwise(s, e, mode="surface"), surface
+    )
+    np.testing.assert_almost_equal(
+        method.distance_pairwise(s, e, SETTINGS, mode="slant"),
+        np.sqrt(surface**2 + climb**2),
+    )
+    np.testing.assert_almost_equal(
+        method.distance_pairwise(s, e, settings=SETTINGS, mode="surface_climb"),
+        surface + climb,
+    )
+
+    np.testing.assert_almost_equal(
+        method.distance(s, e, mode="surface_climb"),
+        method.distance(S, E) + np.abs(E_HEIGHTS[None, :] - S_HEIGHTS[:, None]),
+    )
+    np.testing.assert_almost_equal(
+        method.distance_from_point(s[0], e, mode="slant"),
+        np.sqrt(
+            method.distance_from_point(S[0], E) ** 2
+            + (E_HEIGHTS - S_HEIGHTS[0]) ** 2
+        ),
+    )
+
+
+def test_mode_unknown():
+    with pytest.raises(ValueError):
+        CalculationMethod.HAVERSINE.distance(S, E, mode="climb")